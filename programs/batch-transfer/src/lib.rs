@@ -1,15 +1,35 @@
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::program_option::COption;
 use anchor_lang::solana_program::program_error::ProgramError;
-use anchor_spl::token::{self, Token, TokenAccount};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_token_2022::extension::{transfer_hook, StateWithExtensions};
+use spl_token_2022::state::AccountState;
+use spl_transfer_hook_interface::get_extra_account_metas_address;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+use spl_type_length_value::state::TlvStateBorrowed;
 
 declare_id!("CRYsXNCjnhR1dgrpd3rEtY8ec7DNKa2659iCaN5tatXK");
 
+// 链下Merkle树构建工具，不参与BPF程序构建
+#[cfg(all(feature = "merkle-builder", not(target_os = "solana")))]
+pub mod merkle;
+
 #[program]
 pub mod batch_transfer {
     use super::*;
 
     /**
      * @notice 初始化合约账户并设置管理员
+     * @dev 只有程序当前的升级权限地址可以初始化，防止部署后被他人抢先初始化。
+     * 若程序已被设为不可升级（没有升级权限），默认拒绝初始化；
+     * 需要在不可升级的部署上初始化时，以 `immutable-init` 特性编译，
+     * 此时退化为仅校验部署者与管理员一致，应在部署的同一批交易中完成初始化。
      * @param ctx 上下文
      * @param admin 管理员地址
      */
@@ -20,18 +40,234 @@ pub mod batch_transfer {
         // 确保部署者就是指定的管理员
         require!(ctx.accounts.deployer.key() == admin, ErrorCode::Unauthorized);
 
+        // 确保部署者是程序的升级权限地址
+        let upgrade_authority = ctx.accounts.program_data.upgrade_authority_address;
+        #[cfg(not(feature = "immutable-init"))]
+        require!(upgrade_authority.is_some(), ErrorCode::ImmutableProgram);
+        if let Some(upgrade_authority) = upgrade_authority {
+            require!(
+                ctx.accounts.deployer.key() == upgrade_authority,
+                ErrorCode::Unauthorized
+            );
+        }
+
         bank_account.admin = admin;
-        bank_account.fee = 0; // 初始手续费设为0
+        bank_account.fee_config = FeeConfig::default(); // 初始手续费设为0
         bank_account.is_initialized = true;
+        bank_account.pending_admin = None;
+        bank_account.paused = false;
+        bank_account.version = BankAccount::VERSION;
+        bank_account.config_delay = DEFAULT_CONFIG_DELAY;
+        bank_account.allow_fee_decrease_bypass = false;
+        bank_account.pending_config = None;
+        Ok(())
+    }
+
+    /**
+     * @notice 提交配置变更(手续费配置、变更延迟和降费是否可跳过延迟)，到达生效时间后通过 execute_config_change 生效
+     * @dev eta 必须不早于 当前时间 + 当前的 config_delay；当前配置开启 allow_fee_decrease_bypass 时，
+     * 只降低手续费且不缩短延迟的新配置不受此限制，可以立即生效。同一时间只能有一个待生效的变更，需先执行或取消
+     * @param ctx 上下文
+     * @param new_config 新配置
+     * @param eta 生效时间(unix时间戳)
+     */
+    pub fn queue_config_change(ctx: Context<ConfigChange>, new_config: BankConfig, eta: i64) -> Result<()> {
+        let bank_account = &mut ctx.accounts.bank_account;
+
+        require!(
+            ctx.accounts.admin.key() == bank_account.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(bank_account.pending_config.is_none(), ErrorCode::ConfigChangePending);
+        validate_fee_config(&new_config.fee_config)?;
+        require!(
+            (0..=MAX_CONFIG_DELAY).contains(&new_config.config_delay),
+            ErrorCode::InvalidConfigDelay
+        );
+
+        // 开启降费跳过延迟时只降低手续费的变更可以立即生效，其他变更需提前 config_delay 公告
+        let current_config = bank_account.config();
+        let bypasses_delay = bypasses_config_delay(&current_config, &new_config);
+        if !bypasses_delay {
+            let earliest = Clock::get()?
+                .unix_timestamp
+                .checked_add(current_config.config_delay)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            require!(eta >= earliest, ErrorCode::ConfigChangeTooEarly);
+        }
+
+        bank_account.pending_config = Some(PendingConfigChange {
+            config: new_config,
+            eta,
+        });
+
+        emit!(ConfigChangeQueuedEvent {
+            admin: bank_account.admin,
+            current_config,
+            new_config,
+            eta,
+            bypasses_delay,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 执行已到达生效时间的配置变更，任何人都可以调用
+     * @param ctx 上下文
+     */
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        let bank_account = &mut ctx.accounts.bank_account;
+
+        let pending = bank_account
+            .pending_config
+            .ok_or(ErrorCode::NoPendingConfigChange)?;
+        require!(
+            Clock::get()?.unix_timestamp >= pending.eta,
+            ErrorCode::ConfigChangeNotReady
+        );
+
+        let previous_config = bank_account.config();
+        bank_account.fee_config = pending.config.fee_config;
+        bank_account.config_delay = pending.config.config_delay;
+        bank_account.allow_fee_decrease_bypass = pending.config.allow_fee_decrease_bypass;
+        bank_account.pending_config = None;
+
+        emit!(ConfigChangeExecutedEvent {
+            previous_config,
+            new_config: pending.config,
+            eta: pending.eta,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 取消待生效的配置变更
+     * @param ctx 上下文
+     */
+    pub fn cancel_config_change(ctx: Context<ConfigChange>) -> Result<()> {
+        let bank_account = &mut ctx.accounts.bank_account;
+
+        require!(
+            ctx.accounts.admin.key() == bank_account.admin,
+            ErrorCode::Unauthorized
+        );
+
+        let cancelled = bank_account
+            .pending_config
+            .take()
+            .ok_or(ErrorCode::NoPendingConfigChange)?;
+
+        emit!(ConfigChangeCancelledEvent {
+            admin: bank_account.admin,
+            cancelled_config: cancelled.config,
+            eta: cancelled.eta,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 提名新的管理员，需由新管理员调用 accept_admin 确认后生效
+     * @param ctx 上下文
+     * @param new_admin 新管理员地址
+     */
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        let bank_account = &mut ctx.accounts.bank_account;
+
+        require!(
+            ctx.accounts.admin.key() == bank_account.admin,
+            ErrorCode::Unauthorized
+        );
+
+        bank_account.pending_admin = Some(new_admin);
+
+        emit!(AdminTransferProposedEvent {
+            admin: bank_account.admin,
+            pending_admin: new_admin,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 被提名的管理员接受管理员权限
+     * @param ctx 上下文
+     */
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let bank_account = &mut ctx.accounts.bank_account;
+
+        require!(
+            bank_account.pending_admin == Some(ctx.accounts.new_admin.key()),
+            ErrorCode::Unauthorized
+        );
+
+        let previous_admin = bank_account.admin;
+        bank_account.admin = ctx.accounts.new_admin.key();
+        bank_account.pending_admin = None;
+
+        emit!(AdminTransferAcceptedEvent {
+            previous_admin,
+            new_admin: bank_account.admin,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 取消尚未被接受的管理员提名
+     * @param ctx 上下文
+     */
+    pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+        let bank_account = &mut ctx.accounts.bank_account;
+
+        require!(
+            ctx.accounts.admin.key() == bank_account.admin,
+            ErrorCode::Unauthorized
+        );
+
+        let cancelled_admin = bank_account
+            .pending_admin
+            .take()
+            .ok_or(ErrorCode::NoPendingAdmin)?;
+
+        emit!(AdminTransferCancelledEvent {
+            admin: bank_account.admin,
+            cancelled_admin,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 暂停合约，暂停期间拒绝批量转账、创建Merkle空投和批量创建锁仓
+     * @dev 管理员操作(手续费配置、提取手续费、管理员变更等)以及领取空投、提取锁仓等
+     * 从已托管资金中转出的操作不受影响
+     * @param ctx 上下文
+     */
+    pub fn pause(ctx: Context<SetPaused>) -> Result<()> {
+        let bank_account = &mut ctx.accounts.bank_account;
+
+        require!(
+            ctx.accounts.admin.key() == bank_account.admin,
+            ErrorCode::Unauthorized
+        );
+
+        bank_account.paused = true;
+
+        emit!(PausedEvent {
+            admin: bank_account.admin,
+        });
+
         Ok(())
     }
 
     /**
-     * @notice 设置手续费
+     * @notice 恢复合约
      * @param ctx 上下文
-     * @param fee 手续费金额(lamports)
      */
-    pub fn set_fee(ctx: Context<SetFee>, fee: u64) -> Result<()> {
+    pub fn unpause(ctx: Context<SetPaused>) -> Result<()> {
         let bank_account = &mut ctx.accounts.bank_account;
 
         require!(
@@ -39,69 +275,253 @@ pub mod batch_transfer {
             ErrorCode::Unauthorized
         );
 
-        bank_account.fee = fee;
+        bank_account.paused = false;
+
+        emit!(UnpausedEvent {
+            admin: bank_account.admin,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 将银行账户迁移到当前布局
+     * @dev 最初布局 [鉴别符, admin, fee(u64), is_initialized] 中的 fee 迁移为 fee_config.flat_fee；
+     * 加入版本号之前的其他布局按当前结构解析，缺少的字段取默认值。
+     * 账户空间不足时扩容，新增租金由管理员支付
+     * @param ctx 上下文
+     */
+    pub fn migrate_bank_account(ctx: Context<MigrateBankAccount>) -> Result<()> {
+        let bank_account_info = ctx.accounts.bank_account.to_account_info();
+        require_keys_eq!(*bank_account_info.owner, crate::ID, ErrorCode::InvalidBankAccount);
+
+        let (mut bank_account, from_version) = {
+            let data = bank_account_info.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == BankAccount::DISCRIMINATOR,
+                ErrorCode::InvalidBankAccount
+            );
+            if data.len() == 8 + BankAccount::LEGACY_LEN {
+                let legacy = &data[8..];
+                let admin = Pubkey::try_from(&legacy[..32]).map_err(|_| ErrorCode::InvalidBankAccount)?;
+                let fee = u64::from_le_bytes(legacy[32..40].try_into().map_err(|_| ErrorCode::InvalidBankAccount)?);
+                let bank_account = BankAccount {
+                    admin,
+                    fee_config: FeeConfig {
+                        flat_fee: fee,
+                        ..FeeConfig::default()
+                    },
+                    is_initialized: legacy[40] != 0,
+                    pending_admin: None,
+                    paused: false,
+                    version: 0,
+                    config_delay: 0,
+                    allow_fee_decrease_bypass: false,
+                    pending_config: None,
+                };
+                (bank_account, 0)
+            } else {
+                // 旧账户末尾缺少的字段按0补齐，即取默认值
+                let mut padded = data.to_vec();
+                if padded.len() < 8 + BankAccount::LEN {
+                    padded.resize(8 + BankAccount::LEN, 0);
+                }
+                let bank_account = BankAccount::try_deserialize(&mut &padded[..])?;
+                let version = bank_account.version;
+                (bank_account, version)
+            }
+        };
+
+        require!(
+            ctx.accounts.admin.key() == bank_account.admin,
+            ErrorCode::Unauthorized
+        );
+        require!(from_version < BankAccount::VERSION, ErrorCode::BankAccountUpToDate);
+
+        // 扩容并补足租金豁免所需的lamports
+        let new_len = 8 + BankAccount::LEN;
+        if bank_account_info.data_len() < new_len {
+            let required_lamports = Rent::get()?.minimum_balance(new_len);
+            let top_up = required_lamports.saturating_sub(bank_account_info.lamports());
+            if top_up > 0 {
+                let cpi_context = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.admin.to_account_info(),
+                        to: bank_account_info.clone(),
+                    },
+                );
+                anchor_lang::system_program::transfer(cpi_context, top_up)?;
+            }
+            bank_account_info.realloc(new_len, true)?;
+        }
+
+        // 时间锁字段在版本2加入，迁移后的账户与新部署的账户一样启用默认的变更延迟
+        bank_account.config_delay = DEFAULT_CONFIG_DELAY;
+        bank_account.version = BankAccount::VERSION;
+        bank_account.try_serialize(&mut &mut bank_account_info.try_borrow_mut_data()?[..])?;
+
+        emit!(BankAccountMigratedEvent {
+            admin: bank_account.admin,
+            from_version,
+            to_version: BankAccount::VERSION,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 提取已收取的SOL手续费
+     * @param ctx 上下文
+     * @param amount 提取金额(lamports)，为空时提取全部可提取余额
+     */
+    pub fn withdraw_fee(ctx: Context<WithdrawFee>, amount: Option<u64>) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == ctx.accounts.bank_account.admin,
+            ErrorCode::Unauthorized
+        );
+
+        // 银行账户必须保留租金豁免所需的最低余额
+        let bank_account_info = ctx.accounts.bank_account.to_account_info();
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(bank_account_info.data_len());
+        let available = withdrawable_fee(bank_account_info.lamports(), rent_exempt_minimum);
+
+        let amount = amount.unwrap_or(available);
+        require!(
+            amount > 0 && amount <= available,
+            ErrorCode::InsufficientFeeBalance
+        );
+
+        // 银行账户由本程序所有，可以直接扣减lamports
+        let destination_info = ctx.accounts.destination.to_account_info();
+        let bank_balance = safe_sub(bank_account_info.lamports(), amount)?;
+        let destination_balance = safe_add(destination_info.lamports(), amount)?;
+        **bank_account_info.try_borrow_mut_lamports()? = bank_balance;
+        **destination_info.try_borrow_mut_lamports()? = destination_balance;
+
+        emit!(FeeWithdrawnEvent {
+            admin: ctx.accounts.admin.key(),
+            destination: destination_info.key(),
+            amount,
+            remaining: safe_sub(available, amount)?,
+        });
+
         Ok(())
     }
 
     /**
      * @notice 批量转账SOL
+     * @dev skip_invalid 为 true 时，校验失败的转账被跳过而不会使整批交易失败，
+     * 手续费只按成功转账的总额计算，被跳过的序号及原因(错误码)记录在事件中；
+     * 全部转账都被跳过时不收取手续费。
+     * duplicate_policy 为 Merge 时 remaining_accounts 和跳过的序号均对应合并后的转账。
+     * 提供 batch_id 时需传入 [b"batch_receipt", sender, batch_id] 派生的回执账户，
+     * 同一发送者的同一 batch_id 只能执行一次，回执已存在时返回 BatchAlreadyExecuted。
+     * 提供可选的 fee_payer 签名者时，手续费由 fee_payer 支付，转账金额仍由 sender 支付。
+     * 按执行时的手续费配置计算出的手续费超过 max_fee 时返回 FeeExceedsMaximum
      * @param ctx 上下文
      * @param transfers 转账信息数组
+     * @param skip_invalid 是否跳过校验失败的转账
+     * @param duplicate_policy 重复接收者的处理策略
+     * @param batch_id 可选的批次ID，用于防止重复执行
+     * @param max_fee 发送者愿意支付的最高手续费
      */
-    pub fn batch_transfer_sol<'a, 'b, 'c, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, BatchTransferSol<'info>>, 
-        transfers: Vec<TransferInfo>
+    pub fn batch_transfer_sol<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchTransferSol<'info>>, 
+        transfers: Vec<TransferInfo>,
+        skip_invalid: bool,
+        duplicate_policy: DuplicatePolicy,
+        batch_id: Option<[u8; 32]>,
+        max_fee: u64,
     ) -> Result<()> {
+        require!(!ctx.accounts.bank_account.paused, ErrorCode::Paused);
+
         // 检查转账列表不能为空
         if transfers.is_empty() {
             return Err(ErrorCode::EmptyTransfers.into());
         }
 
-        // 计算总转账金额
-        let total_amount = safe_sum_transfer_info(&transfers)?;
-        let fee = ctx.accounts.bank_account.fee;
-        let required_balance = safe_add(total_amount, fee)?;
-
-        // 检查发送者余额是否足够
-        let sender_balance = ctx.accounts.sender.lamports();
-        require!(
-            sender_balance >= required_balance,
-            ErrorCode::InsufficientFunds
-        );
-
-        // 扣除手续费
-        if fee > 0 {
-            let cpi_context = CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                anchor_lang::system_program::Transfer {
-                    from: ctx.accounts.sender.to_account_info(),
-                    to: ctx.accounts.bank_account.to_account_info(),
-                },
-            );
-            anchor_lang::system_program::transfer(cpi_context, fee)?;
+        // 提供批次ID时创建回执，同一批次只能执行一次
+        if let Some(batch_id) = batch_id {
+            record_batch_receipt(
+                &ctx.accounts.sender.to_account_info(),
+                ctx.accounts.batch_receipt.as_ref(),
+                &ctx.accounts.system_program.to_account_info(),
+                ctx.program_id,
+                batch_id,
+            )?;
         }
 
-        // 执行批量转账
+        // 按策略检查或合并重复的接收者
+        let transfers = apply_duplicate_policy(transfers, duplicate_policy)?;
+
+        // 逐笔校验接收者，skip_invalid 模式下记录被跳过的转账
         let remaining_accounts = ctx.remaining_accounts;
+        let rent = Rent::get()?;
+        let mut valid_transfers = Vec::with_capacity(transfers.len());
+        let mut skipped = Vec::new();
         for (i, transfer_info) in transfers.iter().enumerate() {
             if i >= remaining_accounts.len() {
                 return Err(ProgramError::NotEnoughAccountKeys.into());
             }
             
             let recipient_account_info = &remaining_accounts[i];
+            if let Err(error_code) = check_sol_recipient(recipient_account_info, transfer_info, &rent) {
+                if !skip_invalid {
+                    return Err(transfer_error(error_code, i));
+                }
+                skipped.push(SkippedTransfer::new(i, error_code));
+                continue;
+            }
+            valid_transfers.push((transfer_info, recipient_account_info));
+        }
 
-            // 验证接收者账户是否存在
-            require!(
-                recipient_account_info.key() == transfer_info.recipient,
-                ErrorCode::InvalidRecipient
-            );
+        // 计算成功转账的总金额
+        let total_amount = valid_transfers
+            .iter()
+            .try_fold(0u64, |acc, (info, _)| safe_add(acc, info.amount))?;
+        let fee = if valid_transfers.is_empty() {
+            0
+        } else {
+            calculate_fee(&ctx.accounts.bank_account.fee_config, total_amount)?
+        };
+        require!(fee <= max_fee, ErrorCode::FeeExceedsMaximum);
+
+        // 提供了 fee_payer 时由其支付手续费，否则由发送者支付
+        let fee_payer = ctx.accounts.fee_payer.as_ref().map_or_else(
+            || ctx.accounts.sender.to_account_info(),
+            |fee_payer| fee_payer.to_account_info(),
+        );
+        let required_balance = if fee_payer.key == ctx.accounts.sender.key {
+            safe_add(total_amount, fee)?
+        } else {
+            require!(fee_payer.lamports() >= fee, ErrorCode::InsufficientFunds);
+            total_amount
+        };
+
+        // 检查发送者余额是否足够
+        let sender_balance = ctx.accounts.sender.lamports();
+        require!(
+            sender_balance >= required_balance,
+            ErrorCode::InsufficientFunds
+        );
+
+        // 扣除手续费
+        charge_sol_fee(
+            &fee_payer,
+            &ctx.accounts.bank_account.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            fee,
+        )?;
 
+        // 执行批量转账
+        for (transfer_info, recipient_account_info) in &valid_transfers {
             // 使用系统程序进行转账
             let cpi_context = CpiContext::new(
                 ctx.accounts.system_program.to_account_info(),
                 anchor_lang::system_program::Transfer {
                     from: ctx.accounts.sender.to_account_info(),
-                    to: recipient_account_info.clone(),
+                    to: (*recipient_account_info).clone(),
                 },
             );
             anchor_lang::system_program::transfer(cpi_context, transfer_info.amount)?;
@@ -110,196 +530,3603 @@ pub mod batch_transfer {
         // 发送转账事件
         emit!(SolTransferEvent {
             from: ctx.accounts.sender.key(),
-            recipients: transfers.iter().map(|info| info.recipient).collect(),
-            amounts: transfers.iter().map(|info| info.amount).collect(),
+            fee_payer: fee_payer.key(),
+            recipients: valid_transfers.iter().map(|(info, _)| info.recipient).collect(),
+            amounts: valid_transfers.iter().map(|(info, _)| info.amount).collect(),
             total_amount,
             fee,
+            skipped,
+            batch_id,
         });
 
         Ok(())
     }
 
     /**
-     * @notice 批量转账SPL Token
+     * @notice 批量转账SPL Token，支持SPL Token和Token-2022程序
+     * @dev 使用 transfer_checked 转账；带转账手续费扩展的代币，接收者收到扣除扩展手续费后的净额。
+     * 未配置转账钩子的代币，remaining_accounts[i] 为第i笔转账的接收者token账户。
+     * 配置了转账钩子的代币，remaining_accounts 布局为：
+     * [钩子程序, ExtraAccountMetaList, (以代币收取手续费时)金库转账的额外账户..., 接收者0, 接收者0的额外账户..., 接收者1, ...]，
+     * 每笔转账附带的额外账户数量等于 ExtraAccountMetaList 中登记的账户数量，按登记顺序排列。
+     * skip_invalid 为 true 时，接收者校验失败的转账被跳过而不会使整批交易失败，
+     * 手续费只按成功转账的总额计算，被跳过的序号及原因(错误码)记录在事件中；全部转账都被跳过时不收取手续费。
+     * duplicate_policy 为 Merge 时 remaining_accounts 和跳过的序号均对应合并后的转账。
+     * batch_id 的用法同 batch_transfer_sol。
+     * sender 可以是 token_account 的所有者，也可以是通过 approve 获得授权的代理人；
+     * 代理人转账时转出总额(含代币手续费)不能超过剩余的 delegated_amount。
+     * 以SOL收取的手续费由可选的 fee_payer 支付，未提供时由 sender 支付；代币手续费始终从 token_account 扣除。
+     * max_fee 约束实际收取的手续费：以SOL收取时单位为lamports，以代币收取时单位为代币最小单位
      * @param ctx 上下文
      * @param transfers 转账信息数组
+     * @param skip_invalid 是否跳过校验失败的转账
+     * @param duplicate_policy 重复接收者的处理策略
+     * @param batch_id 可选的批次ID，用于防止重复执行
+     * @param max_fee 发送者愿意支付的最高手续费
      */
-    pub fn batch_transfer_token<'a, 'b, 'c, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, BatchTransferToken<'info>>, 
-        transfers: Vec<TransferInfo>
+    pub fn batch_transfer_token<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchTransferToken<'info>>, 
+        transfers: Vec<TransferInfo>,
+        skip_invalid: bool,
+        duplicate_policy: DuplicatePolicy,
+        batch_id: Option<[u8; 32]>,
+        max_fee: u64,
     ) -> Result<()> {
+        require!(!ctx.accounts.bank_account.paused, ErrorCode::Paused);
+
         // 检查转账列表不能为空
         if transfers.is_empty() {
             return Err(ErrorCode::EmptyTransfers.into());
         }
 
-        // 计算总转账金额
-        let total_amount = safe_sum_transfer_info(&transfers)?;
-        let fee = ctx.accounts.bank_account.fee;
+        // 提供批次ID时创建回执，同一批次只能执行一次
+        if let Some(batch_id) = batch_id {
+            record_batch_receipt(
+                &ctx.accounts.sender.to_account_info(),
+                ctx.accounts.batch_receipt.as_ref(),
+                &ctx.accounts.system_program.to_account_info(),
+                ctx.program_id,
+                batch_id,
+            )?;
+        }
+
+        // 按策略检查或合并重复的接收者
+        let transfers = apply_duplicate_policy(transfers, duplicate_policy)?;
+
+        // 该代币配置了代币手续费时必须以代币收取，未配置时不能提供手续费金库
+        let mint_fee_config = load_mint_fee_config(
+            &ctx.accounts.mint_fee_config,
+            ctx.accounts.fee_vault.is_some(),
+            ctx.program_id,
+        )?;
+
+        // 解析转账钩子账户，未配置钩子的代币不需要额外账户
+        let remaining_accounts = ctx.remaining_accounts;
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let (hook_accounts, extras_per_transfer) = parse_hook_accounts(&mint_info, remaining_accounts)?;
+        let mut offset = hook_accounts.len();
+
+        // 以代币收取手续费时，金库转账的额外账户位于第一个接收者之前
+        let fee_vault_extras = if ctx.accounts.fee_vault.is_some() {
+            let extras = take_accounts(remaining_accounts, offset, extras_per_transfer)?;
+            offset += extras_per_transfer;
+            extras
+        } else {
+            &remaining_accounts[..0]
+        };
+
+        // 逐笔校验接收者是与发送者同一mint的可用token账户，skip_invalid 模式下记录被跳过的转账
+        let mut valid_transfers = Vec::with_capacity(transfers.len());
+        let mut skipped = Vec::new();
+        for (i, transfer_info) in transfers.iter().enumerate() {
+            let recipient_token_account = take_accounts(remaining_accounts, offset, 1)?;
+            let extras = take_accounts(remaining_accounts, offset + 1, extras_per_transfer)?;
+            offset += 1 + extras_per_transfer;
+
+            let check = if recipient_token_account[0].key() != transfer_info.recipient {
+                Err(ErrorCode::InvalidRecipient)
+            } else {
+                check_recipient_token_account(
+                    &recipient_token_account[0],
+                    ctx.accounts.token_program.key,
+                    &ctx.accounts.mint.key(),
+                    &ctx.accounts.token_account.key(),
+                )
+            };
+            if let Err(error_code) = check {
+                if !skip_invalid {
+                    return Err(transfer_error(error_code, i));
+                }
+                skipped.push(SkippedTransfer::new(i, error_code));
+                continue;
+            }
+            valid_transfers.push((transfer_info, &recipient_token_account[0], extras));
+        }
+
+        // 计算成功转账的总金额，该代币配置了代币手续费时以代币收取手续费，否则以SOL收取
+        let total_amount = valid_transfers
+            .iter()
+            .try_fold(0u64, |acc, (info, _, _)| safe_add(acc, info.amount))?;
+        let (fee, token_fee) = match &mint_fee_config {
+            _ if valid_transfers.is_empty() => (0, 0),
+            Some(mint_fee_config) => (0, calculate_fee(mint_fee_config, total_amount)?),
+            None => (calculate_fee(&ctx.accounts.bank_account.fee_config, total_amount)?, 0),
+        };
+        require!(fee <= max_fee && token_fee <= max_fee, ErrorCode::FeeExceedsMaximum);
 
         // 检查发送者余额是否足够
-        let token_balance = token::accessor::amount(&ctx.accounts.token_account.to_account_info())?;
-        if token_balance < total_amount {
+        let token_balance = ctx.accounts.token_account.amount;
+        if token_balance < safe_add(total_amount, token_fee)? {
             return Err(ErrorCode::InsufficientFunds.into());
         }
 
-        // 检查发送者SOL余额是否足够支付手续费
-        let sender_balance = ctx.accounts.sender.lamports();
-        if sender_balance < fee {
+        // 发送者以代理人身份转账时，转出总额(含代币手续费)不能超过剩余的授权额度
+        let owner = ctx.accounts.token_account.owner;
+        let delegate = (owner != ctx.accounts.sender.key()).then(|| ctx.accounts.sender.key());
+        if delegate.is_some() {
+            require!(
+                ctx.accounts.token_account.delegated_amount >= safe_add(total_amount, token_fee)?,
+                ErrorCode::InsufficientDelegatedAmount
+            );
+        }
+
+        // 检查手续费支付者的SOL余额是否足够支付手续费，未提供 fee_payer 时由发送者支付
+        let fee_payer = ctx.accounts.fee_payer.as_ref().map_or_else(
+            || ctx.accounts.sender.to_account_info(),
+            |fee_payer| fee_payer.to_account_info(),
+        );
+        if fee_payer.lamports() < fee {
             return Err(ErrorCode::InsufficientFunds.into());
         }
 
         // 扣除手续费
-        if fee > 0 {
-            let cpi_context = CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                anchor_lang::system_program::Transfer {
-                    from: ctx.accounts.sender.to_account_info(),
-                    to: ctx.accounts.bank_account.to_account_info(),
-                },
-            );
-            anchor_lang::system_program::transfer(cpi_context, fee)?;
-        }
+        charge_sol_fee(
+            &fee_payer,
+            &ctx.accounts.bank_account.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            fee,
+        )?;
 
-        // 执行批量转账
-        let remaining_accounts = ctx.remaining_accounts;
-        for (i, transfer_info) in transfers.iter().enumerate() {
-            if i >= remaining_accounts.len() {
-                return Err(ProgramError::NotEnoughAccountKeys.into());
+        // 扣除代币手续费，转入银行账户所有的手续费金库
+        if let Some(fee_vault) = &ctx.accounts.fee_vault {
+            if token_fee > 0 {
+                spl_token_2022::onchain::invoke_transfer_checked(
+                    ctx.accounts.token_program.key,
+                    ctx.accounts.token_account.to_account_info(),
+                    mint_info.clone(),
+                    fee_vault.to_account_info(),
+                    ctx.accounts.sender.to_account_info(),
+                    &[hook_accounts, fee_vault_extras].concat(),
+                    token_fee,
+                    ctx.accounts.mint.decimals,
+                    &[],
+                )?;
             }
-            
-            let recipient_token_account = &remaining_accounts[i];
-
-            // 验证接收者账户是否存在
-            require!(
-                recipient_token_account.key() == transfer_info.recipient,
-                ErrorCode::InvalidRecipient
-            );
+        }
 
-            // 使用 token 程序进行转账
-            let cpi_context = CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                token::Transfer {
-                    from: ctx.accounts.token_account.to_account_info(),
-                    to: recipient_token_account.clone(),
-                    authority: ctx.accounts.sender.to_account_info(),
-                },
-            );
-            token::transfer(cpi_context, transfer_info.amount)?;
+        // 执行批量转账
+        for (transfer_info, recipient_token_account, extras) in &valid_transfers {
+            // 使用 token 程序进行转账，代币配置了转账钩子时一并传入钩子所需账户
+            spl_token_2022::onchain::invoke_transfer_checked(
+                ctx.accounts.token_program.key,
+                ctx.accounts.token_account.to_account_info(),
+                mint_info.clone(),
+                (*recipient_token_account).clone(),
+                ctx.accounts.sender.to_account_info(),
+                &[hook_accounts, extras].concat(),
+                transfer_info.amount,
+                ctx.accounts.mint.decimals,
+                &[],
+            )?;
         }
 
         // 发送转账事件
         emit!(TokenTransferEvent {
             from: ctx.accounts.sender.key(),
             token_account: ctx.accounts.token_account.key(),
-            recipients: transfers.iter().map(|info| info.recipient).collect(),
-            amounts: transfers.iter().map(|info| info.amount).collect(),
+            owner,
+            delegate,
+            fee_payer: fee_payer.key(),
+            recipients: valid_transfers.iter().map(|(info, _, _)| info.recipient).collect(),
+            amounts: valid_transfers.iter().map(|(info, _, _)| info.amount).collect(),
             total_amount,
             fee,
+            token_fee,
+            skipped,
+            batch_id,
         });
 
         Ok(())
     }
-}
-
-#[account]
-#[derive(Default)]
-pub struct BankAccount {
-    pub admin: Pubkey, // 管理员地址
-    pub fee: u64,      // 手续费金额
-    pub is_initialized: bool, // 是否已初始化
-}
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
+    /**
+     * @notice 等额批量转账SOL，每个接收者收到相同金额
+     * @dev 指令数据中只有一个 amount，remaining_accounts 中的每个账户都是接收者，
+     * 省去了每笔转账重复的接收者地址和金额；校验、手续费、max_fee、批次回执和事件与 batch_transfer_sol 一致
+     * @param ctx 上下文
+     * @param amount 每个接收者收到的金额
+     * @param batch_id 可选的批次ID，用于防止重复执行
+     * @param max_fee 发送者愿意支付的最高手续费
+     */
+    pub fn batch_transfer_sol_equal<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchTransferSol<'info>>,
+        amount: u64,
+        batch_id: Option<[u8; 32]>,
+        max_fee: u64,
+    ) -> Result<()> {
+        let transfers = ctx
+            .remaining_accounts
+            .iter()
+            .map(|recipient| TransferInfo {
+                recipient: recipient.key(),
+                amount,
+            })
+            .collect();
+        batch_transfer_sol(ctx, transfers, false, DuplicatePolicy::Allow, batch_id, max_fee)
+    }
+
+    /**
+     * @notice 等额批量转账SPL Token，每个接收者token账户收到相同数量
+     * @dev 指令数据中只有一个 amount，接收者从 remaining_accounts 中读取，布局与 batch_transfer_token 相同，
+     * 配置了转账钩子的代币每个接收者之后附带登记的额外账户；校验、手续费、max_fee、批次回执和事件与 batch_transfer_token 一致
+     * @param ctx 上下文
+     * @param amount 每个接收者收到的数量
+     * @param batch_id 可选的批次ID，用于防止重复执行
+     * @param max_fee 发送者愿意支付的最高手续费
+     */
+    pub fn batch_transfer_token_equal<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchTransferToken<'info>>,
+        amount: u64,
+        batch_id: Option<[u8; 32]>,
+        max_fee: u64,
+    ) -> Result<()> {
+        // 跳过钩子账户和金库转账的额外账户，余下按每个接收者 1 + 额外账户数量 分组
+        let remaining_accounts = ctx.remaining_accounts;
+        let (hook_accounts, extras_per_transfer) =
+            parse_hook_accounts(&ctx.accounts.mint.to_account_info(), remaining_accounts)?;
+        let mut offset = hook_accounts.len();
+        if ctx.accounts.fee_vault.is_some() {
+            offset += extras_per_transfer;
+        }
+        let recipient_accounts = remaining_accounts
+            .get(offset..)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        require!(
+            recipient_accounts.len() % (1 + extras_per_transfer) == 0,
+            ErrorCode::InvalidRemainingAccounts
+        );
+
+        let transfers = recipient_accounts
+            .chunks(1 + extras_per_transfer)
+            .map(|accounts| TransferInfo {
+                recipient: accounts[0].key(),
+                amount,
+            })
+            .collect();
+        batch_transfer_token(ctx, transfers, false, DuplicatePolicy::Allow, batch_id, max_fee)
+    }
+
+    /**
+     * @notice 按权重拆分固定总额批量转账SOL
+     * @dev 每个接收者分得 total * weight / 权重总和，向下取整后剩余的零头按最大余数法分配：
+     * 余数大的接收者各多分1，余数相同时序号小的优先，保证各笔金额之和恰好等于 total。
+     * remaining_accounts 与 batch_transfer_sol 相同，手续费按 total 计算，超过 max_fee 时失败
+     * @param ctx 上下文
+     * @param total 拆分的总金额
+     * @param splits 接收者及其权重
+     * @param batch_id 可选的批次ID，用于防止重复执行
+     * @param max_fee 发送者愿意支付的最高手续费
+     */
+    pub fn batch_split_sol<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchTransferSol<'info>>,
+        total: u64,
+        splits: Vec<SplitInfo>,
+        batch_id: Option<[u8; 32]>,
+        max_fee: u64,
+    ) -> Result<()> {
+        let transfers = split_by_weight(total, &splits)?;
+        batch_transfer_sol(ctx, transfers, false, DuplicatePolicy::Allow, batch_id, max_fee)
+    }
+
+    /**
+     * @notice 按权重拆分固定总额批量转账SPL Token
+     * @dev 拆分规则同 batch_split_sol，remaining_accounts 布局与 batch_transfer_token 相同。
+     * 带转账手续费扩展的代币，接收者收到的仍是扣除扩展手续费后的净额；max_fee 的含义同 batch_transfer_token
+     * @param ctx 上下文
+     * @param total 拆分的代币总数量
+     * @param splits 接收者token账户及其权重
+     * @param batch_id 可选的批次ID，用于防止重复执行
+     * @param max_fee 发送者愿意支付的最高手续费
+     */
+    pub fn batch_split_token<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchTransferToken<'info>>,
+        total: u64,
+        splits: Vec<SplitInfo>,
+        batch_id: Option<[u8; 32]>,
+        max_fee: u64,
+    ) -> Result<()> {
+        let transfers = split_by_weight(total, &splits)?;
+        batch_transfer_token(ctx, transfers, false, DuplicatePolicy::Allow, batch_id, max_fee)
+    }
+
+    /**
+     * @notice 关闭批次回执，租金退还给发送者
+     * @dev remaining_accounts 为要关闭的回执账户(可写)，只能关闭自己的回执；
+     * 回执关闭后同一 batch_id 可以再次执行，应只关闭确认不会再重发的旧批次
+     * @param ctx 上下文
+     */
+    pub fn close_batch_receipts<'info>(ctx: Context<'_, '_, '_, 'info, CloseBatchReceipts<'info>>) -> Result<()> {
+        let sender_info = ctx.accounts.sender.to_account_info();
+        let mut batch_ids = Vec::with_capacity(ctx.remaining_accounts.len());
+        let mut reclaimed = 0u64;
+        for (i, receipt_info) in ctx.remaining_accounts.iter().enumerate() {
+            if receipt_info.owner != ctx.program_id || !receipt_info.is_writable {
+                return Err(transfer_error(ErrorCode::InvalidBatchReceipt, i));
+            }
+            let receipt = BatchReceipt::try_deserialize(&mut &receipt_info.try_borrow_data()?[..])
+                .map_err(|_| transfer_error(ErrorCode::InvalidBatchReceipt, i))?;
+            if receipt.sender != sender_info.key() {
+                return Err(transfer_error(ErrorCode::Unauthorized, i));
+            }
+
+            // 租金转给发送者，账户归还系统程序并清空数据
+            let lamports = receipt_info.lamports();
+            **sender_info.try_borrow_mut_lamports()? = safe_add(sender_info.lamports(), lamports)?;
+            **receipt_info.try_borrow_mut_lamports()? = 0;
+            receipt_info.assign(&System::id());
+            receipt_info.realloc(0, false)?;
+
+            batch_ids.push(receipt.batch_id);
+            reclaimed = safe_add(reclaimed, lamports)?;
+        }
+
+        emit!(BatchReceiptsClosedEvent {
+            sender: sender_info.key(),
+            batch_ids,
+            reclaimed,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 按钱包地址批量转账SPL Token，自动创建接收者的关联token账户(ATA)
+     * @dev transfers 中的 recipient 为接收者钱包地址，程序根据钱包、mint和代币程序推导并校验ATA，
+     * ATA不存在时以幂等方式创建，租金由发送者支付。
+     * 未配置转账钩子的代币，remaining_accounts 依次为每笔转账的 [接收者钱包, 接收者ATA]；
+     * 配置了转账钩子的代币，布局同 batch_transfer_token，只是每个接收者位置为 [接收者钱包, 接收者ATA, 额外账户...]。
+     * max_fee 的含义同 batch_transfer_token
+     * @param ctx 上下文
+     * @param transfers 转账信息数组
+     * @param max_fee 发送者愿意支付的最高手续费
+     */
+    pub fn batch_transfer_token_to_wallets<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchTransferTokenToWallets<'info>>,
+        transfers: Vec<TransferInfo>,
+        max_fee: u64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.bank_account.paused, ErrorCode::Paused);
+
+        // 检查转账列表不能为空
+        if transfers.is_empty() {
+            return Err(ErrorCode::EmptyTransfers.into());
+        }
+
+        // 该代币配置了代币手续费时必须以代币收取，未配置时不能提供手续费金库
+        let mint_fee_config = load_mint_fee_config(
+            &ctx.accounts.mint_fee_config,
+            ctx.accounts.fee_vault.is_some(),
+            ctx.program_id,
+        )?;
+
+        // 计算总转账金额，该代币配置了代币手续费时以代币收取手续费，否则以SOL收取
+        let total_amount = safe_sum_transfer_info(&transfers)?;
+        let (fee, token_fee) = match &mint_fee_config {
+            Some(mint_fee_config) => (0, calculate_fee(mint_fee_config, total_amount)?),
+            None => (calculate_fee(&ctx.accounts.bank_account.fee_config, total_amount)?, 0),
+        };
+        require!(fee <= max_fee && token_fee <= max_fee, ErrorCode::FeeExceedsMaximum);
+
+        // 检查发送者余额是否足够
+        let token_balance = ctx.accounts.token_account.amount;
+        if token_balance < safe_add(total_amount, token_fee)? {
+            return Err(ErrorCode::InsufficientFunds.into());
+        }
+
+        // 检查发送者SOL余额是否足够支付手续费
+        let sender_balance = ctx.accounts.sender.lamports();
+        if sender_balance < fee {
+            return Err(ErrorCode::InsufficientFunds.into());
+        }
+
+        // 扣除手续费
+        charge_sol_fee(
+            &ctx.accounts.sender.to_account_info(),
+            &ctx.accounts.bank_account.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            fee,
+        )?;
+
+        // 解析转账钩子账户，未配置钩子的代币不需要额外账户
+        let remaining_accounts = ctx.remaining_accounts;
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let (hook_accounts, extras_per_transfer) = parse_hook_accounts(&mint_info, remaining_accounts)?;
+        let mut offset = hook_accounts.len();
+
+        // 扣除代币手续费，转入银行账户所有的手续费金库
+        if let Some(fee_vault) = &ctx.accounts.fee_vault {
+            let extras = take_accounts(remaining_accounts, offset, extras_per_transfer)?;
+            offset += extras_per_transfer;
+            if token_fee > 0 {
+                spl_token_2022::onchain::invoke_transfer_checked(
+                    ctx.accounts.token_program.key,
+                    ctx.accounts.token_account.to_account_info(),
+                    mint_info.clone(),
+                    fee_vault.to_account_info(),
+                    ctx.accounts.sender.to_account_info(),
+                    &[hook_accounts, extras].concat(),
+                    token_fee,
+                    ctx.accounts.mint.decimals,
+                    &[],
+                )?;
+            }
+        }
+
+        // 执行批量转账
+        let mut token_accounts = Vec::with_capacity(transfers.len());
+        let mut created_token_accounts = Vec::new();
+        for (i, transfer_info) in transfers.iter().enumerate() {
+            let recipient_accounts = take_accounts(remaining_accounts, offset, 2)?;
+            let extras = take_accounts(remaining_accounts, offset + 2, extras_per_transfer)?;
+            offset += 2 + extras_per_transfer;
+            let wallet = &recipient_accounts[0];
+            let recipient_token_account = &recipient_accounts[1];
+
+            // 验证接收者钱包和ATA地址
+            let expected_token_account = get_associated_token_address_with_program_id(
+                wallet.key,
+                &ctx.accounts.mint.key(),
+                ctx.accounts.token_program.key,
+            );
+            if wallet.key() != transfer_info.recipient || recipient_token_account.key() != expected_token_account {
+                return Err(transfer_error(ErrorCode::InvalidRecipient, i));
+            }
+
+            // ATA不存在时创建，由发送者支付租金
+            if recipient_token_account.data_is_empty() {
+                let cpi_context = CpiContext::new(
+                    ctx.accounts.associated_token_program.to_account_info(),
+                    associated_token::Create {
+                        payer: ctx.accounts.sender.to_account_info(),
+                        associated_token: recipient_token_account.clone(),
+                        authority: wallet.clone(),
+                        mint: mint_info.clone(),
+                        system_program: ctx.accounts.system_program.to_account_info(),
+                        token_program: ctx.accounts.token_program.to_account_info(),
+                    },
+                );
+                associated_token::create_idempotent(cpi_context)?;
+                created_token_accounts.push(recipient_token_account.key());
+            }
+            validate_recipient_token_account(
+                recipient_token_account,
+                ctx.accounts.token_program.key,
+                &ctx.accounts.mint.key(),
+                &ctx.accounts.token_account.key(),
+                i,
+            )?;
+
+            // 使用 token 程序进行转账，代币配置了转账钩子时一并传入钩子所需账户
+            spl_token_2022::onchain::invoke_transfer_checked(
+                ctx.accounts.token_program.key,
+                ctx.accounts.token_account.to_account_info(),
+                mint_info.clone(),
+                recipient_token_account.clone(),
+                ctx.accounts.sender.to_account_info(),
+                &[hook_accounts, extras].concat(),
+                transfer_info.amount,
+                ctx.accounts.mint.decimals,
+                &[],
+            )?;
+            token_accounts.push(recipient_token_account.key());
+        }
+
+        // 发送转账事件
+        emit!(TokenWalletTransferEvent {
+            from: ctx.accounts.sender.key(),
+            token_account: ctx.accounts.token_account.key(),
+            mint: ctx.accounts.mint.key(),
+            recipients: transfers.iter().map(|info| info.recipient).collect(),
+            token_accounts,
+            created_token_accounts,
+            amounts: transfers.iter().map(|info| info.amount).collect(),
+            total_amount,
+            fee,
+            token_fee,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 提交某个代币以代币本身收取的手续费配置变更，并创建该代币的手续费金库；
+     * 到达生效时间后通过 execute_mint_fee_config_change 生效
+     * @dev 与 queue_config_change 使用同一个 config_delay：eta 必须不早于 当前时间 + config_delay；
+     * 银行账户开启 allow_fee_decrease_bypass 时，已生效的配置只降低手续费可以立即生效，首次启用代币手续费不能跳过延迟。
+     * 同一代币同一时间只能有一个待生效的变更
+     * @param ctx 上下文
+     * @param fee_config 新的手续费配置，金额以代币最小单位计算
+     * @param eta 生效时间(unix时间戳)
+     */
+    pub fn queue_mint_fee_config_change(
+        ctx: Context<QueueMintFeeConfigChange>,
+        fee_config: FeeConfig,
+        eta: i64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == ctx.accounts.bank_account.admin,
+            ErrorCode::Unauthorized
+        );
+        validate_fee_config(&fee_config)?;
+
+        let mint_fee_config = &mut ctx.accounts.mint_fee_config;
+        require!(mint_fee_config.pending_config.is_none(), ErrorCode::ConfigChangePending);
+
+        let current_fee_config = mint_fee_config.fee_config;
+        let bypasses_delay = ctx.accounts.bank_account.allow_fee_decrease_bypass
+            && current_fee_config.is_some_and(|current| fee_config_not_raised(&current, &fee_config));
+        if !bypasses_delay {
+            let earliest = Clock::get()?
+                .unix_timestamp
+                .checked_add(ctx.accounts.bank_account.config_delay)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            require!(eta >= earliest, ErrorCode::ConfigChangeTooEarly);
+        }
+
+        mint_fee_config.mint = ctx.accounts.mint.key();
+        mint_fee_config.pending_config = Some(PendingMintFeeConfigChange { fee_config, eta });
+
+        emit!(MintFeeConfigChangeQueuedEvent {
+            admin: ctx.accounts.admin.key(),
+            mint: mint_fee_config.mint,
+            fee_vault: ctx.accounts.fee_vault.key(),
+            current_fee_config,
+            new_fee_config: fee_config,
+            eta,
+            bypasses_delay,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 执行已到达生效时间的代币手续费配置变更，任何人都可以调用
+     * @param ctx 上下文
+     */
+    pub fn execute_mint_fee_config_change(ctx: Context<ExecuteMintFeeConfigChange>) -> Result<()> {
+        let mint_fee_config = &mut ctx.accounts.mint_fee_config;
+
+        let pending = mint_fee_config
+            .pending_config
+            .ok_or(ErrorCode::NoPendingConfigChange)?;
+        require!(
+            Clock::get()?.unix_timestamp >= pending.eta,
+            ErrorCode::ConfigChangeNotReady
+        );
+
+        let previous_fee_config = mint_fee_config.fee_config;
+        mint_fee_config.fee_config = Some(pending.fee_config);
+        mint_fee_config.pending_config = None;
+
+        emit!(MintFeeConfigChangeExecutedEvent {
+            mint: mint_fee_config.mint,
+            previous_fee_config,
+            new_fee_config: pending.fee_config,
+            eta: pending.eta,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 取消待生效的代币手续费配置变更
+     * @param ctx 上下文
+     */
+    pub fn cancel_mint_fee_config_change(ctx: Context<CancelMintFeeConfigChange>) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == ctx.accounts.bank_account.admin,
+            ErrorCode::Unauthorized
+        );
+
+        let mint_fee_config = &mut ctx.accounts.mint_fee_config;
+        let cancelled = mint_fee_config
+            .pending_config
+            .take()
+            .ok_or(ErrorCode::NoPendingConfigChange)?;
+
+        emit!(MintFeeConfigChangeCancelledEvent {
+            admin: ctx.accounts.admin.key(),
+            mint: mint_fee_config.mint,
+            cancelled_fee_config: cancelled.fee_config,
+            eta: cancelled.eta,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 提取手续费金库中以代币收取的手续费
+     * @dev 代币配置了转账钩子时，钩子程序、ExtraAccountMetaList 及额外账户通过 remaining_accounts 传入
+     * @param ctx 上下文
+     * @param amount 提取数量，为空时提取全部
+     */
+    pub fn withdraw_token_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawTokenFees<'info>>,
+        amount: Option<u64>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.admin.key() == ctx.accounts.bank_account.admin,
+            ErrorCode::Unauthorized
+        );
+
+        let available = ctx.accounts.fee_vault.amount;
+        let amount = amount.unwrap_or(available);
+        require!(
+            amount > 0 && amount <= available,
+            ErrorCode::InsufficientFeeBalance
+        );
+
+        // 手续费金库的所有者是银行账户PDA，使用PDA种子签名
+        let signer_seeds: &[&[&[u8]]] = &[&[b"bank_account", &[ctx.bumps.bank_account]]];
+        spl_token_2022::onchain::invoke_transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.fee_vault.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.destination.to_account_info(),
+            ctx.accounts.bank_account.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            ctx.accounts.mint.decimals,
+            signer_seeds,
+        )?;
+
+        emit!(TokenFeeWithdrawnEvent {
+            admin: ctx.accounts.admin.key(),
+            mint: ctx.accounts.mint.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+            remaining: safe_sub(available, amount)?,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 创建Merkle空投分发器，并将空投总额转入分发器的PDA金库
+     * @dev 叶子节点为 merkle_leaf(index, TransferInfo { recipient, amount })，recipient 为领取者钱包地址。
+     * 金库必须收到完整的空投总额，因此不支持实际收取转账手续费的Token-2022代币。
+     * 按 bank_account 的手续费配置以SOL收取一次手续费。代币配置了转账钩子时，钩子所需账户通过 remaining_accounts 传入
+     * @param ctx 上下文
+     * @param merkle_root Merkle树根
+     * @param total 空投总额，即可被领取的最大数量
+     * @param expiry 领取截止时间(unix时间戳)，到期后创建者可以收回剩余代币
+     * @param max_fee 创建者愿意支付的最高手续费
+     */
+    pub fn create_distributor<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateDistributor<'info>>,
+        merkle_root: [u8; 32],
+        total: u64,
+        expiry: i64,
+        max_fee: u64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.bank_account.paused, ErrorCode::Paused);
+
+        require!(total > 0, ErrorCode::InvalidDistributorConfig);
+        require!(expiry > Clock::get()?.unix_timestamp, ErrorCode::InvalidDistributorConfig);
+
+        // 检查创建者余额是否足够
+        require!(ctx.accounts.creator_token_account.amount >= total, ErrorCode::InsufficientFunds);
+        let fee = calculate_fee(&ctx.accounts.bank_account.fee_config, total)?;
+        require!(fee <= max_fee, ErrorCode::FeeExceedsMaximum);
+        require!(ctx.accounts.creator.lamports() >= fee, ErrorCode::InsufficientFunds);
+
+        // 扣除手续费
+        charge_sol_fee(
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.bank_account.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            fee,
+        )?;
+
+        // 将空投总额转入分发器金库，金库必须收到全额，否则最后的领取会因余额不足失败
+        spl_token_2022::onchain::invoke_transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.creator_token_account.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.creator.to_account_info(),
+            ctx.remaining_accounts,
+            total,
+            ctx.accounts.mint.decimals,
+            &[],
+        )?;
+        ctx.accounts.vault.reload()?;
+        require!(ctx.accounts.vault.amount == total, ErrorCode::InvalidDistributorConfig);
+
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.creator = ctx.accounts.creator.key();
+        distributor.mint = ctx.accounts.mint.key();
+        distributor.vault = ctx.accounts.vault.key();
+        distributor.merkle_root = merkle_root;
+        distributor.total = total;
+        distributor.claimed = 0;
+        distributor.expiry = expiry;
+        distributor.clawed_back = false;
+        distributor.bump = ctx.bumps.distributor;
+
+        emit!(DistributorCreatedEvent {
+            distributor: distributor.key(),
+            creator: distributor.creator,
+            mint: distributor.mint,
+            vault: distributor.vault,
+            merkle_root,
+            total,
+            expiry,
+            fee,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 领取Merkle空投
+     * @dev 领取者即签名者，领取状态记录在按序号分块的位图账户中，位图账户不存在时由领取者支付租金创建
+     * @param ctx 上下文
+     * @param index 叶子节点序号
+     * @param amount 领取数量
+     * @param proof Merkle证明
+     */
+    pub fn claim<'info>(
+        ctx: Context<'_, '_, '_, 'info, Claim<'info>>,
+        index: u64,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let distributor = &ctx.accounts.distributor;
+        require!(
+            !distributor.clawed_back && Clock::get()?.unix_timestamp < distributor.expiry,
+            ErrorCode::DistributorExpired
+        );
+
+        // 校验Merkle证明
+        let leaf = merkle_leaf(
+            index,
+            &TransferInfo {
+                recipient: ctx.accounts.claimant.key(),
+                amount,
+            },
+        );
+        require!(
+            verify_merkle_proof(&proof, &distributor.merkle_root, leaf),
+            ErrorCode::InvalidMerkleProof
+        );
+
+        // 在位图中记录领取状态
+        let claim_bitmap = &mut ctx.accounts.claim_bitmap;
+        if claim_bitmap.distributor == Pubkey::default() {
+            claim_bitmap.distributor = distributor.key();
+            claim_bitmap.chunk = claim_bitmap_chunk(index)?;
+        }
+        let (byte, mask) = claim_bitmap_position(index);
+        require!(claim_bitmap.bits[byte] & mask == 0, ErrorCode::AlreadyClaimed);
+        claim_bitmap.bits[byte] |= mask;
+
+        let claimed = safe_add(distributor.claimed, amount)?;
+        require!(claimed <= distributor.total, ErrorCode::InsufficientFunds);
+
+        // 分发器PDA签名，从金库转出
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"distributor",
+            distributor.creator.as_ref(),
+            distributor.mint.as_ref(),
+            distributor.merkle_root.as_ref(),
+            &[distributor.bump],
+        ]];
+        spl_token_2022::onchain::invoke_transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.claimant_token_account.to_account_info(),
+            distributor.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            ctx.accounts.mint.decimals,
+            signer_seeds,
+        )?;
+
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.claimed = claimed;
+
+        emit!(ClaimedEvent {
+            distributor: distributor.key(),
+            claimant: ctx.accounts.claimant.key(),
+            token_account: ctx.accounts.claimant_token_account.key(),
+            index,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 空投到期后，创建者收回金库中剩余的代币
+     * @param ctx 上下文
+     */
+    pub fn clawback<'info>(ctx: Context<'_, '_, '_, 'info, Clawback<'info>>) -> Result<()> {
+        let distributor = &ctx.accounts.distributor;
+        require!(
+            Clock::get()?.unix_timestamp >= distributor.expiry,
+            ErrorCode::DistributorNotExpired
+        );
+
+        let amount = ctx.accounts.vault.amount;
+        require!(amount > 0, ErrorCode::InsufficientFunds);
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"distributor",
+            distributor.creator.as_ref(),
+            distributor.mint.as_ref(),
+            distributor.merkle_root.as_ref(),
+            &[distributor.bump],
+        ]];
+        spl_token_2022::onchain::invoke_transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.destination.to_account_info(),
+            distributor.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            ctx.accounts.mint.decimals,
+            signer_seeds,
+        )?;
+
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.clawed_back = true;
+
+        emit!(ClawbackEvent {
+            distributor: distributor.key(),
+            creator: distributor.creator,
+            destination: ctx.accounts.destination.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 批量创建线性释放的代币锁仓
+     * @dev 为每个接收者创建锁仓PDA，种子为 [b"vesting", 发送者, mint, 接收者, start(i64 LE)]，
+     * 锁仓代币存放在锁仓PDA的关联token账户(ATA)中，锁仓账户和ATA的租金由发送者支付。
+     * 按 bank_account 的手续费配置以SOL收取一次手续费。
+     * 未配置转账钩子的代币，remaining_accounts 依次为每个锁仓的 [锁仓PDA, 锁仓ATA]；
+     * 配置了转账钩子的代币，先传入钩子程序和 ExtraAccountMetaList，每个锁仓位置为 [锁仓PDA, 锁仓ATA, 额外账户...]
+     * @param ctx 上下文
+     * @param schedules 锁仓信息数组
+     * @param max_fee 发送者愿意支付的最高手续费
+     */
+    pub fn batch_create_vesting<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchCreateVesting<'info>>,
+        schedules: Vec<VestingInfo>,
+        max_fee: u64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.bank_account.paused, ErrorCode::Paused);
+
+        // 检查锁仓列表不能为空
+        require!(!schedules.is_empty(), ErrorCode::EmptyTransfers);
+        for (i, schedule) in schedules.iter().enumerate() {
+            if !is_valid_vesting_schedule(schedule) {
+                return Err(transfer_error(ErrorCode::InvalidVestingSchedule, i));
+            }
+        }
+
+        // 检查发送者余额是否足够
+        let total_amount = schedules
+            .iter()
+            .try_fold(0u64, |acc, schedule| safe_add(acc, schedule.amount))?;
+        require!(ctx.accounts.token_account.amount >= total_amount, ErrorCode::InsufficientFunds);
+        let fee = calculate_fee(&ctx.accounts.bank_account.fee_config, total_amount)?;
+        require!(fee <= max_fee, ErrorCode::FeeExceedsMaximum);
+        require!(ctx.accounts.sender.lamports() >= fee, ErrorCode::InsufficientFunds);
+
+        // 扣除手续费
+        charge_sol_fee(
+            &ctx.accounts.sender.to_account_info(),
+            &ctx.accounts.bank_account.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            fee,
+        )?;
+
+        // 解析转账钩子账户，未配置钩子的代币不需要额外账户
+        let remaining_accounts = ctx.remaining_accounts;
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let (hook_accounts, extras_per_transfer) = parse_hook_accounts(&mint_info, remaining_accounts)?;
+        let mut offset = hook_accounts.len();
+
+        let sender_key = ctx.accounts.sender.key();
+        let mint_key = ctx.accounts.mint.key();
+        let mut vestings = Vec::with_capacity(schedules.len());
+        let mut amounts = Vec::with_capacity(schedules.len());
+        for (i, schedule) in schedules.iter().enumerate() {
+            let vesting_accounts = take_accounts(remaining_accounts, offset, 2)?;
+            let extras = take_accounts(remaining_accounts, offset + 2, extras_per_transfer)?;
+            offset += 2 + extras_per_transfer;
+            let vesting_info = &vesting_accounts[0];
+            let vault = &vesting_accounts[1];
+
+            // 验证锁仓PDA和锁仓ATA地址
+            let start_bytes = schedule.start.to_le_bytes();
+            let (expected_vesting, bump) = Pubkey::find_program_address(
+                &[
+                    b"vesting",
+                    sender_key.as_ref(),
+                    mint_key.as_ref(),
+                    schedule.recipient.as_ref(),
+                    &start_bytes,
+                ],
+                ctx.program_id,
+            );
+            let expected_vault = get_associated_token_address_with_program_id(
+                &expected_vesting,
+                &mint_key,
+                ctx.accounts.token_program.key,
+            );
+            if vesting_info.key() != expected_vesting || vault.key() != expected_vault {
+                return Err(transfer_error(ErrorCode::InvalidVestingAccount, i));
+            }
+            if !vesting_info.data_is_empty() || vesting_info.owner != &System::id() {
+                return Err(transfer_error(ErrorCode::InvalidVestingAccount, i));
+            }
+
+            // 创建锁仓账户，由发送者支付租金
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                b"vesting",
+                sender_key.as_ref(),
+                mint_key.as_ref(),
+                schedule.recipient.as_ref(),
+                &start_bytes,
+                &[bump],
+            ]];
+            let system_program = ctx.accounts.system_program.to_account_info();
+            create_pda_account(
+                &ctx.accounts.sender.to_account_info(),
+                vesting_info,
+                &system_program,
+                8 + VestingAccount::LEN,
+                ctx.program_id,
+                signer_seeds,
+            )?;
+
+            // 创建锁仓ATA，由发送者支付租金
+            let cpi_context = CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: ctx.accounts.sender.to_account_info(),
+                    associated_token: vault.clone(),
+                    authority: vesting_info.clone(),
+                    mint: mint_info.clone(),
+                    system_program,
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+            );
+            associated_token::create_idempotent(cpi_context)?;
+
+            // 将锁仓数量转入锁仓ATA，代币配置了转账钩子时一并传入钩子所需账户
+            spl_token_2022::onchain::invoke_transfer_checked(
+                ctx.accounts.token_program.key,
+                ctx.accounts.token_account.to_account_info(),
+                mint_info.clone(),
+                vault.clone(),
+                ctx.accounts.sender.to_account_info(),
+                &[hook_accounts, extras].concat(),
+                schedule.amount,
+                ctx.accounts.mint.decimals,
+                &[],
+            )?;
+
+            // 带转账手续费扩展的代币，锁仓数量以锁仓ATA实际收到的数量为准
+            let amount = {
+                let data = vault.try_borrow_data()?;
+                StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?.base.amount
+            };
+
+            let vesting = VestingAccount {
+                creator: sender_key,
+                recipient: schedule.recipient,
+                mint: mint_key,
+                vault: vault.key(),
+                start: schedule.start,
+                cliff: schedule.cliff,
+                end: schedule.end,
+                amount,
+                withdrawn: 0,
+                revocable: schedule.revocable,
+                revoked: false,
+                bump,
+            };
+            vesting.try_serialize(&mut &mut vesting_info.try_borrow_mut_data()?[..])?;
+
+            vestings.push(vesting_info.key());
+            amounts.push(amount);
+        }
+
+        emit!(VestingCreatedEvent {
+            creator: sender_key,
+            token_account: ctx.accounts.token_account.key(),
+            mint: mint_key,
+            recipients: schedules.iter().map(|schedule| schedule.recipient).collect(),
+            vestings,
+            amounts,
+            total_amount,
+            fee,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 接收者提取锁仓中已释放且尚未提取的代币
+     * @dev 代币配置了转账钩子时，钩子所需账户通过 remaining_accounts 传入
+     * @param ctx 上下文
+     */
+    pub fn withdraw_vested<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawVested<'info>>) -> Result<()> {
+        let vesting = &ctx.accounts.vesting;
+        let vested = vesting.vested_amount(Clock::get()?.unix_timestamp);
+        let amount = safe_sub(vested, vesting.withdrawn)?;
+        require!(amount > 0, ErrorCode::NothingToWithdraw);
+
+        // 锁仓PDA签名，从锁仓ATA转出
+        let start_bytes = vesting.start.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"vesting",
+            vesting.creator.as_ref(),
+            vesting.mint.as_ref(),
+            vesting.recipient.as_ref(),
+            &start_bytes,
+            &[vesting.bump],
+        ]];
+        spl_token_2022::onchain::invoke_transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.recipient_token_account.to_account_info(),
+            vesting.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            ctx.accounts.mint.decimals,
+            signer_seeds,
+        )?;
+
+        let vesting = &mut ctx.accounts.vesting;
+        vesting.withdrawn = vested;
+
+        emit!(VestingWithdrawnEvent {
+            vesting: vesting.key(),
+            recipient: vesting.recipient,
+            token_account: ctx.accounts.recipient_token_account.key(),
+            amount,
+            withdrawn: vesting.withdrawn,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 创建者撤销可撤销的锁仓，收回尚未释放的代币
+     * @dev 撤销时已释放的代币仍归接收者所有，可继续通过 withdraw_vested 提取；
+     * 代币配置了转账钩子时，钩子所需账户通过 remaining_accounts 传入
+     * @param ctx 上下文
+     */
+    pub fn revoke_vesting<'info>(ctx: Context<'_, '_, '_, 'info, RevokeVesting<'info>>) -> Result<()> {
+        let vesting = &ctx.accounts.vesting;
+        require!(vesting.revocable, ErrorCode::VestingNotRevocable);
+        require!(!vesting.revoked, ErrorCode::VestingAlreadyRevoked);
+
+        let vested = vesting.vested_amount(Clock::get()?.unix_timestamp);
+        let unvested = safe_sub(vesting.amount, vested)?;
+        require!(unvested > 0, ErrorCode::NothingToWithdraw);
+
+        let start_bytes = vesting.start.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"vesting",
+            vesting.creator.as_ref(),
+            vesting.mint.as_ref(),
+            vesting.recipient.as_ref(),
+            &start_bytes,
+            &[vesting.bump],
+        ]];
+        spl_token_2022::onchain::invoke_transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.destination.to_account_info(),
+            vesting.to_account_info(),
+            ctx.remaining_accounts,
+            unvested,
+            ctx.accounts.mint.decimals,
+            signer_seeds,
+        )?;
+
+        // 锁仓总量截断为撤销时已释放的数量
+        let vesting = &mut ctx.accounts.vesting;
+        vesting.amount = vested;
+        vesting.revoked = true;
+
+        emit!(VestingRevokedEvent {
+            vesting: vesting.key(),
+            creator: vesting.creator,
+            destination: ctx.accounts.destination.key(),
+            amount: unvested,
+            vested,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 创建分批执行的SOL分发，并将分发总额托管到分发PDA
+     * @dev 分发PDA种子为 [b"distribution", creator, distribution_id(u64 LE)]，分发总额存放在分发PDA自身的lamports中。
+     * 按 bank_account 的手续费配置以SOL收取一次手续费，取消分发时不退还。
+     * 创建后通过 upload_distribution_entries 分块上传转账列表，上传完成后任何人都可以调用 crank_distribution 执行
+     * @param ctx 上下文
+     * @param distribution_id 分发ID，同一创建者下唯一
+     * @param total_entries 转账列表的总条数
+     * @param total_amount 分发总额，上传的转账金额之和必须等于该值
+     * @param max_fee 创建者愿意支付的最高手续费
+     */
+    pub fn create_distribution(
+        ctx: Context<CreateDistribution>,
+        distribution_id: u64,
+        total_entries: u32,
+        total_amount: u64,
+        max_fee: u64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.bank_account.paused, ErrorCode::Paused);
+        require!(total_entries > 0 && total_amount > 0, ErrorCode::InvalidDistributionConfig);
+
+        // 检查创建者余额是否足够支付分发总额和手续费
+        let fee = calculate_fee(&ctx.accounts.bank_account.fee_config, total_amount)?;
+        require!(fee <= max_fee, ErrorCode::FeeExceedsMaximum);
+        require!(
+            ctx.accounts.creator.lamports() >= safe_add(total_amount, fee)?,
+            ErrorCode::InsufficientFunds
+        );
+
+        // 扣除手续费
+        charge_sol_fee(
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.bank_account.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            fee,
+        )?;
+
+        // 将分发总额托管到分发PDA
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.creator.to_account_info(),
+                to: ctx.accounts.distribution.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, total_amount)?;
+
+        let distribution = &mut ctx.accounts.distribution;
+        distribution.creator = ctx.accounts.creator.key();
+        distribution.mint = None;
+        distribution.distribution_id = distribution_id;
+        distribution.total_entries = total_entries;
+        distribution.total_amount = total_amount;
+        distribution.bump = ctx.bumps.distribution;
+
+        emit!(DistributionCreatedEvent {
+            distribution: distribution.key(),
+            creator: distribution.creator,
+            mint: None,
+            distribution_id,
+            total_entries,
+            total_amount,
+            fee,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 创建分批执行的代币分发，并将分发总额托管到分发金库
+     * @dev 金库为分发PDA所有的PDA token账户，种子为 [b"distribution_vault", distribution]。
+     * 金库必须收到完整的分发总额，因此不支持实际收取转账手续费的Token-2022代币。
+     * 按 bank_account 的手续费配置以SOL收取一次手续费，取消分发时不退还。
+     * 代币配置了转账钩子时，钩子所需账户通过 remaining_accounts 传入
+     * @param ctx 上下文
+     * @param distribution_id 分发ID，同一创建者下唯一
+     * @param total_entries 转账列表的总条数
+     * @param total_amount 分发总额，上传的转账金额之和必须等于该值
+     * @param max_fee 创建者愿意支付的最高手续费
+     */
+    pub fn create_token_distribution<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateTokenDistribution<'info>>,
+        distribution_id: u64,
+        total_entries: u32,
+        total_amount: u64,
+        max_fee: u64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.bank_account.paused, ErrorCode::Paused);
+        require!(total_entries > 0 && total_amount > 0, ErrorCode::InvalidDistributionConfig);
+
+        // 检查创建者余额是否足够
+        require!(
+            ctx.accounts.creator_token_account.amount >= total_amount,
+            ErrorCode::InsufficientFunds
+        );
+        let fee = calculate_fee(&ctx.accounts.bank_account.fee_config, total_amount)?;
+        require!(fee <= max_fee, ErrorCode::FeeExceedsMaximum);
+        require!(ctx.accounts.creator.lamports() >= fee, ErrorCode::InsufficientFunds);
+
+        // 扣除手续费
+        charge_sol_fee(
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.bank_account.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            fee,
+        )?;
+
+        // 将分发总额转入分发金库，金库必须收到全额
+        spl_token_2022::onchain::invoke_transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.creator_token_account.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.creator.to_account_info(),
+            ctx.remaining_accounts,
+            total_amount,
+            ctx.accounts.mint.decimals,
+            &[],
+        )?;
+        ctx.accounts.vault.reload()?;
+        require!(
+            ctx.accounts.vault.amount == total_amount,
+            ErrorCode::InvalidDistributionConfig
+        );
+
+        let distribution = &mut ctx.accounts.distribution;
+        distribution.creator = ctx.accounts.creator.key();
+        distribution.mint = Some(ctx.accounts.mint.key());
+        distribution.distribution_id = distribution_id;
+        distribution.total_entries = total_entries;
+        distribution.total_amount = total_amount;
+        distribution.bump = ctx.bumps.distribution;
+
+        emit!(DistributionCreatedEvent {
+            distribution: distribution.key(),
+            creator: distribution.creator,
+            mint: distribution.mint,
+            distribution_id,
+            total_entries,
+            total_amount,
+            fee,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 创建者分块上传分发的转账列表
+     * @dev 转账列表按上传顺序追加存放在分发账户的固定头部之后，账户扩容所需的租金由创建者支付，
+     * 单次上传受账户单次扩容上限(10KB)限制。
+     * 上传的总条数不能超过 total_entries，金额之和不能超过 total_amount，
+     * 上传最后一块时金额之和必须恰好等于 total_amount。
+     * 代币分发的 recipient 为接收者的token账户，SOL分发的 recipient 为接收者钱包地址
+     * @param ctx 上下文
+     * @param entries 本次上传的转账信息
+     */
+    pub fn upload_distribution_entries(
+        ctx: Context<UploadDistributionEntries>,
+        entries: Vec<TransferInfo>,
+    ) -> Result<()> {
+        require!(!entries.is_empty(), ErrorCode::EmptyTransfers);
+
+        let distribution = &mut ctx.accounts.distribution;
+        let count = u32::try_from(entries.len()).map_err(|_| ErrorCode::InvalidDistributionEntries)?;
+        let uploaded_entries = distribution
+            .uploaded_entries
+            .checked_add(count)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            uploaded_entries <= distribution.total_entries,
+            ErrorCode::InvalidDistributionEntries
+        );
+        let uploaded_amount = safe_add(distribution.uploaded_amount, safe_sum_transfer_info(&entries)?)?;
+        require!(
+            uploaded_amount <= distribution.total_amount,
+            ErrorCode::InvalidDistributionEntries
+        );
+        require!(
+            uploaded_entries < distribution.total_entries || uploaded_amount == distribution.total_amount,
+            ErrorCode::InvalidDistributionEntries
+        );
+
+        // 账户扩容，新增租金由创建者支付；SOL分发的托管金额也存放在本账户，
+        // 因此按新旧大小的租金差额补足，而不是按当前余额计算
+        let distribution_info = distribution.to_account_info();
+        let new_space = Distribution::space(uploaded_entries);
+        let rent = Rent::get()?;
+        let top_up = safe_sub(
+            rent.minimum_balance(new_space),
+            rent.minimum_balance(distribution_info.data_len()),
+        )?;
+        if top_up > 0 {
+            let cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: distribution_info.clone(),
+                },
+            );
+            anchor_lang::system_program::transfer(cpi_context, top_up)?;
+        }
+        distribution_info.realloc(new_space, false)?;
+
+        // 追加写入转账列表
+        {
+            let mut data = distribution_info.try_borrow_mut_data()?;
+            let mut offset = Distribution::space(distribution.uploaded_entries);
+            for entry in &entries {
+                entry.serialize(&mut &mut data[offset..offset + Distribution::ENTRY_LEN])?;
+                offset += Distribution::ENTRY_LEN;
+            }
+        }
+
+        distribution.uploaded_entries = uploaded_entries;
+        distribution.uploaded_amount = uploaded_amount;
+
+        emit!(DistributionEntriesUploadedEvent {
+            distribution: distribution.key(),
+            count,
+            uploaded_entries,
+            uploaded_amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 从存储的游标开始执行分发中最多 max_items 笔转账，任何人都可以调用
+     * @dev 转账列表上传完成后才能执行，暂停期间拒绝执行。remaining_accounts 按本次执行的转账依次传入接收者账户；
+     * 代币配置了转账钩子时布局为 [钩子程序, ExtraAccountMetaList, 接收者0, 接收者0的额外账户..., 接收者1, ...]。
+     * 传入的账户与转账列表不一致或不可写时整笔交易失败；
+     * 接收者自身状态导致无法转账(新SOL账户低于租金豁免、token账户冻结或mint不一致等)时跳过该笔，
+     * 跳过的金额留在托管中，取消分发时退还创建者。游标到达末尾后分发完成。
+     * 代币分发需要传入 vault、mint 和 token_program，SOL分发省略这些账户
+     * @param ctx 上下文
+     * @param max_items 本次最多执行的转账笔数
+     */
+    pub fn crank_distribution<'info>(
+        ctx: Context<'_, '_, '_, 'info, CrankDistribution<'info>>,
+        max_items: u32,
+    ) -> Result<()> {
+        require!(!ctx.accounts.bank_account.paused, ErrorCode::Paused);
+        require!(max_items > 0, ErrorCode::EmptyTransfers);
+
+        let distribution = &ctx.accounts.distribution;
+        require!(
+            distribution.uploaded_entries == distribution.total_entries,
+            ErrorCode::DistributionNotReady
+        );
+        require!(
+            distribution.cursor < distribution.total_entries,
+            ErrorCode::DistributionCompleted
+        );
+
+        // 读取本次执行的转账，CPI 之前释放账户数据的借用
+        let start = distribution.cursor;
+        let end = start + max_items.min(distribution.total_entries - start);
+        let distribution_info = distribution.to_account_info();
+        let entries = {
+            let data = distribution_info.try_borrow_data()?;
+            (start..end)
+                .map(|i| {
+                    let offset = Distribution::space(i);
+                    TransferInfo::deserialize(&mut &data[offset..offset + Distribution::ENTRY_LEN])
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        };
+
+        let remaining_accounts = ctx.remaining_accounts;
+        let mut sent = Vec::with_capacity(entries.len());
+        let mut skipped = Vec::new();
+        let mut skipped_amount = 0u64;
+        match distribution.mint {
+            None => {
+                // SOL分发直接从分发PDA扣减lamports
+                let rent = Rent::get()?;
+                for (i, entry) in entries.iter().enumerate() {
+                    let index = start as usize + i;
+                    let recipient = take_accounts(remaining_accounts, i, 1)?;
+                    match check_sol_recipient(&recipient[0], entry, &rent) {
+                        Ok(()) => {}
+                        Err(ErrorCode::InvalidRecipient) => {
+                            return Err(transfer_error(ErrorCode::InvalidRecipient, index));
+                        }
+                        Err(error_code) => {
+                            skipped.push(SkippedTransfer::new(index, error_code));
+                            skipped_amount = safe_add(skipped_amount, entry.amount)?;
+                            continue;
+                        }
+                    }
+                    **distribution_info.try_borrow_mut_lamports()? =
+                        safe_sub(distribution_info.lamports(), entry.amount)?;
+                    **recipient[0].try_borrow_mut_lamports()? = safe_add(recipient[0].lamports(), entry.amount)?;
+                    sent.push(entry);
+                }
+            }
+            Some(mint_key) => {
+                let (Some(vault), Some(mint), Some(token_program)) =
+                    (&ctx.accounts.vault, &ctx.accounts.mint, &ctx.accounts.token_program)
+                else {
+                    return Err(ErrorCode::InvalidDistributionAccounts.into());
+                };
+                require_keys_eq!(mint.key(), mint_key, ErrorCode::InvalidDistributionAccounts);
+
+                // 解析转账钩子账户，未配置钩子的代币不需要额外账户
+                let mint_info = mint.to_account_info();
+                let (hook_accounts, extras_per_transfer) = parse_hook_accounts(&mint_info, remaining_accounts)?;
+                let mut offset = hook_accounts.len();
+
+                let id_bytes = distribution.distribution_id.to_le_bytes();
+                let signer_seeds: &[&[&[u8]]] = &[&[
+                    b"distribution",
+                    distribution.creator.as_ref(),
+                    &id_bytes,
+                    &[distribution.bump],
+                ]];
+                for (i, entry) in entries.iter().enumerate() {
+                    let index = start as usize + i;
+                    let recipient_token_account = take_accounts(remaining_accounts, offset, 1)?;
+                    let extras = take_accounts(remaining_accounts, offset + 1, extras_per_transfer)?;
+                    offset += 1 + extras_per_transfer;
+
+                    let recipient = &recipient_token_account[0];
+                    if recipient.key() != entry.recipient || !recipient.is_writable {
+                        return Err(transfer_error(ErrorCode::InvalidRecipient, index));
+                    }
+                    if let Err(error_code) =
+                        check_recipient_token_account(recipient, token_program.key, &mint_key, &vault.key())
+                    {
+                        skipped.push(SkippedTransfer::new(index, error_code));
+                        skipped_amount = safe_add(skipped_amount, entry.amount)?;
+                        continue;
+                    }
+
+                    // 分发PDA签名，从分发金库转出
+                    spl_token_2022::onchain::invoke_transfer_checked(
+                        token_program.key,
+                        vault.to_account_info(),
+                        mint_info.clone(),
+                        recipient.clone(),
+                        distribution_info.clone(),
+                        &[hook_accounts, extras].concat(),
+                        entry.amount,
+                        mint.decimals,
+                        signer_seeds,
+                    )?;
+                    sent.push(entry);
+                }
+            }
+        }
+
+        let amount = sent.iter().try_fold(0u64, |acc, info| safe_add(acc, info.amount))?;
+        let distribution = &mut ctx.accounts.distribution;
+        distribution.cursor = end;
+        distribution.distributed_amount = safe_add(distribution.distributed_amount, amount)?;
+        distribution.skipped_amount = safe_add(distribution.skipped_amount, skipped_amount)?;
+
+        emit!(DistributionCrankedEvent {
+            distribution: distribution.key(),
+            recipients: sent.iter().map(|info| info.recipient).collect(),
+            amounts: sent.iter().map(|info| info.amount).collect(),
+            amount,
+            skipped,
+            cursor: end,
+            completed: end == distribution.total_entries,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 创建者取消分发，退还尚未发出的托管金额并关闭分发账户
+     * @dev 任何时候都可以取消，包括分发完成后取回被跳过的金额和账户租金；手续费不退还。
+     * SOL分发的托管金额和租金随分发账户一起退还创建者；
+     * 代币分发需要传入 vault、mint、destination 和 token_program，剩余代币转入 destination，
+     * 金库关闭后租金退还创建者，代币配置了转账钩子时钩子所需账户通过 remaining_accounts 传入
+     * @param ctx 上下文
+     */
+    pub fn cancel_distribution<'info>(ctx: Context<'_, '_, '_, 'info, CancelDistribution<'info>>) -> Result<()> {
+        let distribution = &ctx.accounts.distribution;
+        let refunded = safe_sub(distribution.total_amount, distribution.distributed_amount)?;
+
+        if let Some(mint_key) = distribution.mint {
+            let (Some(vault), Some(mint), Some(destination), Some(token_program)) = (
+                &ctx.accounts.vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.destination,
+                &ctx.accounts.token_program,
+            ) else {
+                return Err(ErrorCode::InvalidDistributionAccounts.into());
+            };
+            require_keys_eq!(mint.key(), mint_key, ErrorCode::InvalidDistributionAccounts);
+            require_keys_eq!(destination.mint, mint_key, ErrorCode::InvalidDistributionAccounts);
+
+            let id_bytes = distribution.distribution_id.to_le_bytes();
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                b"distribution",
+                distribution.creator.as_ref(),
+                &id_bytes,
+                &[distribution.bump],
+            ]];
+            if vault.amount > 0 {
+                spl_token_2022::onchain::invoke_transfer_checked(
+                    token_program.key,
+                    vault.to_account_info(),
+                    mint.to_account_info(),
+                    destination.to_account_info(),
+                    distribution.to_account_info(),
+                    ctx.remaining_accounts,
+                    vault.amount,
+                    mint.decimals,
+                    signer_seeds,
+                )?;
+            }
+
+            // 关闭金库，租金退还创建者
+            anchor_spl::token_interface::close_account(CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                anchor_spl::token_interface::CloseAccount {
+                    account: vault.to_account_info(),
+                    destination: ctx.accounts.creator.to_account_info(),
+                    authority: distribution.to_account_info(),
+                },
+                signer_seeds,
+            ))?;
+        }
+
+        emit!(DistributionCancelledEvent {
+            distribution: distribution.key(),
+            creator: distribution.creator,
+            refunded,
+            distributed_amount: distribution.distributed_amount,
+            cursor: distribution.cursor,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 创建由本程序所有的资金库
+     * @dev 资金库PDA种子为 [b"vault", owner, vault_id(u64 LE)]，SOL存放在资金库PDA自身的lamports中，
+     * 每种代币存放在资金库所有的 [b"vault_token", vault, mint] PDA token账户中
+     * @param ctx 上下文
+     * @param vault_id 资金库ID，同一所有者下唯一
+     */
+    pub fn create_vault(ctx: Context<CreateVault>, vault_id: u64) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.owner = ctx.accounts.owner.key();
+        vault.vault_id = vault_id;
+        vault.bump = ctx.bumps.vault;
+
+        emit!(VaultCreatedEvent {
+            vault: vault.key(),
+            owner: vault.owner,
+            vault_id,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 向资金库存入SOL
+     * @param ctx 上下文
+     * @param amount 存入金额(lamports)
+     */
+    pub fn deposit_vault_sol(ctx: Context<DepositVaultSol>, amount: u64) -> Result<()> {
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.depositor.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, amount)?;
+
+        emit!(VaultDepositedEvent {
+            vault: ctx.accounts.vault.key(),
+            depositor: ctx.accounts.depositor.key(),
+            mint: None,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 向资金库存入代币，资金库的该代币账户不存在时由存入者支付租金创建
+     * @dev 代币配置了转账钩子时，钩子所需账户通过 remaining_accounts 传入
+     * @param ctx 上下文
+     * @param amount 存入数量
+     */
+    pub fn deposit_vault_token<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositVaultToken<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        spl_token_2022::onchain::invoke_transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.depositor_token_account.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.vault_token_account.to_account_info(),
+            ctx.accounts.depositor.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            ctx.accounts.mint.decimals,
+            &[],
+        )?;
+
+        emit!(VaultDepositedEvent {
+            vault: ctx.accounts.vault.key(),
+            depositor: ctx.accounts.depositor.key(),
+            mint: Some(ctx.accounts.mint.key()),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 所有者从资金库提取SOL，资金库保留租金豁免所需的最低余额
+     * @param ctx 上下文
+     * @param amount 提取金额(lamports)
+     */
+    pub fn withdraw_vault_sol(ctx: Context<WithdrawVaultSol>, amount: u64) -> Result<()> {
+        let vault_info = ctx.accounts.vault.to_account_info();
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(vault_info.data_len());
+        require!(
+            amount <= vault_info.lamports().saturating_sub(rent_exempt_minimum),
+            ErrorCode::InsufficientFunds
+        );
+
+        // 资金库由本程序所有，可以直接扣减lamports
+        let owner_info = ctx.accounts.owner.to_account_info();
+        **vault_info.try_borrow_mut_lamports()? = safe_sub(vault_info.lamports(), amount)?;
+        **owner_info.try_borrow_mut_lamports()? = safe_add(owner_info.lamports(), amount)?;
+
+        emit!(VaultWithdrawnEvent {
+            vault: vault_info.key(),
+            owner: owner_info.key(),
+            mint: None,
+            destination: owner_info.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 所有者从资金库提取代币
+     * @dev 代币配置了转账钩子时，钩子所需账户通过 remaining_accounts 传入
+     * @param ctx 上下文
+     * @param amount 提取数量
+     */
+    pub fn withdraw_vault_token<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawVaultToken<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.vault_token_account.amount >= amount,
+            ErrorCode::InsufficientFunds
+        );
+
+        let vault = &ctx.accounts.vault;
+        let id_bytes = vault.vault_id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"vault", vault.owner.as_ref(), &id_bytes, &[vault.bump]]];
+        spl_token_2022::onchain::invoke_transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.vault_token_account.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.destination.to_account_info(),
+            vault.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            ctx.accounts.mint.decimals,
+            signer_seeds,
+        )?;
+
+        emit!(VaultWithdrawnEvent {
+            vault: vault.key(),
+            owner: vault.owner,
+            mint: Some(ctx.accounts.mint.key()),
+            destination: ctx.accounts.destination.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 所有者为资金库登记操作员及其额度
+     * @dev 操作员账户PDA种子为 [b"vault_operator", vault, operator]。
+     * 每个资产(SOL或某个代币)一条额度，未登记的资产不允许操作员转出；
+     * 额度包括单批转账总额上限和滚动24小时窗口内的转账总额上限(按整点小时分桶，见 OperatorAllowance)，手续费不计入额度。
+     * 修改额度需先撤销再重新登记
+     * @param ctx 上下文
+     * @param operator 操作员地址
+     * @param limits 各资产的额度配置
+     */
+    pub fn add_vault_operator(
+        ctx: Context<AddVaultOperator>,
+        operator: Pubkey,
+        limits: Vec<OperatorLimit>,
+    ) -> Result<()> {
+        validate_operator_limits(&limits)?;
+
+        let vault_operator = &mut ctx.accounts.vault_operator;
+        vault_operator.vault = ctx.accounts.vault.key();
+        vault_operator.operator = operator;
+        vault_operator.allowances = limits.iter().copied().map(OperatorAllowance::new).collect();
+        vault_operator.bump = ctx.bumps.vault_operator;
+
+        emit!(VaultOperatorAddedEvent {
+            vault: vault_operator.vault,
+            operator,
+            limits,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 所有者撤销操作员，关闭操作员账户并将租金退还所有者
+     * @dev 任何时候都可以撤销，包括暂停期间
+     * @param ctx 上下文
+     */
+    pub fn revoke_vault_operator(ctx: Context<RevokeVaultOperator>) -> Result<()> {
+        emit!(VaultOperatorRevokedEvent {
+            vault: ctx.accounts.vault.key(),
+            operator: ctx.accounts.vault_operator.operator,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 操作员从资金库批量转账SOL
+     * @dev 转账总额计入操作员的SOL额度，超过单批上限或24小时上限时整批失败。
+     * 手续费按 bank_account 的手续费配置计算，同样从资金库扣除；
+     * 资金库须在转账后保留租金豁免所需的最低余额。remaining_accounts[i] 为第i笔转账的接收者
+     * @param ctx 上下文
+     * @param transfers 转账信息数组
+     * @param max_fee 操作员愿意支付的最高手续费
+     */
+    pub fn vault_batch_transfer_sol<'info>(
+        ctx: Context<'_, '_, '_, 'info, VaultBatchTransferSol<'info>>,
+        transfers: Vec<TransferInfo>,
+        max_fee: u64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.bank_account.paused, ErrorCode::Paused);
+        require!(!transfers.is_empty(), ErrorCode::EmptyTransfers);
+
+        // 校验接收者
+        let rent = Rent::get()?;
+        let recipients = take_accounts(ctx.remaining_accounts, 0, transfers.len())?;
+        for (i, (transfer_info, recipient)) in transfers.iter().zip(recipients).enumerate() {
+            check_sol_recipient(recipient, transfer_info, &rent).map_err(|error_code| transfer_error(error_code, i))?;
+        }
+
+        // 按操作员额度记账
+        let total_amount = safe_sum_transfer_info(&transfers)?;
+        ctx.accounts
+            .vault_operator
+            .consume(None, total_amount, Clock::get()?.unix_timestamp)?;
+
+        // 检查资金库余额是否足够
+        let fee = calculate_fee(&ctx.accounts.bank_account.fee_config, total_amount)?;
+        require!(fee <= max_fee, ErrorCode::FeeExceedsMaximum);
+        let vault_info = ctx.accounts.vault.to_account_info();
+        let available = vault_info.lamports().saturating_sub(rent.minimum_balance(vault_info.data_len()));
+        require!(available >= safe_add(total_amount, fee)?, ErrorCode::InsufficientFunds);
+
+        // 资金库由本程序所有，直接扣减lamports支付手续费和转账
+        let bank_account_info = ctx.accounts.bank_account.to_account_info();
+        **vault_info.try_borrow_mut_lamports()? = safe_sub(vault_info.lamports(), fee)?;
+        **bank_account_info.try_borrow_mut_lamports()? = safe_add(bank_account_info.lamports(), fee)?;
+        for (transfer_info, recipient) in transfers.iter().zip(recipients) {
+            **vault_info.try_borrow_mut_lamports()? = safe_sub(vault_info.lamports(), transfer_info.amount)?;
+            **recipient.try_borrow_mut_lamports()? = safe_add(recipient.lamports(), transfer_info.amount)?;
+        }
+
+        emit!(VaultTransferEvent {
+            vault: vault_info.key(),
+            operator: ctx.accounts.operator.key(),
+            mint: None,
+            recipients: transfers.iter().map(|info| info.recipient).collect(),
+            amounts: transfers.iter().map(|info| info.amount).collect(),
+            total_amount,
+            fee,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @notice 操作员从资金库批量转账代币，由资金库PDA签名
+     * @dev 转账总额计入操作员该代币的额度，超过单批上限或24小时上限时整批失败。
+     * 手续费按 bank_account 的手续费配置以SOL从资金库扣除。
+     * remaining_accounts 的布局与 batch_transfer_token 相同(不含代币手续费金库的额外账户)
+     * @param ctx 上下文
+     * @param transfers 转账信息数组
+     * @param max_fee 操作员愿意支付的最高手续费
+     */
+    pub fn vault_batch_transfer_token<'info>(
+        ctx: Context<'_, '_, '_, 'info, VaultBatchTransferToken<'info>>,
+        transfers: Vec<TransferInfo>,
+        max_fee: u64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.bank_account.paused, ErrorCode::Paused);
+        require!(!transfers.is_empty(), ErrorCode::EmptyTransfers);
+
+        // 解析转账钩子账户，未配置钩子的代币不需要额外账户
+        let remaining_accounts = ctx.remaining_accounts;
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let (hook_accounts, extras_per_transfer) = parse_hook_accounts(&mint_info, remaining_accounts)?;
+        let mut offset = hook_accounts.len();
+
+        // 校验接收者是与资金库同一mint的可用token账户
+        let mut recipients = Vec::with_capacity(transfers.len());
+        for (i, transfer_info) in transfers.iter().enumerate() {
+            let recipient_token_account = take_accounts(remaining_accounts, offset, 1)?;
+            let extras = take_accounts(remaining_accounts, offset + 1, extras_per_transfer)?;
+            offset += 1 + extras_per_transfer;
+
+            if recipient_token_account[0].key() != transfer_info.recipient {
+                return Err(transfer_error(ErrorCode::InvalidRecipient, i));
+            }
+            validate_recipient_token_account(
+                &recipient_token_account[0],
+                ctx.accounts.token_program.key,
+                &ctx.accounts.mint.key(),
+                &ctx.accounts.vault_token_account.key(),
+                i,
+            )?;
+            recipients.push((&recipient_token_account[0], extras));
+        }
+
+        // 按操作员额度记账
+        let total_amount = safe_sum_transfer_info(&transfers)?;
+        ctx.accounts.vault_operator.consume(
+            Some(ctx.accounts.mint.key()),
+            total_amount,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        // 检查资金库代币余额和支付手续费的SOL余额是否足够
+        require!(
+            ctx.accounts.vault_token_account.amount >= total_amount,
+            ErrorCode::InsufficientFunds
+        );
+        let fee = calculate_fee(&ctx.accounts.bank_account.fee_config, total_amount)?;
+        require!(fee <= max_fee, ErrorCode::FeeExceedsMaximum);
+        let vault_info = ctx.accounts.vault.to_account_info();
+        let available = vault_info
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(vault_info.data_len()));
+        require!(available >= fee, ErrorCode::InsufficientFunds);
+
+        // 资金库PDA签名，从资金库的代币账户转出
+        let vault = &ctx.accounts.vault;
+        let id_bytes = vault.vault_id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"vault", vault.owner.as_ref(), &id_bytes, &[vault.bump]]];
+        for (transfer_info, (recipient_token_account, extras)) in transfers.iter().zip(&recipients) {
+            spl_token_2022::onchain::invoke_transfer_checked(
+                ctx.accounts.token_program.key,
+                ctx.accounts.vault_token_account.to_account_info(),
+                mint_info.clone(),
+                (*recipient_token_account).clone(),
+                vault_info.clone(),
+                &[hook_accounts, extras].concat(),
+                transfer_info.amount,
+                ctx.accounts.mint.decimals,
+                signer_seeds,
+            )?;
+        }
+
+        // 资金库由本程序所有，转账完成后直接扣减lamports支付手续费
+        let bank_account_info = ctx.accounts.bank_account.to_account_info();
+        **vault_info.try_borrow_mut_lamports()? = safe_sub(vault_info.lamports(), fee)?;
+        **bank_account_info.try_borrow_mut_lamports()? = safe_add(bank_account_info.lamports(), fee)?;
+
+        emit!(VaultTransferEvent {
+            vault: vault.key(),
+            operator: ctx.accounts.operator.key(),
+            mint: Some(ctx.accounts.mint.key()),
+            recipients: transfers.iter().map(|info| info.recipient).collect(),
+            amounts: transfers.iter().map(|info| info.amount).collect(),
+            total_amount,
+            fee,
+        });
+
+        Ok(())
+    }
+}
+
+/**
+ * @notice 手续费配置
+ * @dev 手续费 = flat_fee + 转账总额 * bps / 10000，结果不低于 min_fee，
+ * 且在 max_fee 不为0时不高于 max_fee。SOL批量转账按lamports计算转账总额，
+ * SPL Token批量转账按代币最小单位计算转账总额，手续费默认以lamports收取；
+ * 用作 MintFeeConfig 时手续费以代币收取，所有金额均为代币最小单位。
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct FeeConfig {
+    pub flat_fee: u64, // 固定手续费(lamports)
+    pub bps: u16,      // 按转账总额收取的基点，10000为100%
+    pub min_fee: u64,  // 最低手续费(lamports)
+    pub max_fee: u64,  // 最高手续费(lamports)，0表示不设上限
+}
+
+impl FeeConfig {
+    pub const LEN: usize = 8 + 2 + 8 + 8;
+}
+
+/// 配置变更延迟的上限(30天)，防止设置过长的延迟导致配置无法再修改
+pub const MAX_CONFIG_DELAY: i64 = 30 * 24 * 60 * 60;
+/// 初始化(或迁移)银行账户时的默认配置变更延迟(1天)，部署后立即受时间锁保护
+pub const DEFAULT_CONFIG_DELAY: i64 = 24 * 60 * 60;
+
+/**
+ * @notice 受时间锁保护的银行账户配置
+ * @dev 通过 queue_config_change 提交、execute_config_change 生效
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct BankConfig {
+    pub fee_config: FeeConfig,           // 手续费配置
+    pub config_delay: i64,               // 配置变更需要提前公告的秒数
+    pub allow_fee_decrease_bypass: bool, // 只降低手续费的变更是否可以跳过延迟立即生效
+}
+
+impl BankConfig {
+    pub const LEN: usize = FeeConfig::LEN + 8 + 1;
+}
+
+/**
+ * @notice 待生效的配置变更
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct PendingConfigChange {
+    pub config: BankConfig, // 新配置
+    pub eta: i64,           // 生效时间(unix时间戳)
+}
+
+impl PendingConfigChange {
+    pub const LEN: usize = BankConfig::LEN + 8;
+}
+
+/**
+ * @notice 待生效的代币手续费配置变更
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct PendingMintFeeConfigChange {
+    pub fee_config: FeeConfig, // 新的手续费配置
+    pub eta: i64,              // 生效时间(unix时间戳)
+}
+
+impl PendingMintFeeConfigChange {
+    pub const LEN: usize = FeeConfig::LEN + 8;
+}
+
+/**
+ * @notice 单个代币的代币手续费配置
+ * @dev 通过 queue_mint_fee_config_change 提交、execute_mint_fee_config_change 生效；
+ * 已有生效的配置时批量转账该代币必须提供手续费金库，手续费以代币收取并转入金库
+ */
+#[account]
+#[derive(Default)]
+pub struct MintFeeConfig {
+    pub mint: Pubkey,                  // 代币mint地址
+    pub fee_config: Option<FeeConfig>, // 当前生效的手续费配置，金额以代币最小单位计算；首次提交的变更生效前为空
+    pub pending_config: Option<PendingMintFeeConfigChange>, // 待生效的配置变更
+}
+
+impl MintFeeConfig {
+    pub const LEN: usize = 32 + (1 + FeeConfig::LEN) + (1 + PendingMintFeeConfigChange::LEN);
+}
+
+#[account]
+#[derive(Default)]
+pub struct BankAccount {
+    pub admin: Pubkey, // 管理员地址
+    pub fee_config: FeeConfig, // 手续费配置
+    pub is_initialized: bool, // 是否已初始化
+    pub pending_admin: Option<Pubkey>, // 待接受的新管理员地址
+    pub paused: bool, // 是否已暂停，暂停期间拒绝批量转账、创建空投和锁仓
+    pub version: u8, // 账户布局版本
+    pub config_delay: i64, // 配置变更需要提前公告的秒数
+    pub allow_fee_decrease_bypass: bool, // 只降低手续费的变更是否可以跳过延迟立即生效
+    pub pending_config: Option<PendingConfigChange>, // 待生效的配置变更
+}
+
+impl BankAccount {
+    pub const LEN: usize = 32 + FeeConfig::LEN + 1 + (1 + 32) + 1 + 1 + 8 + 1 + (1 + PendingConfigChange::LEN);
+    /// 当前账户布局版本；0 表示加入版本号之前创建的账户
+    pub const VERSION: u8 = 2;
+    /// 最初的账户布局：admin(32) + fee(u64) + is_initialized(1)，不含8字节鉴别符
+    pub const LEGACY_LEN: usize = 32 + 8 + 1;
+
+    /// 当前生效的配置
+    pub fn config(&self) -> BankConfig {
+        BankConfig {
+            fee_config: self.fee_config,
+            config_delay: self.config_delay,
+            allow_fee_decrease_bypass: self.allow_fee_decrease_bypass,
+        }
+    }
+}
+
+/**
+ * @notice Merkle空投分发器
+ * @dev PDA种子为 [b"distributor", creator, mint, merkle_root]，金库为分发器所有的PDA token账户
+ */
+#[account]
+pub struct MerkleDistributor {
+    pub creator: Pubkey,        // 创建者地址
+    pub mint: Pubkey,           // 代币mint地址
+    pub vault: Pubkey,          // 金库token账户
+    pub merkle_root: [u8; 32],  // Merkle树根
+    pub total: u64,             // 空投总额
+    pub claimed: u64,           // 已领取数量
+    pub expiry: i64,            // 领取截止时间(unix时间戳)
+    pub clawed_back: bool,      // 创建者是否已收回剩余代币
+    pub bump: u8,               // PDA bump
+}
+
+impl MerkleDistributor {
+    pub const LEN: usize = 32 + 32 + 32 + 32 + 8 + 8 + 8 + 1 + 1;
+}
+
+/// 每个领取位图账户的字节数，每个账户记录 CLAIM_BITMAP_BYTES * 8 个序号的领取状态
+pub const CLAIM_BITMAP_BYTES: usize = 256;
+
+/**
+ * @notice 分发器的领取位图分块
+ * @dev PDA种子为 [b"claim_bitmap", distributor, chunk(u32 LE)]，第 chunk 块记录序号
+ * [chunk * CLAIM_BITMAP_BYTES * 8, (chunk + 1) * CLAIM_BITMAP_BYTES * 8) 的领取状态
+ */
+#[account]
+pub struct ClaimBitmap {
+    pub distributor: Pubkey,             // 所属分发器
+    pub chunk: u32,                      // 分块序号
+    pub bits: [u8; CLAIM_BITMAP_BYTES], // 领取状态位图
+}
+
+impl ClaimBitmap {
+    pub const LEN: usize = 32 + 4 + CLAIM_BITMAP_BYTES;
+}
+
+/**
+ * @notice 线性释放的代币锁仓
+ * @dev PDA种子为 [b"vesting", creator, mint, recipient, start(i64 LE)]，锁仓代币存放在锁仓PDA的ATA中。
+ * cliff 之前释放数量为0，cliff 之后按 (当前时间 - start) / (end - start) 线性释放，end 之后全部释放
+ */
+#[account]
+pub struct VestingAccount {
+    pub creator: Pubkey,   // 创建者地址
+    pub recipient: Pubkey, // 接收者地址
+    pub mint: Pubkey,      // 代币mint地址
+    pub vault: Pubkey,     // 锁仓ATA
+    pub start: i64,        // 开始释放时间(unix时间戳)
+    pub cliff: i64,        // 悬崖时间，此前不可提取
+    pub end: i64,          // 全部释放时间
+    pub amount: u64,       // 锁仓总量，撤销后为撤销时已释放的数量
+    pub withdrawn: u64,    // 已提取数量
+    pub revocable: bool,   // 创建者是否可以撤销
+    pub revoked: bool,     // 是否已撤销
+    pub bump: u8,          // PDA bump
+}
+
+impl VestingAccount {
+    pub const LEN: usize = 32 * 4 + 8 * 3 + 8 * 2 + 1 + 1 + 1;
+
+    /// 截至 now 已释放的数量，已撤销的锁仓全部释放
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        if self.revoked || now >= self.end {
+            return self.amount;
+        }
+        if now < self.cliff {
+            return 0;
+        }
+        // 在 i128 中计算时间差，start/end 为 i64 极端值时也不会溢出；此处 start <= now < end，两个差值都为正且不超过 u64 范围
+        let elapsed = (i128::from(now) - i128::from(self.start)) as u128;
+        let duration = (i128::from(self.end) - i128::from(self.start)) as u128;
+        (u128::from(self.amount) * elapsed / duration) as u64
+    }
+}
+
+/**
+ * @notice 批次回执，记录某个发送者已执行的批次
+ * @dev PDA种子为 [b"batch_receipt", sender, batch_id]
+ */
+#[account]
+pub struct BatchReceipt {
+    pub sender: Pubkey,     // 发送者地址
+    pub batch_id: [u8; 32], // 批次ID
+    pub executed_at: i64,   // 执行时间(unix时间戳)
+    pub bump: u8,           // PDA bump
+}
+
+impl BatchReceipt {
+    pub const LEN: usize = 32 + 32 + 8 + 1;
+}
+
+/**
+ * @notice 分批执行的分发
+ * @dev PDA种子为 [b"distribution", creator, distribution_id(u64 LE)]。
+ * 转账列表不反序列化到结构体中，而是以 TransferInfo 的borsh格式(每条 ENTRY_LEN 字节)
+ * 依次存放在 8 + LEN 之后，执行时按游标只读取需要的部分。
+ * SOL分发的托管金额存放在本账户的lamports中，代币分发存放在 [b"distribution_vault", distribution] 金库中
+ */
+#[account]
+pub struct Distribution {
+    pub creator: Pubkey,          // 创建者地址
+    pub mint: Option<Pubkey>,     // 代币mint地址，SOL分发为 None
+    pub distribution_id: u64,     // 分发ID
+    pub total_entries: u32,       // 转账列表总条数
+    pub uploaded_entries: u32,    // 已上传条数
+    pub cursor: u32,              // 下一笔待执行的转账序号
+    pub total_amount: u64,        // 分发总额
+    pub uploaded_amount: u64,     // 已上传的转账金额之和
+    pub distributed_amount: u64,  // 已发出的金额
+    pub skipped_amount: u64,      // 被跳过的金额
+    pub bump: u8,                 // PDA bump
+}
+
+impl Distribution {
+    pub const LEN: usize = 32 + (1 + 32) + 8 + 4 * 3 + 8 * 4 + 1;
+    /// 每条转账信息的字节数：recipient(32) + amount(u64 LE)
+    pub const ENTRY_LEN: usize = 32 + 8;
+
+    /// 存放 entries 条转账信息时的账户大小，也是第 entries 条转账信息的起始位置
+    pub fn space(entries: u32) -> usize {
+        8 + Self::LEN + entries as usize * Self::ENTRY_LEN
+    }
+}
+
+/**
+ * @notice 由本程序所有的资金库
+ * @dev PDA种子为 [b"vault", owner, vault_id(u64 LE)]。SOL存放在本账户的lamports中，
+ * 代币存放在 [b"vault_token", vault, mint] PDA token账户中，均由资金库PDA签名转出
+ */
+#[account]
+pub struct Vault {
+    pub owner: Pubkey,    // 所有者地址
+    pub vault_id: u64,    // 资金库ID
+    pub bump: u8,         // PDA bump
+}
+
+impl Vault {
+    pub const LEN: usize = 32 + 8 + 1;
+}
+
+/// 操作员滚动24小时额度的窗口按整点小时分桶，窗口为当前小时及之前的23个小时
+pub const OPERATOR_WINDOW_BUCKETS: usize = 24;
+/// 每个分桶覆盖的秒数
+pub const OPERATOR_BUCKET_SECONDS: i64 = 3600;
+/// 每个操作员最多登记的资产额度条数
+pub const MAX_OPERATOR_LIMITS: usize = 8;
+
+/**
+ * @notice 操作员对单个资产的额度及其使用记录
+ * @dev daily_cap 限制的是按小时粒度滚动的24小时窗口，而不是自然日：按小时分桶记录转出总额，
+ * buckets[h % 24] 记录第 h 小时(unix时间戳 / 3600)的转出总额，最近记账的小时为 last_bucket；
+ * 统计时只累加最近24个小时(含当前小时)的分桶。一笔转出从其所在小时的整点起算满24小时后滑出窗口，
+ * 因此实际占用额度的时长在23到24小时之间，取决于转出发生在该小时内的时刻
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OperatorAllowance {
+    pub limit: OperatorLimit,                         // 额度配置
+    pub last_bucket: i64,                             // 最近一次记账所在的小时
+    pub buckets: [u64; OPERATOR_WINDOW_BUCKETS],      // 按小时记录的转出总额
+}
+
+impl OperatorAllowance {
+    pub const LEN: usize = OperatorLimit::LEN + 8 + 8 * OPERATOR_WINDOW_BUCKETS;
+
+    pub fn new(limit: OperatorLimit) -> Self {
+        Self {
+            limit,
+            last_bucket: 0,
+            buckets: [0; OPERATOR_WINDOW_BUCKETS],
+        }
+    }
+
+    /// 截至 now 的最近24小时(含当前小时)内已转出的总额
+    pub fn spent_in_window(&self, now: i64) -> u64 {
+        let oldest = now.div_euclid(OPERATOR_BUCKET_SECONDS) - (OPERATOR_WINDOW_BUCKETS as i64 - 1);
+        let first = oldest.max(self.last_bucket - (OPERATOR_WINDOW_BUCKETS as i64 - 1));
+        (first..=self.last_bucket).fold(0u64, |acc, hour| {
+            acc.saturating_add(self.buckets[hour.rem_euclid(OPERATOR_WINDOW_BUCKETS as i64) as usize])
+        })
+    }
+
+    /// 校验并记录一批转出的总额：不超过单批上限，且加上最近24小时已转出的总额后不超过24小时上限
+    pub fn consume(&mut self, amount: u64, now: i64) -> std::result::Result<(), ErrorCode> {
+        if amount > self.limit.max_per_batch {
+            return Err(ErrorCode::OperatorBatchLimitExceeded);
+        }
+        let spent = self.spent_in_window(now);
+        if spent.checked_add(amount).is_none_or(|total| total > self.limit.daily_cap) {
+            return Err(ErrorCode::OperatorDailyCapExceeded);
+        }
+
+        // 清空滑出窗口的分桶后记入当前小时
+        let current = now.div_euclid(OPERATOR_BUCKET_SECONDS);
+        if current > self.last_bucket {
+            let first = (self.last_bucket + 1).max(current - (OPERATOR_WINDOW_BUCKETS as i64 - 1));
+            for hour in first..=current {
+                self.buckets[hour.rem_euclid(OPERATOR_WINDOW_BUCKETS as i64) as usize] = 0;
+            }
+            self.last_bucket = current;
+        }
+        let bucket = &mut self.buckets[self.last_bucket.rem_euclid(OPERATOR_WINDOW_BUCKETS as i64) as usize];
+        *bucket = bucket.saturating_add(amount);
+        Ok(())
+    }
+}
+
+/**
+ * @notice 资金库的操作员
+ * @dev PDA种子为 [b"vault_operator", vault, operator]，撤销时关闭账户
+ */
+#[account]
+pub struct VaultOperator {
+    pub vault: Pubkey,                        // 所属资金库
+    pub operator: Pubkey,                     // 操作员地址
+    pub allowances: Vec<OperatorAllowance>,   // 各资产的额度，未登记的资产不允许转出
+    pub bump: u8,                             // PDA bump
+}
+
+impl VaultOperator {
+    /// 登记 limits 条额度时的账户大小
+    pub fn space(limits: usize) -> usize {
+        8 + 32 + 32 + 4 + limits * OperatorAllowance::LEN + 1
+    }
+
+    /// 按资产(None 为SOL)查找额度并记录一批转出
+    pub fn consume(&mut self, mint: Option<Pubkey>, amount: u64, now: i64) -> std::result::Result<(), ErrorCode> {
+        self.allowances
+            .iter_mut()
+            .find(|allowance| allowance.limit.mint == mint)
+            .ok_or(ErrorCode::OperatorMintNotAllowed)?
+            .consume(amount, now)
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = deployer,
+        space = 8 + BankAccount::LEN,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    #[account(mut)]
+    pub deployer: Signer<'info>,
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    pub new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAdminTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateBankAccount<'info> {
+    /// CHECK: 旧布局无法按当前结构反序列化，在指令中校验所有者和鉴别符后手动解析
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFee<'info> {
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    pub admin: Signer<'info>,
+    /// CHECK: 手续费接收账户，由管理员指定，仅接收lamports
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BatchTransferSol<'info> {
+    /// CHECK: 发送者账户，必须是签名者且可变
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: 批次回执，提供 batch_id 时传入，地址和状态在指令中校验
+    #[account(mut)]
+    pub batch_receipt: Option<UncheckedAccount<'info>>,
+    /// 可选的手续费支付者，提供时代替发送者支付手续费
+    #[account(mut)]
+    pub fee_payer: Option<Signer<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseBatchReceipts<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BatchTransferToken<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    /// 发送者必须是该token账户的所有者或代理人(delegate)
+    #[account(
+        mut,
+        constraint = token_account.owner == sender.key()
+            || token_account.delegate == COption::Some(sender.key()),
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: 该代币的代币手续费配置PDA，始终传入；已有生效的配置时以代币收取手续费，必须同时提供 fee_vault
+    #[account(
+        seeds = [b"mint_fee_config", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_fee_config: UncheckedAccount<'info>,
+    /// 以代币收取手续费时提供
+    #[account(
+        mut,
+        seeds = [b"fee_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub fee_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: 批次回执，提供 batch_id 时传入，地址和状态在指令中校验
+    #[account(mut)]
+    pub batch_receipt: Option<UncheckedAccount<'info>>,
+    /// 可选的手续费支付者，提供时代替发送者支付以SOL收取的手续费
+    #[account(mut)]
+    pub fee_payer: Option<Signer<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct BatchTransferTokenToWallets<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    #[account(
+        mut,
+        constraint = token_account.owner == sender.key(),
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: 该代币的代币手续费配置PDA，始终传入；已有生效的配置时以代币收取手续费，必须同时提供 fee_vault
+    #[account(
+        seeds = [b"mint_fee_config", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_fee_config: UncheckedAccount<'info>,
+    /// 以代币收取手续费时提供
+    #[account(
+        mut,
+        seeds = [b"fee_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub fee_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct QueueMintFeeConfigChange<'info> {
+    #[account(
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + MintFeeConfig::LEN,
+        seeds = [b"mint_fee_config", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_fee_config: Account<'info, MintFeeConfig>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        token::mint = mint,
+        token::authority = bank_account,
+        token::token_program = token_program,
+        seeds = [b"fee_vault", mint.key().as_ref()],
+        bump
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteMintFeeConfigChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint_fee_config", mint_fee_config.mint.as_ref()],
+        bump
+    )]
+    pub mint_fee_config: Account<'info, MintFeeConfig>,
+}
+
+#[derive(Accounts)]
+pub struct CancelMintFeeConfigChange<'info> {
+    #[account(
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"mint_fee_config", mint_fee_config.mint.as_ref()],
+        bump
+    )]
+    pub mint_fee_config: Account<'info, MintFeeConfig>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTokenFees<'info> {
+    #[account(
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    pub admin: Signer<'info>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"fee_vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = destination.mint == mint.key() @ ErrorCode::InvalidRecipient
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(merkle_root: [u8; 32])]
+pub struct CreateDistributor<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + MerkleDistributor::LEN,
+        seeds = [b"distributor", creator.key().as_ref(), mint.key().as_ref(), merkle_root.as_ref()],
+        bump
+    )]
+    pub distributor: Account<'info, MerkleDistributor>,
+    #[account(
+        init,
+        payer = creator,
+        token::mint = mint,
+        token::authority = distributor,
+        token::token_program = token_program,
+        seeds = [b"distributor_vault", distributor.key().as_ref()],
+        bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == creator.key(),
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct Claim<'info> {
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+    #[account(
+        mut,
+        has_one = mint,
+        has_one = vault
+    )]
+    pub distributor: Account<'info, MerkleDistributor>,
+    #[account(
+        init_if_needed,
+        payer = claimant,
+        space = 8 + ClaimBitmap::LEN,
+        seeds = [b"claim_bitmap", distributor.key().as_ref(), &claim_bitmap_chunk(index)?.to_le_bytes()],
+        bump
+    )]
+    pub claim_bitmap: Box<Account<'info, ClaimBitmap>>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub claimant_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Clawback<'info> {
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        has_one = creator @ ErrorCode::Unauthorized,
+        has_one = mint,
+        has_one = vault
+    )]
+    pub distributor: Account<'info, MerkleDistributor>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct BatchCreateVesting<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    #[account(
+        mut,
+        constraint = token_account.owner == sender.key(),
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawVested<'info> {
+    pub recipient: Signer<'info>,
+    #[account(
+        mut,
+        has_one = recipient @ ErrorCode::Unauthorized,
+        has_one = mint,
+        has_one = vault
+    )]
+    pub vesting: Account<'info, VestingAccount>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        has_one = creator @ ErrorCode::Unauthorized,
+        has_one = mint,
+        has_one = vault
+    )]
+    pub vesting: Account<'info, VestingAccount>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(distribution_id: u64)]
+pub struct CreateDistribution<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    #[account(
+        init,
+        payer = creator,
+        space = Distribution::space(0),
+        seeds = [b"distribution", creator.key().as_ref(), distribution_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub distribution: Account<'info, Distribution>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(distribution_id: u64)]
+pub struct CreateTokenDistribution<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    #[account(
         init,
-        payer = deployer,
-        space = 8 + 32 + 8 + 1, // 增加1字节存储is_initialized
+        payer = creator,
+        space = Distribution::space(0),
+        seeds = [b"distribution", creator.key().as_ref(), distribution_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub distribution: Account<'info, Distribution>,
+    #[account(
+        init,
+        payer = creator,
+        token::mint = mint,
+        token::authority = distribution,
+        token::token_program = token_program,
+        seeds = [b"distribution_vault", distribution.key().as_ref()],
+        bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == creator.key(),
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UploadDistributionEntries<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        has_one = creator @ ErrorCode::Unauthorized
+    )]
+    pub distribution: Account<'info, Distribution>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CrankDistribution<'info> {
+    #[account(
         seeds = [b"bank_account"],
         bump
     )]
     pub bank_account: Account<'info, BankAccount>,
     #[account(mut)]
-    pub deployer: Signer<'info>,
+    pub distribution: Account<'info, Distribution>,
+    #[account(
+        mut,
+        seeds = [b"distribution_vault", distribution.key().as_ref()],
+        bump
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelDistribution<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        has_one = creator @ ErrorCode::Unauthorized,
+        close = creator
+    )]
+    pub distribution: Account<'info, Distribution>,
+    #[account(
+        mut,
+        seeds = [b"distribution_vault", distribution.key().as_ref()],
+        bump
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub destination: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+#[derive(Accounts)]
+#[instruction(vault_id: u64)]
+pub struct CreateVault<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Vault::LEN,
+        seeds = [b"vault", owner.key().as_ref(), vault_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositVaultSol<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositVaultToken<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+    pub vault: Account<'info, Vault>,
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        token::mint = mint,
+        token::authority = vault,
+        token::token_program = token_program,
+        seeds = [b"vault_token", vault.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        constraint = depositor_token_account.owner == depositor.key(),
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub depositor_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawVaultSol<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized
+    )]
+    pub vault: Account<'info, Vault>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawVaultToken<'info> {
+    pub owner: Signer<'info>,
+    #[account(has_one = owner @ ErrorCode::Unauthorized)]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        mut,
+        seeds = [b"vault_token", vault.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(operator: Pubkey, limits: Vec<OperatorLimit>)]
+pub struct AddVaultOperator<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(has_one = owner @ ErrorCode::Unauthorized)]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        init,
+        payer = owner,
+        space = VaultOperator::space(limits.len()),
+        seeds = [b"vault_operator", vault.key().as_ref(), operator.as_ref()],
+        bump
+    )]
+    pub vault_operator: Account<'info, VaultOperator>,
     pub system_program: Program<'info, System>,
 }
 
-#[derive(Accounts)]
-pub struct SetFee<'info> {
-    #[account(
-        mut,
-        seeds = [b"bank_account"],
-        bump
-    )]
-    pub bank_account: Account<'info, BankAccount>,
-    #[account(mut)]
-    pub admin: Signer<'info>,
+#[derive(Accounts)]
+pub struct RevokeVaultOperator<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(has_one = owner @ ErrorCode::Unauthorized)]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        mut,
+        has_one = vault,
+        close = owner
+    )]
+    pub vault_operator: Account<'info, VaultOperator>,
+}
+
+#[derive(Accounts)]
+pub struct VaultBatchTransferSol<'info> {
+    pub operator: Signer<'info>,
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        mut,
+        seeds = [b"vault_operator", vault.key().as_ref(), operator.key().as_ref()],
+        bump = vault_operator.bump
+    )]
+    pub vault_operator: Account<'info, VaultOperator>,
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+}
+
+#[derive(Accounts)]
+pub struct VaultBatchTransferToken<'info> {
+    pub operator: Signer<'info>,
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    #[account(
+        mut,
+        seeds = [b"vault_operator", vault.key().as_ref(), operator.key().as_ref()],
+        bump = vault_operator.bump
+    )]
+    pub vault_operator: Account<'info, VaultOperator>,
+    #[account(
+        mut,
+        seeds = [b"bank_account"],
+        bump
+    )]
+    pub bank_account: Account<'info, BankAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault_token", vault.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/**
+ * @notice SOL转账事件
+ * @param from 发送者地址
+ * @param fee_payer 手续费支付者地址，未单独提供时与 from 相同
+ * @param recipients 成功转账的接收者地址列表
+ * @param amounts 成功转账的金额列表
+ * @param total_amount 成功转账的总金额
+ * @param fee 手续费
+ * @param skipped skip_invalid 模式下被跳过的转账
+ * @param batch_id 批次ID，未提供时为 None
+ */
+#[event]
+pub struct SolTransferEvent {
+    pub from: Pubkey,
+    pub fee_payer: Pubkey,
+    pub recipients: Vec<Pubkey>,
+    pub amounts: Vec<u64>,
+    pub total_amount: u64,
+    pub fee: u64,
+    pub skipped: Vec<SkippedTransfer>,
+    pub batch_id: Option<[u8; 32]>,
+}
+
+/**
+ * @notice SPL Token转账事件
+ * @param from 发送者(签名者)地址
+ * @param token_account 转出的token账户
+ * @param owner 转出token账户的所有者
+ * @param delegate 发送者以代理人身份转账时为代理人地址，所有者自己转账时为 None
+ * @param fee_payer SOL手续费支付者地址，未单独提供时与 from 相同
+ * @param recipients 成功转账的接收者地址列表
+ * @param amounts 成功转账的金额列表
+ * @param total_amount 成功转账的总金额
+ * @param fee 以SOL收取的手续费
+ * @param token_fee 以代币收取的手续费
+ * @param skipped skip_invalid 模式下被跳过的转账
+ * @param batch_id 批次ID，未提供时为 None
+ */
+#[event]
+pub struct TokenTransferEvent {
+    pub from: Pubkey,
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub delegate: Option<Pubkey>,
+    pub fee_payer: Pubkey,
+    pub recipients: Vec<Pubkey>,
+    pub amounts: Vec<u64>,
+    pub total_amount: u64,
+    pub fee: u64,
+    pub token_fee: u64,
+    pub skipped: Vec<SkippedTransfer>,
+    pub batch_id: Option<[u8; 32]>,
+}
+
+/**
+ * @notice 关闭批次回执事件
+ * @param sender 发送者地址
+ * @param batch_ids 关闭的批次ID列表
+ * @param reclaimed 退还的租金总额
+ */
+#[event]
+pub struct BatchReceiptsClosedEvent {
+    pub sender: Pubkey,
+    pub batch_ids: Vec<[u8; 32]>,
+    pub reclaimed: u64,
+}
+
+/**
+ * @notice 按钱包地址批量转账SPL Token事件
+ * @param from 发送者地址
+ * @param token_account 发送者的token账户
+ * @param mint 代币mint地址
+ * @param recipients 接收者钱包地址列表
+ * @param token_accounts 接收者ATA地址列表，与 recipients 一一对应
+ * @param created_token_accounts 本次新创建的ATA地址列表
+ * @param amounts 转账金额列表
+ * @param total_amount 总转账金额
+ * @param fee 以SOL收取的手续费
+ * @param token_fee 以代币收取的手续费
+ */
+#[event]
+pub struct TokenWalletTransferEvent {
+    pub from: Pubkey,
+    pub token_account: Pubkey,
+    pub mint: Pubkey,
+    pub recipients: Vec<Pubkey>,
+    pub token_accounts: Vec<Pubkey>,
+    pub created_token_accounts: Vec<Pubkey>,
+    pub amounts: Vec<u64>,
+    pub total_amount: u64,
+    pub fee: u64,
+    pub token_fee: u64,
+}
+
+/**
+ * @notice 代币手续费配置变更提交事件
+ * @param admin 管理员地址
+ * @param mint 代币mint地址
+ * @param fee_vault 手续费金库地址
+ * @param current_fee_config 当前生效的手续费配置，尚未启用代币手续费时为空
+ * @param new_fee_config 待生效的新手续费配置
+ * @param eta 生效时间
+ * @param bypasses_delay 是否为只降低手续费、跳过延迟的变更
+ */
+#[event]
+pub struct MintFeeConfigChangeQueuedEvent {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub fee_vault: Pubkey,
+    pub current_fee_config: Option<FeeConfig>,
+    pub new_fee_config: FeeConfig,
+    pub eta: i64,
+    pub bypasses_delay: bool,
+}
+
+/**
+ * @notice 代币手续费配置变更生效事件
+ * @param mint 代币mint地址
+ * @param previous_fee_config 变更前的手续费配置
+ * @param new_fee_config 生效的新手续费配置
+ * @param eta 提交时指定的生效时间
+ */
+#[event]
+pub struct MintFeeConfigChangeExecutedEvent {
+    pub mint: Pubkey,
+    pub previous_fee_config: Option<FeeConfig>,
+    pub new_fee_config: FeeConfig,
+    pub eta: i64,
+}
+
+/**
+ * @notice 代币手续费配置变更取消事件
+ * @param admin 管理员地址
+ * @param mint 代币mint地址
+ * @param cancelled_fee_config 被取消的手续费配置
+ * @param eta 被取消变更的生效时间
+ */
+#[event]
+pub struct MintFeeConfigChangeCancelledEvent {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub cancelled_fee_config: FeeConfig,
+    pub eta: i64,
+}
+
+/**
+ * @notice 代币手续费提取事件
+ * @param admin 管理员地址
+ * @param mint 代币mint地址
+ * @param destination 接收代币的token账户
+ * @param amount 提取数量
+ * @param remaining 提取后金库剩余数量
+ */
+#[event]
+pub struct TokenFeeWithdrawnEvent {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+/**
+ * @notice 配置变更提交事件
+ * @param admin 管理员地址
+ * @param current_config 当前生效的配置
+ * @param new_config 待生效的新配置
+ * @param eta 生效时间
+ * @param bypasses_delay 是否为只降低手续费、跳过延迟的变更
+ */
+#[event]
+pub struct ConfigChangeQueuedEvent {
+    pub admin: Pubkey,
+    pub current_config: BankConfig,
+    pub new_config: BankConfig,
+    pub eta: i64,
+    pub bypasses_delay: bool,
+}
+
+/**
+ * @notice 配置变更生效事件
+ * @param previous_config 变更前的配置
+ * @param new_config 生效的新配置
+ * @param eta 提交时指定的生效时间
+ */
+#[event]
+pub struct ConfigChangeExecutedEvent {
+    pub previous_config: BankConfig,
+    pub new_config: BankConfig,
+    pub eta: i64,
+}
+
+/**
+ * @notice 配置变更取消事件
+ * @param admin 管理员地址
+ * @param cancelled_config 被取消的配置
+ * @param eta 被取消变更的生效时间
+ */
+#[event]
+pub struct ConfigChangeCancelledEvent {
+    pub admin: Pubkey,
+    pub cancelled_config: BankConfig,
+    pub eta: i64,
+}
+
+/**
+ * @notice 管理员提名事件
+ * @param admin 当前管理员地址
+ * @param pending_admin 被提名的新管理员地址
+ */
+#[event]
+pub struct AdminTransferProposedEvent {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+/**
+ * @notice 管理员变更事件
+ * @param previous_admin 原管理员地址
+ * @param new_admin 新管理员地址
+ */
+#[event]
+pub struct AdminTransferAcceptedEvent {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+/**
+ * @notice 管理员提名取消事件
+ * @param admin 当前管理员地址
+ * @param cancelled_admin 被取消提名的地址
+ */
+#[event]
+pub struct AdminTransferCancelledEvent {
+    pub admin: Pubkey,
+    pub cancelled_admin: Pubkey,
+}
+
+/**
+ * @notice 合约暂停事件
+ * @param admin 管理员地址
+ */
+#[event]
+pub struct PausedEvent {
+    pub admin: Pubkey,
+}
+
+/**
+ * @notice 合约恢复事件
+ * @param admin 管理员地址
+ */
+#[event]
+pub struct UnpausedEvent {
+    pub admin: Pubkey,
+}
+
+/**
+ * @notice 银行账户迁移事件
+ * @param admin 管理员地址
+ * @param from_version 迁移前的布局版本
+ * @param to_version 迁移后的布局版本
+ */
+#[event]
+pub struct BankAccountMigratedEvent {
+    pub admin: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+/**
+ * @notice 手续费提取事件
+ * @param admin 管理员地址
+ * @param destination 手续费接收地址
+ * @param amount 提取金额
+ * @param remaining 提取后剩余的可提取金额
+ */
+#[event]
+pub struct FeeWithdrawnEvent {
+    pub admin: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+/**
+ * @notice Merkle空投分发器创建事件
+ * @param distributor 分发器地址
+ * @param creator 创建者地址
+ * @param mint 代币mint地址
+ * @param vault 金库token账户
+ * @param merkle_root Merkle树根
+ * @param total 空投总额
+ * @param expiry 领取截止时间
+ * @param fee 以SOL收取的手续费
+ */
+#[event]
+pub struct DistributorCreatedEvent {
+    pub distributor: Pubkey,
+    pub creator: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total: u64,
+    pub expiry: i64,
+    pub fee: u64,
 }
 
-#[derive(Accounts)]
-pub struct BatchTransferSol<'info> {
-    /// CHECK: 发送者账户，必须是签名者且可变
-    #[account(mut)]
-    pub sender: Signer<'info>,
-    #[account(
-        mut,
-        seeds = [b"bank_account"],
-        bump
-    )]
-    pub bank_account: Account<'info, BankAccount>,
-    pub system_program: Program<'info, System>,
+/**
+ * @notice Merkle空投领取事件
+ * @param distributor 分发器地址
+ * @param claimant 领取者地址
+ * @param token_account 接收代币的token账户
+ * @param index 叶子节点序号
+ * @param amount 领取数量
+ */
+#[event]
+pub struct ClaimedEvent {
+    pub distributor: Pubkey,
+    pub claimant: Pubkey,
+    pub token_account: Pubkey,
+    pub index: u64,
+    pub amount: u64,
 }
 
-#[derive(Accounts)]
-pub struct BatchTransferToken<'info> {
-    #[account(mut)]
-    pub sender: Signer<'info>,
-    #[account(
-        mut,
-        seeds = [b"bank_account"],
-        bump
-    )]
-    pub bank_account: Account<'info, BankAccount>,
-    #[account(
-        mut,
-        constraint = token_account.owner == sender.key()
-    )]
-    pub token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+/**
+ * @notice Merkle空投收回事件
+ * @param distributor 分发器地址
+ * @param creator 创建者地址
+ * @param destination 接收剩余代币的token账户
+ * @param amount 收回数量
+ */
+#[event]
+pub struct ClawbackEvent {
+    pub distributor: Pubkey,
+    pub creator: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
 }
 
 /**
- * @notice SOL转账事件
- * @param from 发送者地址
+ * @notice 批量创建锁仓事件
+ * @param creator 创建者地址
+ * @param token_account 创建者的token账户
+ * @param mint 代币mint地址
  * @param recipients 接收者地址列表
- * @param amounts 转账金额列表
- * @param total_amount 总转账金额
- * @param fee 手续费
+ * @param vestings 锁仓账户地址列表
+ * @param amounts 各锁仓ATA实际收到的锁仓数量
+ * @param total_amount 从创建者转出的总数量
+ * @param fee 以SOL收取的手续费
  */
 #[event]
-pub struct SolTransferEvent {
-    pub from: Pubkey,
+pub struct VestingCreatedEvent {
+    pub creator: Pubkey,
+    pub token_account: Pubkey,
+    pub mint: Pubkey,
     pub recipients: Vec<Pubkey>,
+    pub vestings: Vec<Pubkey>,
     pub amounts: Vec<u64>,
     pub total_amount: u64,
     pub fee: u64,
 }
 
 /**
- * @notice SPL Token转账事件
- * @param from 发送者地址
- * @param token_account 发送者的token账户
+ * @notice 锁仓提取事件
+ * @param vesting 锁仓账户地址
+ * @param recipient 接收者地址
+ * @param token_account 接收代币的token账户
+ * @param amount 本次提取数量
+ * @param withdrawn 累计提取数量
+ */
+#[event]
+pub struct VestingWithdrawnEvent {
+    pub vesting: Pubkey,
+    pub recipient: Pubkey,
+    pub token_account: Pubkey,
+    pub amount: u64,
+    pub withdrawn: u64,
+}
+
+/**
+ * @notice 锁仓撤销事件
+ * @param vesting 锁仓账户地址
+ * @param creator 创建者地址
+ * @param destination 接收未释放代币的token账户
+ * @param amount 收回的未释放数量
+ * @param vested 撤销时已释放的数量
+ */
+#[event]
+pub struct VestingRevokedEvent {
+    pub vesting: Pubkey,
+    pub creator: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub vested: u64,
+}
+
+/**
+ * @notice 分发创建事件
+ * @param distribution 分发账户地址
+ * @param creator 创建者地址
+ * @param mint 代币mint地址，SOL分发为 None
+ * @param distribution_id 分发ID
+ * @param total_entries 转账列表总条数
+ * @param total_amount 托管的分发总额
+ * @param fee 以SOL收取的手续费
+ */
+#[event]
+pub struct DistributionCreatedEvent {
+    pub distribution: Pubkey,
+    pub creator: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub distribution_id: u64,
+    pub total_entries: u32,
+    pub total_amount: u64,
+    pub fee: u64,
+}
+
+/**
+ * @notice 分发转账列表上传事件
+ * @param distribution 分发账户地址
+ * @param count 本次上传的条数
+ * @param uploaded_entries 累计已上传条数
+ * @param uploaded_amount 累计已上传的金额之和
+ */
+#[event]
+pub struct DistributionEntriesUploadedEvent {
+    pub distribution: Pubkey,
+    pub count: u32,
+    pub uploaded_entries: u32,
+    pub uploaded_amount: u64,
+}
+
+/**
+ * @notice 分发执行事件
+ * @param distribution 分发账户地址
+ * @param recipients 本次成功转账的接收者列表
+ * @param amounts 本次成功转账的金额列表
+ * @param amount 本次发出的总金额
+ * @param skipped 本次被跳过的转账，序号为转账列表中的绝对序号
+ * @param cursor 执行后的游标
+ * @param completed 是否已执行完整个转账列表
+ */
+#[event]
+pub struct DistributionCrankedEvent {
+    pub distribution: Pubkey,
+    pub recipients: Vec<Pubkey>,
+    pub amounts: Vec<u64>,
+    pub amount: u64,
+    pub skipped: Vec<SkippedTransfer>,
+    pub cursor: u32,
+    pub completed: bool,
+}
+
+/**
+ * @notice 分发取消事件
+ * @param distribution 分发账户地址
+ * @param creator 创建者地址
+ * @param refunded 退还创建者的未发出金额
+ * @param distributed_amount 取消前已发出的金额
+ * @param cursor 取消时的游标
+ */
+#[event]
+pub struct DistributionCancelledEvent {
+    pub distribution: Pubkey,
+    pub creator: Pubkey,
+    pub refunded: u64,
+    pub distributed_amount: u64,
+    pub cursor: u32,
+}
+
+/**
+ * @notice 资金库创建事件
+ * @param vault 资金库地址
+ * @param owner 所有者地址
+ * @param vault_id 资金库ID
+ */
+#[event]
+pub struct VaultCreatedEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub vault_id: u64,
+}
+
+/**
+ * @notice 资金库存入事件
+ * @param vault 资金库地址
+ * @param depositor 存入者地址
+ * @param mint 代币mint地址，存入SOL时为 None
+ * @param amount 存入数量
+ */
+#[event]
+pub struct VaultDepositedEvent {
+    pub vault: Pubkey,
+    pub depositor: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+}
+
+/**
+ * @notice 资金库提取事件
+ * @param vault 资金库地址
+ * @param owner 所有者地址
+ * @param mint 代币mint地址，提取SOL时为 None
+ * @param destination 接收地址，提取SOL时为所有者，提取代币时为目标token账户
+ * @param amount 提取数量
+ */
+#[event]
+pub struct VaultWithdrawnEvent {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+/**
+ * @notice 操作员登记事件
+ * @param vault 资金库地址
+ * @param operator 操作员地址
+ * @param limits 各资产的额度配置
+ */
+#[event]
+pub struct VaultOperatorAddedEvent {
+    pub vault: Pubkey,
+    pub operator: Pubkey,
+    pub limits: Vec<OperatorLimit>,
+}
+
+/**
+ * @notice 操作员撤销事件
+ * @param vault 资金库地址
+ * @param operator 操作员地址
+ */
+#[event]
+pub struct VaultOperatorRevokedEvent {
+    pub vault: Pubkey,
+    pub operator: Pubkey,
+}
+
+/**
+ * @notice 资金库批量转账事件
+ * @param vault 资金库地址
+ * @param operator 执行转账的操作员
+ * @param mint 代币mint地址，SOL转账为 None
  * @param recipients 接收者地址列表
  * @param amounts 转账金额列表
- * @param total_amount 总转账金额
- * @param fee 手续费
+ * @param total_amount 转账总额
+ * @param fee 从资金库扣除的SOL手续费
  */
 #[event]
-pub struct TokenTransferEvent {
-    pub from: Pubkey,
-    pub token_account: Pubkey,
+pub struct VaultTransferEvent {
+    pub vault: Pubkey,
+    pub operator: Pubkey,
+    pub mint: Option<Pubkey>,
     pub recipients: Vec<Pubkey>,
     pub amounts: Vec<u64>,
     pub total_amount: u64,
@@ -312,6 +4139,77 @@ pub struct TransferInfo {
     pub amount: u64,
 }
 
+/**
+ * @notice 按权重拆分时的接收者及其权重
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SplitInfo {
+    pub recipient: Pubkey, // 接收者地址
+    pub weight: u64,       // 权重
+}
+
+/**
+ * @notice skip_invalid 模式下被跳过的转账
+ * @dev reason 为校验失败对应的 ErrorCode 错误码，与整批失败时返回的错误码一致
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SkippedTransfer {
+    pub index: u32,  // 转账序号
+    pub reason: u32, // 跳过原因(错误码)
+}
+
+impl SkippedTransfer {
+    pub fn new(index: usize, reason: ErrorCode) -> Self {
+        Self {
+            index: index as u32,
+            reason: reason.into(),
+        }
+    }
+}
+
+/**
+ * @notice 批量转账中重复接收者的处理策略
+ * @dev Reject 和 Merge 要求 transfers 按接收者地址升序排列，只需比较相邻两笔即可发现重复，
+ * Reject 要求严格升序，出现重复时返回 DuplicateRecipient；
+ * Merge 将相邻的重复接收者合并为一笔转账，金额相加，remaining_accounts 按合并后的转账提供
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    #[default]
+    Allow,  // 不检查，重复的接收者分别转账
+    Reject, // 拒绝重复的接收者
+    Merge,  // 合并重复的接收者
+}
+
+/**
+ * @notice 单个锁仓的释放计划
+ * @dev 要求 amount > 0 且 start <= cliff <= end、start < end
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct VestingInfo {
+    pub recipient: Pubkey, // 接收者地址
+    pub amount: u64,       // 锁仓数量
+    pub start: i64,        // 开始释放时间(unix时间戳)
+    pub cliff: i64,        // 悬崖时间，此前不可提取
+    pub end: i64,          // 全部释放时间
+    pub revocable: bool,   // 创建者是否可以撤销并收回未释放部分
+}
+
+/**
+ * @notice 操作员对单个资产的额度配置
+ * @dev 要求 0 < max_per_batch <= daily_cap，金额单位为lamports或代币最小单位
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OperatorLimit {
+    pub mint: Option<Pubkey>, // 代币mint地址，None 表示SOL
+    pub max_per_batch: u64,   // 单批转账总额上限
+    pub daily_cap: u64,       // 滚动24小时窗口(按整点小时分桶)内的转账总额上限
+}
+
+impl OperatorLimit {
+    pub const LEN: usize = (1 + 32) + 8 + 8;
+}
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("SOL余额不足")]
@@ -326,10 +4224,106 @@ pub enum ErrorCode {
     AlreadyInitialized,
     #[msg("接收者账户无效")]
     InvalidRecipient,
+    #[msg("可提取的手续费不足")]
+    InsufficientFeeBalance,
+    #[msg("没有待接受的管理员提名")]
+    NoPendingAdmin,
+    #[msg("程序不可升级，无法校验初始化权限")]
+    ImmutableProgram,
+    #[msg("手续费配置无效")]
+    InvalidFeeConfig,
+    #[msg("该代币未配置代币手续费，不能提供手续费金库")]
+    IncompleteTokenFeeAccounts,
+    #[msg("转账钩子程序或ExtraAccountMetaList账户无效")]
+    InvalidTransferHookAccounts,
+    #[msg("接收者账户不是有效的token账户")]
+    InvalidRecipientTokenAccount,
+    #[msg("接收者token账户的mint与发送者不一致")]
+    RecipientMintMismatch,
+    #[msg("接收者token账户已被冻结")]
+    RecipientAccountFrozen,
+    #[msg("接收者不能是发送者自己的token账户")]
+    RecipientIsSender,
+    #[msg("分发器配置无效")]
+    InvalidDistributorConfig,
+    #[msg("空投已过期")]
+    DistributorExpired,
+    #[msg("空投尚未过期")]
+    DistributorNotExpired,
+    #[msg("Merkle证明无效")]
+    InvalidMerkleProof,
+    #[msg("该序号已领取")]
+    AlreadyClaimed,
+    #[msg("锁仓释放计划无效")]
+    InvalidVestingSchedule,
+    #[msg("锁仓账户地址无效或已存在")]
+    InvalidVestingAccount,
+    #[msg("没有可提取的代币")]
+    NothingToWithdraw,
+    #[msg("该锁仓不可撤销")]
+    VestingNotRevocable,
+    #[msg("该锁仓已撤销")]
+    VestingAlreadyRevoked,
+    #[msg("合约已暂停")]
+    Paused,
+    #[msg("银行账户无效")]
+    InvalidBankAccount,
+    #[msg("银行账户已是最新版本")]
+    BankAccountUpToDate,
+    #[msg("转账后新接收者账户的余额低于租金豁免最低余额")]
+    RecipientBelowRentExemption,
+    #[msg("接收者重复")]
+    DuplicateRecipient,
+    #[msg("转账列表未按接收者地址升序排列")]
+    TransfersNotSorted,
+    #[msg("remaining_accounts 的数量与转账布局不匹配")]
+    InvalidRemainingAccounts,
+    #[msg("拆分权重无效")]
+    InvalidSplitWeights,
+    #[msg("该批次已执行")]
+    BatchAlreadyExecuted,
+    #[msg("批次回执账户无效")]
+    InvalidBatchReceipt,
+    #[msg("分发配置无效")]
+    InvalidDistributionConfig,
+    #[msg("分发转账列表的条数或金额与配置不一致")]
+    InvalidDistributionEntries,
+    #[msg("分发转账列表尚未上传完成")]
+    DistributionNotReady,
+    #[msg("分发已执行完成")]
+    DistributionCompleted,
+    #[msg("分发的代币账户缺失或不匹配")]
+    InvalidDistributionAccounts,
+    #[msg("操作员额度配置无效")]
+    InvalidOperatorLimits,
+    #[msg("操作员不允许转出该资产")]
+    OperatorMintNotAllowed,
+    #[msg("超过操作员的单批转账上限")]
+    OperatorBatchLimitExceeded,
+    #[msg("超过操作员滚动24小时窗口内的转账上限")]
+    OperatorDailyCapExceeded,
+    #[msg("代理人剩余的授权额度不足")]
+    InsufficientDelegatedAmount,
+    #[msg("手续费超过了发送者设置的上限")]
+    FeeExceedsMaximum,
+    #[msg("配置变更延迟超出允许范围")]
+    InvalidConfigDelay,
+    #[msg("生效时间早于配置变更延迟允许的最早时间")]
+    ConfigChangeTooEarly,
+    #[msg("已有待生效的配置变更")]
+    ConfigChangePending,
+    #[msg("没有待生效的配置变更")]
+    NoPendingConfigChange,
+    #[msg("配置变更尚未到达生效时间")]
+    ConfigChangeNotReady,
+    #[msg("该代币已配置代币手续费，必须提供手续费金库")]
+    TokenFeeVaultRequired,
+    #[msg("领取序号超出位图分块可表示的范围")]
+    InvalidClaimIndex,
 }
 
 /// 安全求和函数，防止溢出
-pub fn safe_sum_transfer_info(transfers: &Vec<TransferInfo>) -> std::result::Result<u64, ProgramError> {
+pub fn safe_sum_transfer_info(transfers: &[TransferInfo]) -> std::result::Result<u64, ProgramError> {
     transfers.iter().try_fold(0u64, |acc, info| {
         acc.checked_add(info.amount).ok_or(ProgramError::InvalidArgument)
     })
@@ -339,3 +4333,450 @@ pub fn safe_sum_transfer_info(transfers: &Vec<TransferInfo>) -> std::result::Res
 pub fn safe_add(a: u64, b: u64) -> std::result::Result<u64, ProgramError> {
     a.checked_add(b).ok_or(ProgramError::InvalidArgument)
 }
+
+/// 安全减法函数，防止下溢
+pub fn safe_sub(a: u64, b: u64) -> std::result::Result<u64, ProgramError> {
+    a.checked_sub(b).ok_or(ProgramError::InvalidArgument)
+}
+
+/// 基点的分母，10000个基点为100%
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// 校验手续费配置：基点不超过100%，设置上限时下限不高于上限
+pub fn validate_fee_config(fee_config: &FeeConfig) -> Result<()> {
+    require!(
+        u64::from(fee_config.bps) <= BPS_DENOMINATOR,
+        ErrorCode::InvalidFeeConfig
+    );
+    require!(
+        fee_config.max_fee == 0 || fee_config.min_fee <= fee_config.max_fee,
+        ErrorCode::InvalidFeeConfig
+    );
+    Ok(())
+}
+
+/// 校验操作员额度配置：条数为 1..=MAX_OPERATOR_LIMITS，每条满足 0 < max_per_batch <= daily_cap，且同一资产只能登记一次
+pub fn validate_operator_limits(limits: &[OperatorLimit]) -> Result<()> {
+    require!(
+        !limits.is_empty() && limits.len() <= MAX_OPERATOR_LIMITS,
+        ErrorCode::InvalidOperatorLimits
+    );
+    for (i, limit) in limits.iter().enumerate() {
+        require!(
+            limit.max_per_batch > 0 && limit.max_per_batch <= limit.daily_cap,
+            ErrorCode::InvalidOperatorLimits
+        );
+        require!(
+            limits[..i].iter().all(|other| other.mint != limit.mint),
+            ErrorCode::InvalidOperatorLimits
+        );
+    }
+    Ok(())
+}
+
+/// 按转账总额计算基点金额，向下取整，防止溢出
+pub fn safe_bps_of(amount: u64, bps: u16) -> std::result::Result<u64, ProgramError> {
+    let value = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(ProgramError::InvalidArgument)?
+        / BPS_DENOMINATOR as u128;
+    u64::try_from(value).map_err(|_| ProgramError::InvalidArgument)
+}
+
+/// 根据手续费配置和转账总额计算手续费
+pub fn calculate_fee(fee_config: &FeeConfig, total_amount: u64) -> std::result::Result<u64, ProgramError> {
+    let fee = safe_add(fee_config.flat_fee, safe_bps_of(total_amount, fee_config.bps)?)?;
+    let fee = fee.max(fee_config.min_fee);
+    if fee_config.max_fee > 0 {
+        Ok(fee.min(fee_config.max_fee))
+    } else {
+        Ok(fee)
+    }
+}
+
+/// 判断配置变更能否跳过延迟：当前配置开启了降费跳过延迟，新手续费配置对任意转账总额都不高于当前配置，
+/// 且不缩短变更延迟；关闭降费跳过延迟只会让配置更严格，同样可以立即生效
+pub fn bypasses_config_delay(current: &BankConfig, new: &BankConfig) -> bool {
+    current.allow_fee_decrease_bypass
+        && fee_config_not_raised(&current.fee_config, &new.fee_config)
+        && new.config_delay >= current.config_delay
+}
+
+/// 判断新手续费配置对任意转账总额计算出的手续费都不高于当前配置
+pub fn fee_config_not_raised(current: &FeeConfig, new: &FeeConfig) -> bool {
+    let max_fee_not_raised = current.max_fee == 0 || (new.max_fee != 0 && new.max_fee <= current.max_fee);
+    new.flat_fee <= current.flat_fee && new.bps <= current.bps && new.min_fee <= current.min_fee && max_fee_not_raised
+}
+
+/// 计算银行账户中可提取的手续费，保留租金豁免所需的最低余额
+pub fn withdrawable_fee(balance: u64, rent_exempt_minimum: u64) -> u64 {
+    balance.saturating_sub(rent_exempt_minimum)
+}
+
+/// 从 remaining_accounts 开头解析转账钩子账户：返回钩子程序和 ExtraAccountMetaList 账户(未配置钩子时为空)，
+/// 以及每笔转账需要附带的额外账户数量
+fn parse_hook_accounts<'a, 'info>(
+    mint: &AccountInfo<'info>,
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> Result<(&'a [AccountInfo<'info>], usize)> {
+    let extras_per_transfer = transfer_hook_extra_account_count(mint, remaining_accounts)?;
+    let hook_accounts = if extras_per_transfer.is_some() {
+        &remaining_accounts[..2]
+    } else {
+        &remaining_accounts[..0]
+    };
+    Ok((hook_accounts, extras_per_transfer.unwrap_or(0)))
+}
+
+/// 从付款账户向银行账户收取SOL手续费，手续费为0时不发起转账
+fn charge_sol_fee<'info>(
+    payer: &AccountInfo<'info>,
+    bank_account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    fee: u64,
+) -> Result<()> {
+    if fee == 0 {
+        return Ok(());
+    }
+    let cpi_context = CpiContext::new(
+        system_program.clone(),
+        anchor_lang::system_program::Transfer {
+            from: payer.clone(),
+            to: bank_account.clone(),
+        },
+    );
+    anchor_lang::system_program::transfer(cpi_context, fee)
+}
+
+/// 读取代币的转账钩子配置，未配置钩子时返回 None；
+/// 配置了钩子时校验 remaining_accounts 开头的钩子程序和 ExtraAccountMetaList 账户，
+/// 并返回每笔转账需要附带的额外账户数量
+pub fn transfer_hook_extra_account_count(
+    mint: &AccountInfo,
+    remaining_accounts: &[AccountInfo],
+) -> Result<Option<usize>> {
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let Some(hook_program_id) = transfer_hook::get_program_id(&mint_state) else {
+        return Ok(None);
+    };
+
+    require!(remaining_accounts.len() >= 2, ErrorCode::InvalidTransferHookAccounts);
+    let hook_program = &remaining_accounts[0];
+    let extra_account_meta_list = &remaining_accounts[1];
+    require!(
+        hook_program.key() == hook_program_id
+            && extra_account_meta_list.key() == get_extra_account_metas_address(mint.key, &hook_program_id),
+        ErrorCode::InvalidTransferHookAccounts
+    );
+
+    let data = extra_account_meta_list.try_borrow_data()?;
+    let tlv_state = TlvStateBorrowed::unpack(&data)?;
+    let extra_account_metas = ExtraAccountMetaList::unpack_with_tlv_state::<ExecuteInstruction>(&tlv_state)?;
+    Ok(Some(extra_account_metas.data().len()))
+}
+
+/// 构造附带转账序号的错误：返回的错误码与序号无关，失败的是第几笔转账只记录在程序日志的错误信息中，
+/// 客户端需要从交易日志而不是交易错误中读取序号
+pub fn transfer_error(error_code: ErrorCode, index: usize) -> Error {
+    Error::from(AnchorError {
+        error_name: error_code.name(),
+        error_code_number: error_code.into(),
+        error_msg: format!("{} (转账序号: {})", error_code, index),
+        error_origin: None,
+        compared_values: None,
+    })
+}
+
+/// 校验接收者token账户：归属当前代币程序、与发送者同一mint、未冻结且不是发送者自己的账户
+pub fn validate_recipient_token_account(
+    recipient: &AccountInfo,
+    token_program: &Pubkey,
+    mint: &Pubkey,
+    sender_token_account: &Pubkey,
+    index: usize,
+) -> Result<()> {
+    check_recipient_token_account(recipient, token_program, mint, sender_token_account)
+        .map_err(|error_code| transfer_error(error_code, index))
+}
+
+/// 同 validate_recipient_token_account，校验失败时只返回错误码，供 skip_invalid 模式记录跳过原因
+pub fn check_recipient_token_account(
+    recipient: &AccountInfo,
+    token_program: &Pubkey,
+    mint: &Pubkey,
+    sender_token_account: &Pubkey,
+) -> std::result::Result<(), ErrorCode> {
+    if recipient.key == sender_token_account {
+        return Err(ErrorCode::RecipientIsSender);
+    }
+    if recipient.owner != token_program {
+        return Err(ErrorCode::InvalidRecipientTokenAccount);
+    }
+
+    let data = recipient
+        .try_borrow_data()
+        .map_err(|_| ErrorCode::InvalidRecipientTokenAccount)?;
+    let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)
+        .map_err(|_| ErrorCode::InvalidRecipientTokenAccount)?;
+    if account.base.mint != *mint {
+        return Err(ErrorCode::RecipientMintMismatch);
+    }
+    if account.base.state == AccountState::Frozen {
+        return Err(ErrorCode::RecipientAccountFrozen);
+    }
+    Ok(())
+}
+
+/// 校验SOL接收者：地址与转账信息一致且可写；余额为0的新账户收到的金额必须达到租金豁免最低余额，
+/// 否则交易会在运行时因租金检查失败
+pub fn check_sol_recipient(
+    recipient: &AccountInfo,
+    transfer_info: &TransferInfo,
+    rent: &Rent,
+) -> std::result::Result<(), ErrorCode> {
+    if recipient.key() != transfer_info.recipient || !recipient.is_writable {
+        return Err(ErrorCode::InvalidRecipient);
+    }
+    if recipient.lamports() == 0
+        && transfer_info.amount > 0
+        && !rent.is_exempt(transfer_info.amount, recipient.data_len())
+    {
+        return Err(ErrorCode::RecipientBelowRentExemption);
+    }
+    Ok(())
+}
+
+/// 创建由本程序所有的PDA账户，租金由 payer 支付；
+/// 地址已被预先转入lamports时无法直接 create_account，改为补足租金后分配空间并指定所有者
+fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let rent_lamports = Rent::get()?.minimum_balance(space);
+    let current_lamports = account.lamports();
+    if current_lamports == 0 {
+        return anchor_lang::system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                anchor_lang::system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                signer_seeds,
+            ),
+            rent_lamports,
+            space as u64,
+            owner,
+        );
+    }
+
+    let top_up = rent_lamports.saturating_sub(current_lamports);
+    if top_up > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    anchor_lang::system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::Allocate {
+                account_to_allocate: account.clone(),
+            },
+            signer_seeds,
+        ),
+        space as u64,
+    )?;
+    anchor_lang::system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::Assign {
+                account_to_assign: account.clone(),
+            },
+            signer_seeds,
+        ),
+        owner,
+    )
+}
+
+/// 创建批次回执：校验回执地址由 [b"batch_receipt", sender, batch_id] 派生，
+/// 回执已存在时返回 BatchAlreadyExecuted，租金由发送者支付
+fn record_batch_receipt<'info>(
+    sender: &AccountInfo<'info>,
+    batch_receipt: Option<&UncheckedAccount<'info>>,
+    system_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+    batch_id: [u8; 32],
+) -> Result<()> {
+    let receipt_info = batch_receipt.ok_or(ErrorCode::InvalidBatchReceipt)?.to_account_info();
+    let (expected_receipt, bump) =
+        Pubkey::find_program_address(&[b"batch_receipt", sender.key.as_ref(), &batch_id], program_id);
+    require_keys_eq!(receipt_info.key(), expected_receipt, ErrorCode::InvalidBatchReceipt);
+    require!(receipt_info.owner != program_id, ErrorCode::BatchAlreadyExecuted);
+    require!(
+        receipt_info.data_is_empty() && receipt_info.owner == &System::id(),
+        ErrorCode::InvalidBatchReceipt
+    );
+
+    let signer_seeds: &[&[&[u8]]] = &[&[b"batch_receipt", sender.key.as_ref(), &batch_id, &[bump]]];
+    create_pda_account(sender, &receipt_info, system_program, 8 + BatchReceipt::LEN, program_id, signer_seeds)?;
+
+    let receipt = BatchReceipt {
+        sender: sender.key(),
+        batch_id,
+        executed_at: Clock::get()?.unix_timestamp,
+        bump,
+    };
+    let mut data = receipt_info.try_borrow_mut_data()?;
+    receipt.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+/// 读取代币手续费配置：mint_fee_config 的地址已由 seeds 约束校验，账户未初始化或尚无生效的配置时返回 None，
+/// 以SOL收取手续费；已有生效的配置时必须提供手续费金库，防止发送者省略账户绕过代币手续费
+fn load_mint_fee_config(
+    mint_fee_config: &AccountInfo,
+    fee_vault_provided: bool,
+    program_id: &Pubkey,
+) -> Result<Option<FeeConfig>> {
+    let fee_config = if mint_fee_config.owner == program_id {
+        MintFeeConfig::try_deserialize(&mut &mint_fee_config.try_borrow_data()?[..])?.fee_config
+    } else {
+        None
+    };
+    match fee_config {
+        Some(_) => require!(fee_vault_provided, ErrorCode::TokenFeeVaultRequired),
+        None => require!(!fee_vault_provided, ErrorCode::IncompleteTokenFeeAccounts),
+    }
+    Ok(fee_config)
+}
+
+/// 从 remaining_accounts 中按位置取出连续的账户，账户不足时返回 NotEnoughAccountKeys
+fn take_accounts<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+    offset: usize,
+    count: usize,
+) -> std::result::Result<&'a [AccountInfo<'info>], ProgramError> {
+    remaining_accounts
+        .get(offset..offset + count)
+        .ok_or(ProgramError::NotEnoughAccountKeys)
+}
+
+/// Merkle叶子节点哈希前缀，与内部节点区分，防止以内部节点冒充叶子
+pub const MERKLE_LEAF_PREFIX: u8 = 0;
+/// Merkle内部节点哈希前缀
+pub const MERKLE_NODE_PREFIX: u8 = 1;
+
+/// 计算Merkle叶子节点：keccak256(0x00 || index(u64 LE) || TransferInfo 的borsh序列化)，
+/// 即 keccak256(0x00 || index || recipient(32字节) || amount(u64 LE))
+pub fn merkle_leaf(index: u64, transfer_info: &TransferInfo) -> [u8; 32] {
+    keccak::hashv(&[
+        &[MERKLE_LEAF_PREFIX],
+        &index.to_le_bytes(),
+        transfer_info.recipient.as_ref(),
+        &transfer_info.amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// 计算Merkle内部节点：keccak256(0x01 || min(a, b) || max(a, b))，子节点排序后哈希，证明中无需记录左右位置
+pub fn merkle_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    keccak::hashv(&[&[MERKLE_NODE_PREFIX], left, right]).to_bytes()
+}
+
+/// 校验Merkle证明
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    proof.iter().fold(leaf, |hash, sibling| merkle_node(&hash, sibling)) == *root
+}
+
+/// 序号所在的领取位图分块，分块编号超出 u32 范围时返回 InvalidClaimIndex，避免截断后不同序号共用同一个位图账户
+pub fn claim_bitmap_chunk(index: u64) -> Result<u32> {
+    u32::try_from(index / (CLAIM_BITMAP_BYTES as u64 * 8)).map_err(|_| error!(ErrorCode::InvalidClaimIndex))
+}
+
+/// 序号在所属位图分块中的字节位置和位掩码
+pub fn claim_bitmap_position(index: u64) -> (usize, u8) {
+    let bit = (index % (CLAIM_BITMAP_BYTES as u64 * 8)) as usize;
+    (bit / 8, 1 << (bit % 8))
+}
+
+/// 按重复接收者策略处理转账列表：Reject 和 Merge 要求按接收者地址升序排列，
+/// Reject 遇到重复时返回 DuplicateRecipient，Merge 将相邻的重复接收者合并为一笔，金额相加
+pub fn apply_duplicate_policy(
+    transfers: Vec<TransferInfo>,
+    policy: DuplicatePolicy,
+) -> Result<Vec<TransferInfo>> {
+    if policy == DuplicatePolicy::Allow {
+        return Ok(transfers);
+    }
+
+    let mut result: Vec<TransferInfo> = Vec::with_capacity(transfers.len());
+    for (i, transfer_info) in transfers.into_iter().enumerate() {
+        if let Some(last) = result.last_mut() {
+            if transfer_info.recipient < last.recipient {
+                return Err(transfer_error(ErrorCode::TransfersNotSorted, i));
+            }
+            if transfer_info.recipient == last.recipient {
+                if policy == DuplicatePolicy::Reject {
+                    return Err(transfer_error(ErrorCode::DuplicateRecipient, i));
+                }
+                last.amount = safe_add(last.amount, transfer_info.amount)?;
+                continue;
+            }
+        }
+        result.push(transfer_info);
+    }
+    Ok(result)
+}
+
+/// 按权重拆分总额：先按 total * weight / 权重总和 向下取整，
+/// 剩余的零头按最大余数法逐一分配给余数最大的接收者，余数相同时序号小的优先，各笔之和恰好等于 total
+pub fn split_by_weight(total: u64, splits: &[SplitInfo]) -> Result<Vec<TransferInfo>> {
+    require!(!splits.is_empty(), ErrorCode::EmptyTransfers);
+    let total_weight = splits
+        .iter()
+        .try_fold(0u64, |acc, split| safe_add(acc, split.weight))?;
+    require!(total_weight > 0, ErrorCode::InvalidSplitWeights);
+
+    // 乘积不超过 u64::MAX * u64::MAX，用 u128 计算不会溢出
+    let mut transfers = Vec::with_capacity(splits.len());
+    let mut remainders = Vec::with_capacity(splits.len());
+    for (i, split) in splits.iter().enumerate() {
+        let product = total as u128 * split.weight as u128;
+        transfers.push(TransferInfo {
+            recipient: split.recipient,
+            amount: (product / total_weight as u128) as u64,
+        });
+        remainders.push((product % total_weight as u128, i));
+    }
+
+    // 向下取整损失的零头小于接收者数量
+    let distributed = safe_sum_transfer_info(&transfers)?;
+    let leftover = safe_sub(total, distributed)? as usize;
+    remainders.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    for &(_, i) in remainders.iter().take(leftover) {
+        transfers[i].amount = safe_add(transfers[i].amount, 1)?;
+    }
+    Ok(transfers)
+}
+
+/// 校验锁仓释放计划：数量大于0、接收者有效，且 start <= cliff <= end、start < end
+pub fn is_valid_vesting_schedule(schedule: &VestingInfo) -> bool {
+    schedule.amount > 0
+        && schedule.recipient != Pubkey::default()
+        && schedule.start <= schedule.cliff
+        && schedule.cliff <= schedule.end
+        && schedule.start < schedule.end
+}
@@ -0,0 +1,157 @@
+//! 链下Merkle树构建工具，仅在启用 `merkle-builder` 特性且非BPF目标时编译
+//!
+//! 叶子和内部节点的哈希直接复用链上的 `merkle_leaf` 和 `merkle_node`，
+//! 因此这里生成的证明与 `claim` 指令的校验逐字节一致。
+
+use crate::{merkle_leaf, merkle_node, safe_sum_transfer_info, ErrorCode, TransferInfo};
+use anchor_lang::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Merkle树，levels[0] 为叶子层，最后一层只有树根
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleTree {
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    /// 按转账列表构建Merkle树，每个条目的叶子序号即其在列表中的位置
+    pub fn new(transfers: &[TransferInfo]) -> Result<Self> {
+        let leaves = transfers
+            .iter()
+            .enumerate()
+            .map(|(index, transfer_info)| merkle_leaf(index as u64, transfer_info))
+            .collect();
+        Self::from_leaves(leaves)
+    }
+
+    /// 由已计算好的叶子哈希构建Merkle树，奇数个节点时最后一个节点直接进入上一层
+    pub fn from_leaves(leaves: Vec<[u8; 32]>) -> Result<Self> {
+        require!(!leaves.is_empty(), ErrorCode::EmptyTransfers);
+
+        let mut levels = vec![leaves];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => merkle_node(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Ok(Self { levels })
+    }
+
+    /// 树根
+    pub fn root(&self) -> [u8; 32] {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    /// 叶子数量
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// 是否没有叶子，构建成功的树总是至少有一个叶子
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    /// 第 position 个叶子的证明，从叶子层自下而上排列；position 越界时返回 None
+    pub fn proof(&self, position: usize) -> Option<Vec<[u8; 32]>> {
+        if position >= self.len() {
+            return None;
+        }
+        let mut proof = Vec::new();
+        let mut position = position;
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(position ^ 1) {
+                proof.push(*sibling);
+            }
+            position /= 2;
+        }
+        Some(proof)
+    }
+}
+
+/// 单个领取者的证明，哈希以十六进制字符串表示，地址为base58
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClaimProof {
+    pub index: u64,
+    pub recipient: String,
+    pub amount: u64,
+    pub proof: Vec<String>,
+}
+
+/// 证明清单，创建分发器时使用 merkle_root 和 total，领取者按 index、amount、proof 调用 claim
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofManifest {
+    pub merkle_root: String,
+    pub total: u64,
+    pub claims: Vec<ClaimProof>,
+}
+
+impl ProofManifest {
+    /// 按转账列表构建Merkle树并生成证明清单
+    pub fn new(transfers: &[TransferInfo]) -> Result<Self> {
+        let tree = MerkleTree::new(transfers)?;
+        let total = safe_sum_transfer_info(transfers)?;
+        let claims = transfers
+            .iter()
+            .enumerate()
+            .map(|(position, transfer_info)| ClaimProof {
+                index: position as u64,
+                recipient: transfer_info.recipient.to_string(),
+                amount: transfer_info.amount,
+                proof: tree.proof(position).unwrap_or_default().iter().map(to_hex).collect(),
+            })
+            .collect();
+        Ok(Self {
+            merkle_root: to_hex(&tree.root()),
+            total,
+            claims,
+        })
+    }
+
+    /// 输出JSON格式的证明清单
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// 输出CSV格式的证明清单，每行一个领取者，证明中的各哈希以分号分隔
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("index,recipient,amount,proof\n");
+        for claim in &self.claims {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                claim.index,
+                claim.recipient,
+                claim.amount,
+                claim.proof.join(";")
+            ));
+        }
+        csv
+    }
+}
+
+/// 哈希转为小写十六进制字符串
+pub fn to_hex(hash: &[u8; 32]) -> String {
+    hash.iter().fold(String::with_capacity(64), |mut hex, byte| {
+        let _ = write!(hex, "{:02x}", byte);
+        hex
+    })
+}
+
+/// 由十六进制字符串解析哈希，格式不正确时返回 None
+pub fn from_hex(hex: &str) -> Option<[u8; 32]> {
+    if hex.len() != 64 || !hex.is_ascii() {
+        return None;
+    }
+    let mut hash = [0u8; 32];
+    for (i, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(hash)
+}
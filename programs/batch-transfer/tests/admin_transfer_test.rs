@@ -0,0 +1,87 @@
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+use batch_transfer::{ErrorCode, FeeConfig};
+
+mod utils_test;
+use utils_test::program_test_utils::*;
+
+/// 测试两步管理员交接：提名、接受后新管理员生效
+#[tokio::test]
+async fn test_propose_and_accept_admin() {
+    let admin = Keypair::new();
+    let new_admin = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    println!("开始测试管理员交接");
+
+    // 当前管理员提名新管理员
+    send(&mut context, &[propose_admin_ix(&admin.pubkey(), &new_admin.pubkey())], &[&admin])
+        .await
+        .expect("提名新管理员失败");
+
+    // 接受之前管理员不变
+    let bank = get_bank_account_data(&mut context).await;
+    assert_eq!(bank.admin, admin.pubkey(), "接受之前管理员不应改变");
+    assert_eq!(bank.pending_admin, Some(new_admin.pubkey()), "待接受管理员设置错误");
+
+    // 新管理员签名接受
+    send(&mut context, &[accept_admin_ix(&new_admin.pubkey())], &[&new_admin])
+        .await
+        .expect("接受管理员权限失败");
+
+    let bank = get_bank_account_data(&mut context).await;
+    assert_eq!(bank.admin, new_admin.pubkey(), "管理员应变更为新管理员");
+    assert_eq!(bank.pending_admin, None, "待接受管理员应被清空");
+
+    // 新管理员可以设置手续费，原管理员不能
+    let fee_config = FeeConfig {
+        flat_fee: 5_000,
+        ..FeeConfig::default()
+    };
+    set_fee_config(&mut context, &new_admin, fee_config)
+        .await
+        .expect("新管理员设置手续费失败");
+    let result = set_fee_config(&mut context, &admin, FeeConfig::default()).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    assert_eq!(get_bank_account_data(&mut context).await.fee_config.flat_fee, 5_000);
+
+    println!("管理员交接测试通过");
+}
+
+/// 测试取消管理员提名后被提名者无法接受
+#[tokio::test]
+async fn test_cancel_admin_transfer() {
+    let admin = Keypair::new();
+    let new_admin = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    send(&mut context, &[propose_admin_ix(&admin.pubkey(), &new_admin.pubkey())], &[&admin])
+        .await
+        .expect("提名新管理员失败");
+    send(&mut context, &[cancel_admin_transfer_ix(&admin.pubkey())], &[&admin])
+        .await
+        .expect("取消管理员提名失败");
+
+    assert_eq!(get_bank_account_data(&mut context).await.pending_admin, None);
+
+    // 提名已取消，被提名者无法再接受
+    let result = send(&mut context, &[accept_admin_ix(&new_admin.pubkey())], &[&new_admin]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    // 没有待接受的提名时不能取消
+    let result = send(&mut context, &[cancel_admin_transfer_ix(&admin.pubkey())], &[&admin]).await;
+    assert_program_error(result, ErrorCode::NoPendingAdmin);
+
+    assert_eq!(get_bank_account_data(&mut context).await.admin, admin.pubkey());
+}
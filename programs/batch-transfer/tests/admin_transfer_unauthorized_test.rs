@@ -0,0 +1,57 @@
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+use batch_transfer::ErrorCode;
+
+mod utils_test;
+use utils_test::program_test_utils::*;
+
+/// 测试管理员交接的各个步骤拒绝错误的签名者
+#[tokio::test]
+async fn test_admin_transfer_unauthorized() {
+    let admin = Keypair::new();
+    let new_admin = Keypair::new();
+    let attacker = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    println!("开始测试未授权的管理员交接");
+
+    // 非管理员不能提名
+    let result = send(
+        &mut context,
+        &[propose_admin_ix(&attacker.pubkey(), &attacker.pubkey())],
+        &[&attacker],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    send(&mut context, &[propose_admin_ix(&admin.pubkey(), &new_admin.pubkey())], &[&admin])
+        .await
+        .expect("提名新管理员失败");
+
+    // 非被提名者不能接受
+    let result = send(&mut context, &[accept_admin_ix(&attacker.pubkey())], &[&attacker]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    // 当前管理员也不能替被提名者接受
+    let result = send(&mut context, &[accept_admin_ix(&admin.pubkey())], &[&admin]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    // 非管理员不能取消提名，被提名者也不能
+    let result = send(&mut context, &[cancel_admin_transfer_ix(&attacker.pubkey())], &[&attacker]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+    let result = send(&mut context, &[cancel_admin_transfer_ix(&new_admin.pubkey())], &[&new_admin]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    // 状态未被改变
+    let bank = get_bank_account_data(&mut context).await;
+    assert_eq!(bank.admin, admin.pubkey(), "管理员不应改变");
+    assert_eq!(bank.pending_admin, Some(new_admin.pubkey()), "提名不应改变");
+
+    println!("未授权的管理员交接测试通过");
+}
@@ -0,0 +1,220 @@
+use anchor_client::solana_sdk::{
+    instruction::AccountMeta,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use anchor_spl::token::spl_token;
+use batch_transfer::{self, BatchReceipt, ErrorCode, TransferInfo};
+
+mod utils_test;
+use utils_test::{get_bank_account, get_batch_receipt};
+use utils_test::program_test_utils::*;
+
+fn with_batch_id(batch_id: [u8; 32]) -> BatchOptions {
+    BatchOptions {
+        batch_id: Some(batch_id),
+        ..BatchOptions::default()
+    }
+}
+
+/// 测试带批次ID的SOL批量转账只能执行一次，不同批次或不同发送者互不影响
+#[tokio::test]
+async fn test_batch_transfer_sol_idempotent() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let other_sender = Keypair::new();
+    let recipient = Keypair::new().pubkey();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), 10 * LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &other_sender.pubkey(), 10 * LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let transfers = vec![TransferInfo {
+        recipient,
+        amount: LAMPORTS_PER_SOL,
+    }];
+    let batch_id = [1u8; 32];
+
+    send(
+        &mut context,
+        &[batch_transfer_sol_with_options_ix(&sender.pubkey(), transfers.clone(), with_batch_id(batch_id))],
+        &[&sender],
+    )
+    .await
+    .expect("带批次ID的批量转账失败");
+    assert_eq!(get_balance(&mut context, &recipient).await, LAMPORTS_PER_SOL);
+
+    let receipt_key = get_batch_receipt(&batch_transfer::ID, &sender.pubkey(), &batch_id);
+    let receipt: BatchReceipt = get_account_data(&mut context, &receipt_key).await;
+    assert_eq!(receipt.sender, sender.pubkey());
+    assert_eq!(receipt.batch_id, batch_id);
+    assert!(receipt.executed_at > 0);
+
+    // 重发同一批次被拒绝，接收者不会收到两次
+    let result = send(
+        &mut context,
+        &[batch_transfer_sol_with_options_ix(&sender.pubkey(), transfers.clone(), with_batch_id(batch_id))],
+        &[&sender],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::BatchAlreadyExecuted);
+    assert_eq!(get_balance(&mut context, &recipient).await, LAMPORTS_PER_SOL);
+
+    // 不同批次ID、其他发送者的同一批次ID都可以执行
+    send(
+        &mut context,
+        &[batch_transfer_sol_with_options_ix(&sender.pubkey(), transfers.clone(), with_batch_id([2u8; 32]))],
+        &[&sender],
+    )
+    .await
+    .expect("新批次的批量转账失败");
+    send(
+        &mut context,
+        &[batch_transfer_sol_with_options_ix(&other_sender.pubkey(), transfers, with_batch_id(batch_id))],
+        &[&other_sender],
+    )
+    .await
+    .expect("其他发送者的批量转账失败");
+    assert_eq!(get_balance(&mut context, &recipient).await, 3 * LAMPORTS_PER_SOL);
+}
+
+/// 测试提供批次ID但缺少回执账户或回执地址错误时拒绝转账
+#[tokio::test]
+async fn test_batch_receipt_invalid_account() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let recipient = Keypair::new().pubkey();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), 10 * LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let transfers = vec![TransferInfo {
+        recipient,
+        amount: LAMPORTS_PER_SOL,
+    }];
+    // 回执账户在固定账户中的位置
+    let receipt_index = 3;
+
+    let mut instruction = batch_transfer_sol_with_options_ix(&sender.pubkey(), transfers.clone(), with_batch_id([1u8; 32]));
+    instruction.accounts[receipt_index] = AccountMeta::new_readonly(batch_transfer::ID, false);
+    let result = send(&mut context, &[instruction], &[&sender]).await;
+    assert_program_error(result, ErrorCode::InvalidBatchReceipt);
+
+    let mut instruction = batch_transfer_sol_with_options_ix(&sender.pubkey(), transfers, with_batch_id([1u8; 32]));
+    instruction.accounts[receipt_index] = AccountMeta::new(Pubkey::new_unique(), false);
+    let result = send(&mut context, &[instruction], &[&sender]).await;
+    assert_program_error(result, ErrorCode::InvalidBatchReceipt);
+    assert_eq!(get_balance(&mut context, &recipient).await, 0);
+}
+
+/// 测试带批次ID的代币批量转账只能执行一次
+#[tokio::test]
+async fn test_batch_transfer_token_idempotent() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let mint = create_mint(&mut context, &spl_token::ID, &mint_authority.pubkey(), 6).await;
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    let recipient = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+    let transfers = vec![TransferInfo {
+        recipient,
+        amount: 1_000,
+    }];
+    let build = || {
+        batch_transfer_token_with_options_ix(
+            &sender.pubkey(),
+            &sender_token,
+            &mint,
+            &spl_token::ID,
+            transfers.clone(),
+            with_batch_id([9u8; 32]),
+        )
+    };
+
+    send(&mut context, &[build()], &[&sender])
+        .await
+        .expect("带批次ID的代币批量转账失败");
+    let result = send(&mut context, &[build()], &[&sender]).await;
+    assert_program_error(result, ErrorCode::BatchAlreadyExecuted);
+    assert_eq!(get_token_balance(&mut context, &recipient).await, 1_000);
+}
+
+/// 测试关闭批次回执退还租金，只能关闭自己的回执，关闭后同一批次ID可以再次执行
+#[tokio::test]
+async fn test_close_batch_receipts() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let attacker = Keypair::new();
+    let recipient = Keypair::new().pubkey();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), 10 * LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &attacker.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let transfers = vec![TransferInfo {
+        recipient,
+        amount: LAMPORTS_PER_SOL,
+    }];
+    let batch_ids = [[1u8; 32], [2u8; 32]];
+    for batch_id in batch_ids {
+        send(
+            &mut context,
+            &[batch_transfer_sol_with_options_ix(&sender.pubkey(), transfers.clone(), with_batch_id(batch_id))],
+            &[&sender],
+        )
+        .await
+        .expect("带批次ID的批量转账失败");
+    }
+    let receipts: Vec<Pubkey> = batch_ids
+        .iter()
+        .map(|batch_id| get_batch_receipt(&batch_transfer::ID, &sender.pubkey(), batch_id))
+        .collect();
+
+    // 其他人不能关闭发送者的回执
+    let result = send(&mut context, &[close_batch_receipts_ix(&attacker.pubkey(), &receipts)], &[&attacker]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    // 非回执账户不能关闭
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+    let result = send(&mut context, &[close_batch_receipts_ix(&sender.pubkey(), &[bank_account])], &[&sender]).await;
+    assert_program_error(result, ErrorCode::InvalidBatchReceipt);
+
+    let rent = get_rent_exempt_minimum(&mut context, &receipts[0]).await;
+    let sender_balance_before = get_balance(&mut context, &sender.pubkey()).await;
+    send(&mut context, &[close_batch_receipts_ix(&sender.pubkey(), &receipts)], &[&sender])
+        .await
+        .expect("关闭批次回执失败");
+    assert_eq!(get_balance(&mut context, &sender.pubkey()).await - sender_balance_before, 2 * rent);
+    for receipt in &receipts {
+        assert!(context.banks_client.get_account(*receipt).await.unwrap().is_none());
+    }
+
+    // 回执关闭后同一批次ID可以再次执行
+    send(
+        &mut context,
+        &[batch_transfer_sol_with_options_ix(&sender.pubkey(), transfers, with_batch_id(batch_ids[0]))],
+        &[&sender],
+    )
+    .await
+    .expect("回执关闭后重新执行批次失败");
+    assert_eq!(get_balance(&mut context, &recipient).await, 3 * LAMPORTS_PER_SOL);
+}
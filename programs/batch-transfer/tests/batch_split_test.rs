@@ -0,0 +1,129 @@
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use batch_transfer::{self, ErrorCode, FeeConfig, SplitInfo};
+
+mod utils_test;
+use utils_test::get_bank_account;
+use utils_test::program_test_utils::*;
+
+/// 测试按权重拆分SOL，各笔金额之和恰好等于总额，手续费按总额计算
+#[tokio::test]
+async fn test_batch_split_sol() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let recipients: Vec<Pubkey> = (0..3).map(|_| Keypair::new().pubkey()).collect();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), 10 * LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+    let fee_config = FeeConfig {
+        bps: 100,
+        ..FeeConfig::default()
+    };
+    set_fee_config(&mut context, &admin, fee_config)
+        .await
+        .expect("设置手续费失败");
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+
+    // 总额不能被权重整除，余数相同，零头 2 分给序号最小的两个接收者
+    let total = 5 * LAMPORTS_PER_SOL;
+    let splits: Vec<SplitInfo> = recipients
+        .iter()
+        .map(|recipient| SplitInfo {
+            recipient: *recipient,
+            weight: 1,
+        })
+        .collect();
+
+    let sender_balance_before = get_balance(&mut context, &sender.pubkey()).await;
+    let bank_balance_before = get_balance(&mut context, &bank_account).await;
+    send(&mut context, &[batch_split_sol_ix(&sender.pubkey(), total, splits)], &[&sender])
+        .await
+        .expect("按权重拆分SOL失败");
+
+    let share = total / 3;
+    assert_eq!(get_balance(&mut context, &recipients[0]).await, share + 1);
+    assert_eq!(get_balance(&mut context, &recipients[1]).await, share + 1);
+    assert_eq!(get_balance(&mut context, &recipients[2]).await, share);
+    let fee = total / 100;
+    assert_eq!(sender_balance_before - get_balance(&mut context, &sender.pubkey()).await, total + fee);
+    assert_eq!(get_balance(&mut context, &bank_account).await - bank_balance_before, fee);
+}
+
+/// 测试权重无效时拒绝拆分
+#[tokio::test]
+async fn test_batch_split_invalid_weights() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let recipient = Keypair::new().pubkey();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), 10 * LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let splits = vec![SplitInfo { recipient, weight: 0 }];
+    let result = send(&mut context, &[batch_split_sol_ix(&sender.pubkey(), LAMPORTS_PER_SOL, splits)], &[&sender]).await;
+    assert_program_error(result, ErrorCode::InvalidSplitWeights);
+
+    let result = send(&mut context, &[batch_split_sol_ix(&sender.pubkey(), LAMPORTS_PER_SOL, vec![])], &[&sender]).await;
+    assert_program_error(result, ErrorCode::EmptyTransfers);
+    assert_eq!(get_balance(&mut context, &recipient).await, 0);
+}
+
+/// 测试按权重拆分代币，支持SPL Token和Token-2022程序
+#[tokio::test]
+async fn test_batch_split_token() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let admin = Keypair::new();
+        let sender = Keypair::new();
+        let mint_authority = Keypair::new();
+
+        let mut program_test = program_test();
+        add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+        add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+        let mut context = program_test.start_with_context().await;
+        setup_bank_account(&mut context, &admin, 10_000).await;
+
+        let mint = create_mint(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+        let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+        mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+        let mut recipients = Vec::new();
+        for _ in 0..3 {
+            recipients.push(create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await);
+        }
+
+        // 100 按 1:2:4 拆分：14.28、28.57、57.14，零头 1 分给余数最大的第二个接收者
+        let splits = vec![
+            SplitInfo {
+                recipient: recipients[0],
+                weight: 1,
+            },
+            SplitInfo {
+                recipient: recipients[1],
+                weight: 2,
+            },
+            SplitInfo {
+                recipient: recipients[2],
+                weight: 4,
+            },
+        ];
+        let instruction = batch_split_token_ix(&sender.pubkey(), &sender_token, &mint, &token_program, 100, splits);
+        send(&mut context, &[instruction], &[&sender])
+            .await
+            .expect("按权重拆分代币失败");
+
+        assert_eq!(get_token_balance(&mut context, &recipients[0]).await, 14);
+        assert_eq!(get_token_balance(&mut context, &recipients[1]).await, 29);
+        assert_eq!(get_token_balance(&mut context, &recipients[2]).await, 57);
+        assert_eq!(get_token_balance(&mut context, &sender_token).await, 1_000_000 - 100);
+    }
+}
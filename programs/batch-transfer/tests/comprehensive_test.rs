@@ -1,5 +1,4 @@
 use batch_transfer::{self, BankAccount};
-use anchor_lang::prelude::*;
 use anchor_client::solana_sdk::signer::keypair::Keypair;
 use anchor_client::solana_sdk::signer::Signer;
 
@@ -17,17 +16,22 @@ fn test_comprehensive_batch_transfer() {
     
     // 创建管理员和发送者账户
     let admin = Keypair::new();
-    let sender = Keypair::new();
+    let _sender = Keypair::new();
     
     // 获取银行账户
-    let (bank_account, _) = utils_test::get_bank_account(&program.id());
+    let (_bank_account, _) = utils_test::get_bank_account(&program.id());
     
     // 步骤2: 初始化银行账户
     println!("步骤2: 初始化银行账户");
-    let bank_account_data = BankAccount {
+    let _bank_account_data = BankAccount {
         admin: admin.pubkey(),
-        fee: 5_000_000, // 0.005 SOL
+        fee_config: batch_transfer::FeeConfig {
+            flat_fee: 5_000_000, // 0.005 SOL
+            ..Default::default()
+        },
         is_initialized: true,
+        version: BankAccount::VERSION,
+        ..Default::default()
     };
     
     // 步骤3: 设置交易费用
@@ -44,13 +48,11 @@ fn test_comprehensive_batch_transfer() {
     let amount2 = 20_000_000; // 0.02 SOL
     let amount3 = 30_000_000; // 0.03 SOL
     
-    let recipients = vec![
-        recipient1.pubkey(),
+    let recipients = [recipient1.pubkey(),
         recipient2.pubkey(),
-        recipient3.pubkey(),
-    ];
+        recipient3.pubkey()];
     
-    let amounts = vec![amount1, amount2, amount3];
+    let amounts = [amount1, amount2, amount3];
     
     // 步骤5: 计算总转账金额和费用
     println!("步骤5: 计算总转账金额和费用");
@@ -0,0 +1,387 @@
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+use anchor_spl::token::spl_token;
+use batch_transfer::{
+    self, BankConfig, ErrorCode, FeeConfig, MintFeeConfig, PendingConfigChange, PendingMintFeeConfigChange,
+    TransferInfo, DEFAULT_CONFIG_DELAY, MAX_CONFIG_DELAY,
+};
+
+mod utils_test;
+use utils_test::get_mint_fee_config;
+use utils_test::program_test_utils::*;
+
+const DELAY: i64 = 24 * 60 * 60;
+
+// 固定手续费配置，开启降费跳过延迟
+fn flat_fee_config(flat_fee: u64, config_delay: i64) -> BankConfig {
+    BankConfig {
+        fee_config: FeeConfig {
+            flat_fee,
+            ..FeeConfig::default()
+        },
+        config_delay,
+        allow_fee_decrease_bypass: true,
+    }
+}
+
+/// 测试新部署的银行账户默认带有变更延迟，不能在同一笔交易中提交并执行提高手续费的变更
+#[tokio::test]
+async fn test_initialize_default_config_delay() {
+    let admin = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    set_program_data(&mut context, Some(admin.pubkey()));
+    send(&mut context, &[initialize_ix(&admin.pubkey())], &[&admin])
+        .await
+        .expect("初始化失败");
+
+    let bank = get_bank_account_data(&mut context).await;
+    assert_eq!(bank.config_delay, DEFAULT_CONFIG_DELAY);
+    assert!(!bank.allow_fee_decrease_bypass);
+
+    let now = get_unix_timestamp(&mut context).await;
+    let result = send(
+        &mut context,
+        &[
+            queue_config_change_ix(&admin.pubkey(), flat_fee_config(10_000, 0), now),
+            execute_config_change_ix(),
+        ],
+        &[&admin],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::ConfigChangeTooEarly);
+    assert_eq!(get_bank_account_data(&mut context).await.fee_config, FeeConfig::default());
+}
+
+/// 测试设置延迟后，提高手续费需要提前公告，到达生效时间后任何人都可以执行
+#[tokio::test]
+async fn test_config_change_timelock() {
+    let admin = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    // 初始延迟为0，设置延迟的变更可以立即生效
+    let now = get_unix_timestamp(&mut context).await;
+    send(
+        &mut context,
+        &[
+            queue_config_change_ix(&admin.pubkey(), flat_fee_config(10_000, DELAY), now),
+            execute_config_change_ix(),
+        ],
+        &[&admin],
+    )
+    .await
+    .expect("设置变更延迟失败");
+    let bank = get_bank_account_data(&mut context).await;
+    assert_eq!(bank.config_delay, DELAY);
+    assert_eq!(bank.pending_config, None);
+
+    // 提高手续费时生效时间必须不早于 当前时间 + 延迟
+    let now = get_unix_timestamp(&mut context).await;
+    let increase = flat_fee_config(20_000, DELAY);
+    let result = send(
+        &mut context,
+        &[queue_config_change_ix(&admin.pubkey(), increase, now + DELAY - 1)],
+        &[&admin],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::ConfigChangeTooEarly);
+
+    let eta = now + DELAY;
+    send(&mut context, &[queue_config_change_ix(&admin.pubkey(), increase, eta)], &[&admin])
+        .await
+        .expect("提交配置变更失败");
+    assert_eq!(
+        get_bank_account_data(&mut context).await.pending_config,
+        Some(PendingConfigChange { config: increase, eta })
+    );
+
+    // 已有待生效的变更时不能再次提交
+    let result = send(&mut context, &[queue_config_change_ix(&admin.pubkey(), increase, eta + 1)], &[&admin]).await;
+    assert_program_error(result, ErrorCode::ConfigChangePending);
+
+    // 未到生效时间不能执行
+    let result = send(&mut context, &[execute_config_change_ix()], &[]).await;
+    assert_program_error(result, ErrorCode::ConfigChangeNotReady);
+    assert_eq!(get_bank_account_data(&mut context).await.fee_config.flat_fee, 10_000);
+
+    // 到达生效时间后由非管理员执行
+    set_unix_timestamp(&mut context, eta).await;
+    send(&mut context, &[execute_config_change_ix()], &[])
+        .await
+        .expect("执行配置变更失败");
+    let bank = get_bank_account_data(&mut context).await;
+    assert_eq!(bank.config(), increase);
+    assert_eq!(bank.pending_config, None);
+
+    let result = send(&mut context, &[execute_config_change_ix()], &[]).await;
+    assert_program_error(result, ErrorCode::NoPendingConfigChange);
+}
+
+/// 测试开启降费跳过延迟时，只降低手续费的变更可以跳过延迟立即生效
+#[tokio::test]
+async fn test_fee_decrease_bypasses_delay() {
+    let admin = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let now = get_unix_timestamp(&mut context).await;
+    send(
+        &mut context,
+        &[
+            queue_config_change_ix(&admin.pubkey(), flat_fee_config(10_000, DELAY), now),
+            execute_config_change_ix(),
+        ],
+        &[&admin],
+    )
+    .await
+    .expect("设置手续费和变更延迟失败");
+
+    // 降低手续费且不缩短延迟，立即生效
+    set_fee_config(&mut context, &admin, flat_fee_config(5_000, DELAY).fee_config)
+        .await
+        .expect("降低手续费失败");
+    assert_eq!(get_bank_account_data(&mut context).await.fee_config.flat_fee, 5_000);
+
+    // 缩短延迟不能跳过延迟
+    let now = get_unix_timestamp(&mut context).await;
+    let result = send(
+        &mut context,
+        &[queue_config_change_ix(&admin.pubkey(), flat_fee_config(5_000, 0), now)],
+        &[&admin],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::ConfigChangeTooEarly);
+
+    // 延迟超出上限
+    let result = send(
+        &mut context,
+        &[queue_config_change_ix(&admin.pubkey(), flat_fee_config(5_000, MAX_CONFIG_DELAY + 1), now + DELAY)],
+        &[&admin],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::InvalidConfigDelay);
+
+    // 关闭降费跳过延迟只会让配置更严格，可以立即生效；关闭后降低手续费也需要等待延迟
+    let strict = BankConfig {
+        allow_fee_decrease_bypass: false,
+        ..flat_fee_config(5_000, DELAY)
+    };
+    let now = get_unix_timestamp(&mut context).await;
+    send(
+        &mut context,
+        &[queue_config_change_ix(&admin.pubkey(), strict, now), execute_config_change_ix()],
+        &[&admin],
+    )
+    .await
+    .expect("关闭降费跳过延迟失败");
+    let result = set_fee_config(&mut context, &admin, flat_fee_config(1_000, DELAY).fee_config).await;
+    assert_program_error(result, ErrorCode::ConfigChangeTooEarly);
+    assert_eq!(get_bank_account_data(&mut context).await.fee_config.flat_fee, 5_000);
+
+    // 重新开启同样需要等待延迟
+    let result = send(
+        &mut context,
+        &[queue_config_change_ix(&admin.pubkey(), flat_fee_config(5_000, DELAY), now)],
+        &[&admin],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::ConfigChangeTooEarly);
+}
+
+/// 测试管理员取消待生效的配置变更，非管理员不能提交或取消
+#[tokio::test]
+async fn test_cancel_config_change() {
+    let admin = Keypair::new();
+    let attacker = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &attacker.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let now = get_unix_timestamp(&mut context).await;
+    let result = send(
+        &mut context,
+        &[queue_config_change_ix(&attacker.pubkey(), flat_fee_config(10_000, 0), now)],
+        &[&attacker],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    let result = send(&mut context, &[cancel_config_change_ix(&admin.pubkey())], &[&admin]).await;
+    assert_program_error(result, ErrorCode::NoPendingConfigChange);
+
+    send(
+        &mut context,
+        &[queue_config_change_ix(&admin.pubkey(), flat_fee_config(10_000, 0), now + DELAY)],
+        &[&admin],
+    )
+    .await
+    .expect("提交配置变更失败");
+
+    let result = send(&mut context, &[cancel_config_change_ix(&attacker.pubkey())], &[&attacker]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    send(&mut context, &[cancel_config_change_ix(&admin.pubkey())], &[&admin])
+        .await
+        .expect("取消配置变更失败");
+    let bank = get_bank_account_data(&mut context).await;
+    assert_eq!(bank.pending_config, None);
+
+    // 取消后到达原生效时间也不会生效
+    set_unix_timestamp(&mut context, now + DELAY).await;
+    let result = send(&mut context, &[execute_config_change_ix()], &[]).await;
+    assert_program_error(result, ErrorCode::NoPendingConfigChange);
+    assert_eq!(get_bank_account_data(&mut context).await.fee_config, FeeConfig::default());
+}
+
+/// 测试代币手续费配置变更同样受变更延迟约束，生效前仍以SOL收取手续费
+#[tokio::test]
+async fn test_mint_fee_config_timelock() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let now = get_unix_timestamp(&mut context).await;
+    send(
+        &mut context,
+        &[
+            queue_config_change_ix(&admin.pubkey(), flat_fee_config(10_000, DELAY), now),
+            execute_config_change_ix(),
+        ],
+        &[&admin],
+    )
+    .await
+    .expect("设置变更延迟失败");
+
+    let mint = create_mint(&mut context, &spl_token::ID, &mint_authority.pubkey(), 6).await;
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    let recipient = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+    let mint_fee_config_address = get_mint_fee_config(&batch_transfer::ID, &mint);
+    let transfers = vec![TransferInfo {
+        recipient,
+        amount: 100_000,
+    }];
+
+    // 首次启用代币手续费不能跳过延迟
+    let fee_config = FeeConfig {
+        bps: 100,
+        ..FeeConfig::default()
+    };
+    let now = get_unix_timestamp(&mut context).await;
+    let queue = |fee_config: FeeConfig, eta: i64| {
+        queue_mint_fee_config_change_ix(&admin.pubkey(), &mint, &spl_token::ID, fee_config, eta)
+    };
+    let result = send(&mut context, &[queue(fee_config, now + DELAY - 1)], &[&admin]).await;
+    assert_program_error(result, ErrorCode::ConfigChangeTooEarly);
+
+    let eta = now + DELAY;
+    send(&mut context, &[queue(fee_config, eta)], &[&admin])
+        .await
+        .expect("提交代币手续费配置变更失败");
+    let mint_fee_config: MintFeeConfig = get_account_data(&mut context, &mint_fee_config_address).await;
+    assert_eq!(mint_fee_config.fee_config, None);
+    assert_eq!(
+        mint_fee_config.pending_config,
+        Some(PendingMintFeeConfigChange { fee_config, eta })
+    );
+
+    let result = send(&mut context, &[queue(fee_config, eta + 1)], &[&admin]).await;
+    assert_program_error(result, ErrorCode::ConfigChangePending);
+
+    // 未到生效时间不能执行，批量转账仍以SOL收取手续费，不能提前传入手续费金库
+    let result = send(&mut context, &[execute_mint_fee_config_change_ix(&mint)], &[]).await;
+    assert_program_error(result, ErrorCode::ConfigChangeNotReady);
+
+    let result = send(
+        &mut context,
+        &[batch_transfer_token_with_token_fee_ix(&sender.pubkey(), &sender_token, &mint, &spl_token::ID, transfers.clone())],
+        &[&sender],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::IncompleteTokenFeeAccounts);
+    send(
+        &mut context,
+        &[batch_transfer_token_ix(&sender.pubkey(), &sender_token, &mint, &spl_token::ID, transfers.clone())],
+        &[&sender],
+    )
+    .await
+    .expect("代币手续费生效前的批量转账失败");
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 900_000);
+
+    // 到达生效时间后由非管理员执行，之后必须以代币支付手续费
+    set_unix_timestamp(&mut context, eta).await;
+    send(&mut context, &[execute_mint_fee_config_change_ix(&mint)], &[])
+        .await
+        .expect("执行代币手续费配置变更失败");
+    let mint_fee_config: MintFeeConfig = get_account_data(&mut context, &mint_fee_config_address).await;
+    assert_eq!(mint_fee_config.fee_config, Some(fee_config));
+    assert_eq!(mint_fee_config.pending_config, None);
+
+    let result = send(
+        &mut context,
+        &[batch_transfer_token_ix(&sender.pubkey(), &sender_token, &mint, &spl_token::ID, transfers.clone())],
+        &[&sender],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::TokenFeeVaultRequired);
+    send(
+        &mut context,
+        &[batch_transfer_token_with_token_fee_ix(&sender.pubkey(), &sender_token, &mint, &spl_token::ID, transfers)],
+        &[&sender],
+    )
+    .await
+    .expect("以代币收取手续费的批量转账失败");
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 900_000 - 100_000 - 1_000);
+
+    // 提高代币手续费需要等待延迟，只降低手续费可以立即生效
+    let raised = FeeConfig {
+        bps: 200,
+        ..FeeConfig::default()
+    };
+    let now = get_unix_timestamp(&mut context).await;
+    let result = send(&mut context, &[queue(raised, now)], &[&admin]).await;
+    assert_program_error(result, ErrorCode::ConfigChangeTooEarly);
+
+    let lowered = FeeConfig {
+        bps: 50,
+        ..FeeConfig::default()
+    };
+    set_mint_fee_config(&mut context, &admin, &mint, &spl_token::ID, lowered)
+        .await
+        .expect("降低代币手续费失败");
+    let mint_fee_config: MintFeeConfig = get_account_data(&mut context, &mint_fee_config_address).await;
+    assert_eq!(mint_fee_config.fee_config, Some(lowered));
+
+    // 取消后到达原生效时间也不会生效
+    send(&mut context, &[queue(raised, now + DELAY)], &[&admin])
+        .await
+        .expect("提交代币手续费配置变更失败");
+    send(&mut context, &[cancel_mint_fee_config_change_ix(&admin.pubkey(), &mint)], &[&admin])
+        .await
+        .expect("取消代币手续费配置变更失败");
+    set_unix_timestamp(&mut context, now + DELAY).await;
+    let result = send(&mut context, &[execute_mint_fee_config_change_ix(&mint)], &[]).await;
+    assert_program_error(result, ErrorCode::NoPendingConfigChange);
+    let mint_fee_config: MintFeeConfig = get_account_data(&mut context, &mint_fee_config_address).await;
+    assert_eq!(mint_fee_config.fee_config, Some(lowered));
+}
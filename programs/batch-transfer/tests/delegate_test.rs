@@ -0,0 +1,105 @@
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use batch_transfer::{self, ErrorCode, TransferInfo};
+
+mod utils_test;
+use utils_test::get_bank_account;
+use utils_test::program_test_utils::*;
+
+/// 测试代理人在授权额度内代替所有者批量转账代币
+#[tokio::test]
+async fn test_batch_transfer_token_as_delegate() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let admin = Keypair::new();
+        let owner = Keypair::new();
+        let delegate = Keypair::new();
+        let mint_authority = Keypair::new();
+
+        let mut program_test = program_test();
+        add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+        add_funded_account(&mut program_test, &owner.pubkey(), LAMPORTS_PER_SOL);
+        add_funded_account(&mut program_test, &delegate.pubkey(), LAMPORTS_PER_SOL);
+        let mut context = program_test.start_with_context().await;
+        setup_bank_account(&mut context, &admin, 10_000).await;
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+
+        let mint = create_mint(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+        let treasury = create_token_account(&mut context, &mint, &owner.pubkey()).await;
+        mint_tokens(&mut context, &mint, &mint_authority, &treasury, 1_000_000).await;
+        approve_delegate(&mut context, &treasury, &owner, &delegate.pubkey(), 5_000).await;
+
+        let recipient1 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+        let recipient2 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+        let transfers = vec![
+            TransferInfo {
+                recipient: recipient1,
+                amount: 1_000,
+            },
+            TransferInfo {
+                recipient: recipient2,
+                amount: 2_000,
+            },
+        ];
+
+        let owner_balance_before = get_balance(&mut context, &owner.pubkey()).await;
+        let delegate_balance_before = get_balance(&mut context, &delegate.pubkey()).await;
+        let bank_balance_before = get_balance(&mut context, &bank_account).await;
+        let instruction = batch_transfer_token_ix(&delegate.pubkey(), &treasury, &mint, &token_program, transfers.clone());
+        send(&mut context, &[instruction], &[&delegate])
+            .await
+            .expect("代理人批量转账失败");
+
+        assert_eq!(get_token_balance(&mut context, &recipient1).await, 1_000);
+        assert_eq!(get_token_balance(&mut context, &recipient2).await, 2_000);
+        assert_eq!(get_token_balance(&mut context, &treasury).await, 1_000_000 - 3_000);
+        // SOL手续费由代理人支付，所有者的SOL余额不变
+        assert_eq!(get_balance(&mut context, &owner.pubkey()).await, owner_balance_before);
+        assert_eq!(delegate_balance_before - get_balance(&mut context, &delegate.pubkey()).await, 10_000);
+        assert_eq!(get_balance(&mut context, &bank_account).await - bank_balance_before, 10_000);
+
+        // 剩余授权额度为2_000，再转3_000应失败
+        let instruction = batch_transfer_token_ix(&delegate.pubkey(), &treasury, &mint, &token_program, transfers);
+        let result = send(&mut context, &[instruction], &[&delegate]).await;
+        assert_program_error(result, ErrorCode::InsufficientDelegatedAmount);
+        assert_eq!(get_token_balance(&mut context, &treasury).await, 1_000_000 - 3_000);
+    }
+}
+
+/// 测试既不是所有者也不是代理人的签名者不能转出代币
+#[tokio::test]
+async fn test_batch_transfer_token_rejects_non_delegate() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let admin = Keypair::new();
+        let owner = Keypair::new();
+        let delegate = Keypair::new();
+        let stranger = Keypair::new();
+        let mint_authority = Keypair::new();
+
+        let mut program_test = program_test();
+        add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+        add_funded_account(&mut program_test, &owner.pubkey(), LAMPORTS_PER_SOL);
+        add_funded_account(&mut program_test, &stranger.pubkey(), LAMPORTS_PER_SOL);
+        let mut context = program_test.start_with_context().await;
+        setup_bank_account(&mut context, &admin, 10_000).await;
+
+        let mint = create_mint(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+        let treasury = create_token_account(&mut context, &mint, &owner.pubkey()).await;
+        mint_tokens(&mut context, &mint, &mint_authority, &treasury, 1_000_000).await;
+        approve_delegate(&mut context, &treasury, &owner, &delegate.pubkey(), 5_000).await;
+
+        let recipient = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+        let transfers = vec![TransferInfo {
+            recipient,
+            amount: 1_000,
+        }];
+        let instruction = batch_transfer_token_ix(&stranger.pubkey(), &treasury, &mint, &token_program, transfers);
+        let result = send(&mut context, &[instruction], &[&stranger]).await;
+        assert!(result.is_err(), "非所有者且非代理人不应能转出代币");
+        assert_eq!(get_token_balance(&mut context, &treasury).await, 1_000_000);
+        assert_eq!(get_token_balance(&mut context, &recipient).await, 0);
+    }
+}
@@ -0,0 +1,360 @@
+use anchor_client::solana_sdk::{
+    instruction::InstructionError,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use batch_transfer::{self, Distribution, ErrorCode, TransferInfo};
+use solana_program_test::ProgramTestContext;
+
+mod utils_test;
+use utils_test::{get_bank_account, get_distribution, get_distribution_vault};
+use utils_test::program_test_utils::*;
+
+fn entries(recipients: &[Pubkey], amounts: &[u64]) -> Vec<TransferInfo> {
+    recipients
+        .iter()
+        .zip(amounts)
+        .map(|(recipient, amount)| TransferInfo {
+            recipient: *recipient,
+            amount: *amount,
+        })
+        .collect()
+}
+
+async fn account_exists(context: &mut ProgramTestContext, pubkey: &Pubkey) -> bool {
+    context.banks_client.get_account(*pubkey).await.unwrap().is_some()
+}
+
+/// 测试SOL分发的完整流程：托管、分块上传、分多次执行、跳过无效接收者，完成后取消取回跳过的金额和租金
+#[tokio::test]
+async fn test_sol_distribution_lifecycle() {
+    let admin = Keypair::new();
+    let creator = Keypair::new();
+    let attacker = Keypair::new();
+    let recipients: Vec<Pubkey> = (0..5).map(|_| Keypair::new().pubkey()).collect();
+    // 第3笔金额低于新账户的租金豁免最低余额，执行时被跳过
+    let amounts = [LAMPORTS_PER_SOL, LAMPORTS_PER_SOL, 100, 2 * LAMPORTS_PER_SOL, LAMPORTS_PER_SOL];
+    let total_amount: u64 = amounts.iter().sum();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &creator.pubkey(), 20 * LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &attacker.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+    let distribution = get_distribution(&batch_transfer::ID, &creator.pubkey(), 7);
+    let rent = context.banks_client.get_rent().await.unwrap();
+
+    // 创建时托管分发总额并收取手续费
+    let creator_balance_before = get_balance(&mut context, &creator.pubkey()).await;
+    let bank_balance_before = get_balance(&mut context, &bank_account).await;
+    send(
+        &mut context,
+        &[create_distribution_ix(&creator.pubkey(), 7, 5, total_amount)],
+        &[&creator],
+    )
+    .await
+    .expect("创建分发失败");
+    assert_eq!(
+        creator_balance_before - get_balance(&mut context, &creator.pubkey()).await,
+        total_amount + 10_000 + rent.minimum_balance(Distribution::space(0))
+    );
+    assert_eq!(get_balance(&mut context, &bank_account).await - bank_balance_before, 10_000);
+
+    // 上传完成之前不能执行
+    let result = send(&mut context, &[crank_distribution_ix(&distribution, &recipients, 5)], &[]).await;
+    assert_program_error(result, ErrorCode::DistributionNotReady);
+
+    let all_entries = entries(&recipients, &amounts);
+    send(
+        &mut context,
+        &[upload_distribution_entries_ix(&creator.pubkey(), &distribution, all_entries[..3].to_vec())],
+        &[&creator],
+    )
+    .await
+    .expect("上传第一块转账列表失败");
+
+    // 非创建者不能上传
+    let instruction = upload_distribution_entries_ix(&attacker.pubkey(), &distribution, all_entries[3..].to_vec());
+    let result = send(&mut context, &[instruction], &[&attacker]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    // 最后一块的金额之和与分发总额不一致
+    let mut wrong_entries = all_entries[3..].to_vec();
+    wrong_entries[1].amount -= 1;
+    let instruction = upload_distribution_entries_ix(&creator.pubkey(), &distribution, wrong_entries);
+    let result = send(&mut context, &[instruction], &[&creator]).await;
+    assert_program_error(result, ErrorCode::InvalidDistributionEntries);
+
+    // 超过总条数
+    let mut extra_entries = all_entries[3..].to_vec();
+    extra_entries.push(all_entries[0].clone());
+    let instruction = upload_distribution_entries_ix(&creator.pubkey(), &distribution, extra_entries);
+    let result = send(&mut context, &[instruction], &[&creator]).await;
+    assert_program_error(result, ErrorCode::InvalidDistributionEntries);
+
+    send(
+        &mut context,
+        &[upload_distribution_entries_ix(&creator.pubkey(), &distribution, all_entries[3..].to_vec())],
+        &[&creator],
+    )
+    .await
+    .expect("上传第二块转账列表失败");
+
+    // 扩容的租金由创建者另外支付，托管金额不变
+    let state: Distribution = get_account_data(&mut context, &distribution).await;
+    assert_eq!(state.uploaded_entries, 5);
+    assert_eq!(state.uploaded_amount, total_amount);
+    assert_eq!(state.cursor, 0);
+    let account = context.banks_client.get_account(distribution).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), Distribution::space(5));
+    assert_eq!(account.lamports, rent.minimum_balance(Distribution::space(5)) + total_amount);
+
+    // 任何人都可以执行，第一次执行前两笔
+    send(&mut context, &[crank_distribution_ix(&distribution, &recipients[..2], 2)], &[])
+        .await
+        .expect("执行分发失败");
+    assert_eq!(get_balance(&mut context, &recipients[0]).await, LAMPORTS_PER_SOL);
+    assert_eq!(get_balance(&mut context, &recipients[1]).await, LAMPORTS_PER_SOL);
+    assert_eq!(get_account_data::<Distribution>(&mut context, &distribution).await.cursor, 2);
+
+    // 传入的账户与转账列表不一致时整笔失败
+    let (result, logs) = send_with_logs(
+        &mut context,
+        &[crank_distribution_ix(&distribution, &[recipients[2], recipients[4], recipients[3]], 3)],
+        &[],
+    )
+    .await;
+    assert_eq!(
+        result,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(u32::from(ErrorCode::InvalidRecipient))
+        ))
+    );
+    assert!(logs.iter().any(|log| log.contains("转账序号: 3")), "{:?}", logs);
+
+    // max_items 超过剩余条数时执行到末尾，低于租金豁免的一笔被跳过
+    send(&mut context, &[crank_distribution_ix(&distribution, &recipients[2..], 10)], &[])
+        .await
+        .expect("执行剩余分发失败");
+    assert_eq!(get_balance(&mut context, &recipients[2]).await, 0);
+    assert_eq!(get_balance(&mut context, &recipients[3]).await, 2 * LAMPORTS_PER_SOL);
+    assert_eq!(get_balance(&mut context, &recipients[4]).await, LAMPORTS_PER_SOL);
+    let state: Distribution = get_account_data(&mut context, &distribution).await;
+    assert_eq!(state.cursor, 5);
+    assert_eq!(state.distributed_amount, total_amount - 100);
+    assert_eq!(state.skipped_amount, 100);
+
+    let result = send(&mut context, &[crank_distribution_ix(&distribution, &[], 1)], &[]).await;
+    assert_program_error(result, ErrorCode::DistributionCompleted);
+
+    // 非创建者不能取消
+    let result = send(
+        &mut context,
+        &[cancel_distribution_ix(&attacker.pubkey(), &distribution)],
+        &[&attacker],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    // 完成后取消，取回被跳过的金额和账户租金
+    let creator_balance_before = get_balance(&mut context, &creator.pubkey()).await;
+    send(&mut context, &[cancel_distribution_ix(&creator.pubkey(), &distribution)], &[&creator])
+        .await
+        .expect("取消分发失败");
+    assert_eq!(
+        get_balance(&mut context, &creator.pubkey()).await - creator_balance_before,
+        100 + rent.minimum_balance(Distribution::space(5))
+    );
+    assert!(!account_exists(&mut context, &distribution).await);
+}
+
+/// 测试中途取消SOL分发退还尚未发出的金额，取消后不能继续执行
+#[tokio::test]
+async fn test_cancel_sol_distribution_midway() {
+    let admin = Keypair::new();
+    let creator = Keypair::new();
+    let recipients: Vec<Pubkey> = (0..3).map(|_| Keypair::new().pubkey()).collect();
+    let amounts = [LAMPORTS_PER_SOL, 2 * LAMPORTS_PER_SOL, 3 * LAMPORTS_PER_SOL];
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &creator.pubkey(), 20 * LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+    let distribution = get_distribution(&batch_transfer::ID, &creator.pubkey(), 1);
+
+    // 总条数或总额为0
+    let result = send(
+        &mut context,
+        &[create_distribution_ix(&creator.pubkey(), 1, 0, LAMPORTS_PER_SOL)],
+        &[&creator],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::InvalidDistributionConfig);
+
+    send(
+        &mut context,
+        &[
+            create_distribution_ix(&creator.pubkey(), 1, 3, 6 * LAMPORTS_PER_SOL),
+            upload_distribution_entries_ix(&creator.pubkey(), &distribution, entries(&recipients, &amounts)),
+        ],
+        &[&creator],
+    )
+    .await
+    .expect("创建并上传分发失败");
+    send(&mut context, &[crank_distribution_ix(&distribution, &recipients[..1], 1)], &[])
+        .await
+        .expect("执行分发失败");
+
+    let rent_exempt_minimum = get_rent_exempt_minimum(&mut context, &distribution).await;
+    let creator_balance_before = get_balance(&mut context, &creator.pubkey()).await;
+    send(&mut context, &[cancel_distribution_ix(&creator.pubkey(), &distribution)], &[&creator])
+        .await
+        .expect("取消分发失败");
+    assert_eq!(
+        get_balance(&mut context, &creator.pubkey()).await - creator_balance_before,
+        5 * LAMPORTS_PER_SOL + rent_exempt_minimum
+    );
+
+    let result = send(&mut context, &[crank_distribution_ix(&distribution, &recipients[1..], 2)], &[]).await;
+    assert!(result.is_err(), "取消后不应继续执行");
+    assert_eq!(get_balance(&mut context, &recipients[0]).await, LAMPORTS_PER_SOL);
+    assert_eq!(get_balance(&mut context, &recipients[1]).await, 0);
+}
+
+/// 测试暂停期间不能创建或执行分发，取消不受影响
+#[tokio::test]
+async fn test_distribution_paused() {
+    let admin = Keypair::new();
+    let creator = Keypair::new();
+    let recipient = Keypair::new().pubkey();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &creator.pubkey(), 10 * LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+    let distribution = get_distribution(&batch_transfer::ID, &creator.pubkey(), 1);
+
+    send(
+        &mut context,
+        &[
+            create_distribution_ix(&creator.pubkey(), 1, 1, LAMPORTS_PER_SOL),
+            upload_distribution_entries_ix(&creator.pubkey(), &distribution, entries(&[recipient], &[LAMPORTS_PER_SOL])),
+        ],
+        &[&creator],
+    )
+    .await
+    .expect("创建并上传分发失败");
+
+    send(&mut context, &[pause_ix(&admin.pubkey())], &[&admin])
+        .await
+        .expect("暂停失败");
+    let result = send(&mut context, &[crank_distribution_ix(&distribution, &[recipient], 1)], &[]).await;
+    assert_program_error(result, ErrorCode::Paused);
+    let result = send(
+        &mut context,
+        &[create_distribution_ix(&creator.pubkey(), 2, 1, LAMPORTS_PER_SOL)],
+        &[&creator],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::Paused);
+
+    send(&mut context, &[cancel_distribution_ix(&creator.pubkey(), &distribution)], &[&creator])
+        .await
+        .expect("暂停期间取消分发失败");
+    assert_eq!(get_balance(&mut context, &recipient).await, 0);
+    assert!(!account_exists(&mut context, &distribution).await);
+}
+
+/// 测试代币分发：托管到金库、执行时跳过冻结的接收者，完成后取消退还剩余代币并关闭金库
+#[tokio::test]
+async fn test_token_distribution() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let admin = Keypair::new();
+        let creator = Keypair::new();
+        let mint_authority = Keypair::new();
+
+        let mut program_test = program_test();
+        add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+        add_funded_account(&mut program_test, &creator.pubkey(), LAMPORTS_PER_SOL);
+        let mut context = program_test.start_with_context().await;
+        setup_bank_account(&mut context, &admin, 10_000).await;
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+
+        let mint = create_mint_with_freeze_authority(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+        let creator_token = create_token_account(&mut context, &mint, &creator.pubkey()).await;
+        mint_tokens(&mut context, &mint, &mint_authority, &creator_token, 1_000_000).await;
+        let valid1 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+        let frozen = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+        freeze_token_account(&mut context, &mint, &mint_authority, &frozen).await;
+        let valid2 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+        let recipients = [valid1, frozen, valid2];
+
+        let distribution = get_distribution(&batch_transfer::ID, &creator.pubkey(), 3);
+        let vault = get_distribution_vault(&batch_transfer::ID, &distribution);
+        let bank_balance_before = get_balance(&mut context, &bank_account).await;
+        send(
+            &mut context,
+            &[
+                create_token_distribution_ix(&creator.pubkey(), &creator_token, &mint, &token_program, 3, 3, 6_000),
+                upload_distribution_entries_ix(
+                    &creator.pubkey(),
+                    &distribution,
+                    entries(&recipients, &[1_000, 2_000, 3_000]),
+                ),
+            ],
+            &[&creator],
+        )
+        .await
+        .expect("创建并上传代币分发失败");
+        assert_eq!(get_token_balance(&mut context, &vault).await, 6_000);
+        assert_eq!(get_token_balance(&mut context, &creator_token).await, 1_000_000 - 6_000);
+        assert_eq!(get_balance(&mut context, &bank_account).await - bank_balance_before, 10_000);
+
+        // 代币分发缺少金库等账户
+        let result = send(&mut context, &[crank_distribution_ix(&distribution, &recipients, 3)], &[]).await;
+        assert_program_error(result, ErrorCode::InvalidDistributionAccounts);
+
+        send(
+            &mut context,
+            &[crank_token_distribution_ix(&distribution, &mint, &token_program, &recipients, 3)],
+            &[],
+        )
+        .await
+        .expect("执行代币分发失败");
+        assert_eq!(get_token_balance(&mut context, &valid1).await, 1_000);
+        assert_eq!(get_token_balance(&mut context, &frozen).await, 0);
+        assert_eq!(get_token_balance(&mut context, &valid2).await, 3_000);
+        assert_eq!(get_token_balance(&mut context, &vault).await, 2_000);
+        let state: Distribution = get_account_data(&mut context, &distribution).await;
+        assert_eq!(state.cursor, 3);
+        assert_eq!(state.skipped_amount, 2_000);
+
+        // 取消后被跳过的代币退还到创建者指定的账户，金库和分发账户的租金退还创建者
+        let creator_balance_before = get_balance(&mut context, &creator.pubkey()).await;
+        let rent_lamports =
+            get_balance(&mut context, &vault).await + get_balance(&mut context, &distribution).await;
+        send(
+            &mut context,
+            &[cancel_token_distribution_ix(&creator.pubkey(), &distribution, &mint, &token_program, &creator_token)],
+            &[&creator],
+        )
+        .await
+        .expect("取消代币分发失败");
+        assert_eq!(get_token_balance(&mut context, &creator_token).await, 1_000_000 - 4_000);
+        assert_eq!(
+            get_balance(&mut context, &creator.pubkey()).await - creator_balance_before,
+            rent_lamports
+        );
+        assert!(!account_exists(&mut context, &vault).await);
+        assert!(!account_exists(&mut context, &distribution).await);
+    }
+}
@@ -0,0 +1,195 @@
+use anchor_client::solana_sdk::{
+    instruction::InstructionError,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use batch_transfer::{self, DuplicatePolicy, ErrorCode, TransferInfo};
+
+mod utils_test;
+use utils_test::get_bank_account;
+use utils_test::program_test_utils::*;
+
+// 生成按地址升序排列的接收者
+fn sorted_recipients(count: usize) -> Vec<Pubkey> {
+    let mut recipients: Vec<Pubkey> = (0..count).map(|_| Keypair::new().pubkey()).collect();
+    recipients.sort();
+    recipients
+}
+
+fn with_policy(duplicate_policy: DuplicatePolicy) -> BatchOptions {
+    BatchOptions {
+        duplicate_policy,
+        ..BatchOptions::default()
+    }
+}
+
+/// 测试SOL批量转账按策略拒绝重复或未排序的接收者
+#[tokio::test]
+async fn test_batch_transfer_sol_reject_duplicates() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let recipients = sorted_recipients(2);
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), 10 * LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let transfer = |recipient: Pubkey| TransferInfo {
+        recipient,
+        amount: LAMPORTS_PER_SOL,
+    };
+
+    // 重复的接收者
+    let transfers = vec![transfer(recipients[0]), transfer(recipients[1]), transfer(recipients[1])];
+    let instruction = batch_transfer_sol_with_options_ix(&sender.pubkey(), transfers, with_policy(DuplicatePolicy::Reject));
+    let (result, logs) = send_with_logs(&mut context, &[instruction], &[&sender]).await;
+    assert_eq!(
+        result,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(u32::from(ErrorCode::DuplicateRecipient))
+        ))
+    );
+    assert!(logs.iter().any(|log| log.contains("转账序号: 2")), "{:?}", logs);
+
+    // 未按地址升序排列
+    let transfers = vec![transfer(recipients[1]), transfer(recipients[0])];
+    let instruction = batch_transfer_sol_with_options_ix(&sender.pubkey(), transfers, with_policy(DuplicatePolicy::Reject));
+    let result = send(&mut context, &[instruction], &[&sender]).await;
+    assert_program_error(result, ErrorCode::TransfersNotSorted);
+    assert_eq!(get_balance(&mut context, &recipients[0]).await, 0);
+
+    // 升序且无重复时正常转账
+    let transfers = vec![transfer(recipients[0]), transfer(recipients[1])];
+    let instruction = batch_transfer_sol_with_options_ix(&sender.pubkey(), transfers, with_policy(DuplicatePolicy::Reject));
+    send(&mut context, &[instruction], &[&sender])
+        .await
+        .expect("无重复接收者的批量转账失败");
+    assert_eq!(get_balance(&mut context, &recipients[0]).await, LAMPORTS_PER_SOL);
+    assert_eq!(get_balance(&mut context, &recipients[1]).await, LAMPORTS_PER_SOL);
+}
+
+/// 测试SOL批量转账合并重复的接收者，手续费按合并后的总额计算
+#[tokio::test]
+async fn test_batch_transfer_sol_merge_duplicates() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let recipients = sorted_recipients(2);
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), 10 * LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+
+    let transfers = vec![
+        TransferInfo {
+            recipient: recipients[0],
+            amount: LAMPORTS_PER_SOL,
+        },
+        TransferInfo {
+            recipient: recipients[0],
+            amount: 2 * LAMPORTS_PER_SOL,
+        },
+        TransferInfo {
+            recipient: recipients[1],
+            amount: LAMPORTS_PER_SOL,
+        },
+    ];
+    let instruction = batch_transfer_sol_with_options_ix(&sender.pubkey(), transfers, with_policy(DuplicatePolicy::Merge));
+    // 合并后每个接收者只传一个账户
+    assert_eq!(
+        instruction.accounts.iter().filter(|meta| meta.pubkey == recipients[0]).count(),
+        1
+    );
+
+    let sender_balance_before = get_balance(&mut context, &sender.pubkey()).await;
+    let bank_balance_before = get_balance(&mut context, &bank_account).await;
+    send(&mut context, &[instruction], &[&sender])
+        .await
+        .expect("合并重复接收者的批量转账失败");
+
+    assert_eq!(get_balance(&mut context, &recipients[0]).await, 3 * LAMPORTS_PER_SOL);
+    assert_eq!(get_balance(&mut context, &recipients[1]).await, LAMPORTS_PER_SOL);
+    assert_eq!(
+        sender_balance_before - get_balance(&mut context, &sender.pubkey()).await,
+        4 * LAMPORTS_PER_SOL + 10_000
+    );
+    assert_eq!(get_balance(&mut context, &bank_account).await - bank_balance_before, 10_000);
+}
+
+/// 测试代币批量转账的重复接收者策略
+#[tokio::test]
+async fn test_batch_transfer_token_duplicate_policy() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let admin = Keypair::new();
+        let sender = Keypair::new();
+        let mint_authority = Keypair::new();
+
+        let mut program_test = program_test();
+        add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+        add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+        let mut context = program_test.start_with_context().await;
+        setup_bank_account(&mut context, &admin, 10_000).await;
+
+        let mint = create_mint(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+        let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+        mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+        let mut recipients = [
+            create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await,
+            create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await,
+        ];
+        recipients.sort();
+
+        let transfers = vec![
+            TransferInfo {
+                recipient: recipients[0],
+                amount: 1_000,
+            },
+            TransferInfo {
+                recipient: recipients[1],
+                amount: 2_000,
+            },
+            TransferInfo {
+                recipient: recipients[1],
+                amount: 3_000,
+            },
+        ];
+
+        // 拒绝重复的接收者
+        let instruction = batch_transfer_token_with_options_ix(
+            &sender.pubkey(),
+            &sender_token,
+            &mint,
+            &token_program,
+            transfers.clone(),
+            with_policy(DuplicatePolicy::Reject),
+        );
+        let result = send(&mut context, &[instruction], &[&sender]).await;
+        assert_program_error(result, ErrorCode::DuplicateRecipient);
+
+        // 合并重复的接收者
+        let instruction = batch_transfer_token_with_options_ix(
+            &sender.pubkey(),
+            &sender_token,
+            &mint,
+            &token_program,
+            transfers,
+            with_policy(DuplicatePolicy::Merge),
+        );
+        send(&mut context, &[instruction], &[&sender])
+            .await
+            .expect("合并重复接收者的代币批量转账失败");
+        assert_eq!(get_token_balance(&mut context, &recipients[0]).await, 1_000);
+        assert_eq!(get_token_balance(&mut context, &recipients[1]).await, 5_000);
+        assert_eq!(get_token_balance(&mut context, &sender_token).await, 1_000_000 - 6_000);
+    }
+}
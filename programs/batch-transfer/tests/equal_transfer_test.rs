@@ -0,0 +1,127 @@
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use batch_transfer::{self, ErrorCode};
+
+mod utils_test;
+use utils_test::get_bank_account;
+use utils_test::program_test_utils::*;
+
+/// 测试等额批量转账SOL，每个接收者收到相同金额，手续费按总额计算
+#[tokio::test]
+async fn test_batch_transfer_sol_equal() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let recipients: Vec<Pubkey> = (0..5).map(|_| Keypair::new().pubkey()).collect();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), 10 * LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+
+    // 指令数据只有鉴别符、金额、可选的批次ID和手续费上限，与接收者数量无关
+    let instruction = batch_transfer_sol_equal_ix(&sender.pubkey(), &recipients, LAMPORTS_PER_SOL);
+    assert_eq!(instruction.data.len(), 8 + 8 + 1 + 8);
+
+    let sender_balance_before = get_balance(&mut context, &sender.pubkey()).await;
+    let bank_balance_before = get_balance(&mut context, &bank_account).await;
+    send(&mut context, &[instruction], &[&sender])
+        .await
+        .expect("等额批量转账SOL失败");
+
+    for recipient in &recipients {
+        assert_eq!(get_balance(&mut context, recipient).await, LAMPORTS_PER_SOL);
+    }
+    assert_eq!(
+        sender_balance_before - get_balance(&mut context, &sender.pubkey()).await,
+        5 * LAMPORTS_PER_SOL + 10_000
+    );
+    assert_eq!(get_balance(&mut context, &bank_account).await - bank_balance_before, 10_000);
+}
+
+/// 测试等额批量转账SOL的接收者列表为空或余额不足
+#[tokio::test]
+async fn test_batch_transfer_sol_equal_invalid() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let recipients: Vec<Pubkey> = (0..3).map(|_| Keypair::new().pubkey()).collect();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), 2 * LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let result = send(
+        &mut context,
+        &[batch_transfer_sol_equal_ix(&sender.pubkey(), &[], LAMPORTS_PER_SOL)],
+        &[&sender],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::EmptyTransfers);
+
+    let result = send(
+        &mut context,
+        &[batch_transfer_sol_equal_ix(&sender.pubkey(), &recipients, LAMPORTS_PER_SOL)],
+        &[&sender],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::InsufficientFunds);
+    assert_eq!(get_balance(&mut context, &recipients[0]).await, 0);
+}
+
+/// 测试等额批量转账SPL Token，支持SPL Token和Token-2022程序
+#[tokio::test]
+async fn test_batch_transfer_token_equal() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let admin = Keypair::new();
+        let sender = Keypair::new();
+        let mint_authority = Keypair::new();
+
+        let mut program_test = program_test();
+        add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+        add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+        let mut context = program_test.start_with_context().await;
+        setup_bank_account(&mut context, &admin, 10_000).await;
+
+        let mint = create_mint(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+        let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+        mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+        let mut recipients = Vec::new();
+        for _ in 0..3 {
+            recipients.push(create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await);
+        }
+
+        // 接收者token账户的mint不一致时返回对应的转账序号
+        let other_mint = create_mint(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+        let other_mint_account = create_token_account(&mut context, &other_mint, &Keypair::new().pubkey()).await;
+        let instruction = batch_transfer_token_equal_ix(
+            &sender.pubkey(),
+            &sender_token,
+            &mint,
+            &token_program,
+            &[recipients[0], other_mint_account],
+            1_000,
+        );
+        let result = send(&mut context, &[instruction], &[&sender]).await;
+        assert_program_error(result, ErrorCode::RecipientMintMismatch);
+
+        let sender_balance_before = get_balance(&mut context, &sender.pubkey()).await;
+        let instruction = batch_transfer_token_equal_ix(&sender.pubkey(), &sender_token, &mint, &token_program, &recipients, 1_000);
+        send(&mut context, &[instruction], &[&sender])
+            .await
+            .expect("等额批量转账代币失败");
+
+        for recipient in &recipients {
+            assert_eq!(get_token_balance(&mut context, recipient).await, 1_000);
+        }
+        assert_eq!(get_token_balance(&mut context, &sender_token).await, 1_000_000 - 3_000);
+        assert_eq!(sender_balance_before - get_balance(&mut context, &sender.pubkey()).await, 10_000);
+    }
+}
@@ -0,0 +1,121 @@
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use batch_transfer::{self, ErrorCode, TransferInfo};
+
+mod utils_test;
+use utils_test::get_bank_account;
+use utils_test::program_test_utils::*;
+
+/// 测试由单独的 fee_payer 支付SOL批量转账的手续费，发送者只支付转账金额
+#[tokio::test]
+async fn test_batch_transfer_sol_with_fee_payer() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let sponsor = Keypair::new();
+    let poor_sponsor = Keypair::new();
+    let recipient1 = Keypair::new().pubkey();
+    let recipient2 = Keypair::new().pubkey();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), 3 * LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sponsor.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &poor_sponsor.pubkey(), 5_000);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+
+    let transfers = vec![
+        TransferInfo {
+            recipient: recipient1,
+            amount: LAMPORTS_PER_SOL,
+        },
+        TransferInfo {
+            recipient: recipient2,
+            amount: 2 * LAMPORTS_PER_SOL,
+        },
+    ];
+    let options = BatchOptions {
+        fee_payer: Some(sponsor.pubkey()),
+        ..BatchOptions::default()
+    };
+
+    // 发送者余额恰好等于转账总额，手续费由 fee_payer 支付
+    let sponsor_balance_before = get_balance(&mut context, &sponsor.pubkey()).await;
+    let bank_balance_before = get_balance(&mut context, &bank_account).await;
+    let instruction = batch_transfer_sol_with_options_ix(&sender.pubkey(), transfers, options);
+    send(&mut context, &[instruction], &[&sender, &sponsor])
+        .await
+        .expect("由 fee_payer 支付手续费的批量转账失败");
+
+    assert_eq!(get_balance(&mut context, &recipient1).await, LAMPORTS_PER_SOL);
+    assert_eq!(get_balance(&mut context, &recipient2).await, 2 * LAMPORTS_PER_SOL);
+    assert_eq!(get_balance(&mut context, &sender.pubkey()).await, 0);
+    assert_eq!(sponsor_balance_before - get_balance(&mut context, &sponsor.pubkey()).await, 10_000);
+    assert_eq!(get_balance(&mut context, &bank_account).await - bank_balance_before, 10_000);
+
+    // fee_payer 余额不足以支付手续费时失败
+    let transfers = vec![TransferInfo {
+        recipient: recipient1,
+        amount: 1,
+    }];
+    let options = BatchOptions {
+        fee_payer: Some(poor_sponsor.pubkey()),
+        ..BatchOptions::default()
+    };
+    let instruction = batch_transfer_sol_with_options_ix(&sponsor.pubkey(), transfers, options);
+    let result = send(&mut context, &[instruction], &[&sponsor, &poor_sponsor]).await;
+    assert_program_error(result, ErrorCode::InsufficientFunds);
+}
+
+/// 测试由单独的 fee_payer 支付代币批量转账的SOL手续费
+#[tokio::test]
+async fn test_batch_transfer_token_with_fee_payer() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let admin = Keypair::new();
+        let sender = Keypair::new();
+        let sponsor = Keypair::new();
+        let mint_authority = Keypair::new();
+
+        let mut program_test = program_test();
+        add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+        add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+        add_funded_account(&mut program_test, &sponsor.pubkey(), LAMPORTS_PER_SOL);
+        let mut context = program_test.start_with_context().await;
+        setup_bank_account(&mut context, &admin, 10_000).await;
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+
+        let mint = create_mint(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+        let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+        mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+        let recipient = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+
+        let transfers = vec![TransferInfo {
+            recipient,
+            amount: 1_000,
+        }];
+        let options = BatchOptions {
+            fee_payer: Some(sponsor.pubkey()),
+            ..BatchOptions::default()
+        };
+
+        let sender_balance_before = get_balance(&mut context, &sender.pubkey()).await;
+        let sponsor_balance_before = get_balance(&mut context, &sponsor.pubkey()).await;
+        let bank_balance_before = get_balance(&mut context, &bank_account).await;
+        let instruction =
+            batch_transfer_token_with_options_ix(&sender.pubkey(), &sender_token, &mint, &token_program, transfers, options);
+        send(&mut context, &[instruction], &[&sender, &sponsor])
+            .await
+            .expect("由 fee_payer 支付手续费的代币批量转账失败");
+
+        assert_eq!(get_token_balance(&mut context, &recipient).await, 1_000);
+        assert_eq!(get_token_balance(&mut context, &sender_token).await, 1_000_000 - 1_000);
+        assert_eq!(get_balance(&mut context, &sender.pubkey()).await, sender_balance_before);
+        assert_eq!(sponsor_balance_before - get_balance(&mut context, &sponsor.pubkey()).await, 10_000);
+        assert_eq!(get_balance(&mut context, &bank_account).await - bank_balance_before, 10_000);
+    }
+}
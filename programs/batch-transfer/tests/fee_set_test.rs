@@ -1,32 +1,141 @@
-use anchor_client::solana_sdk::signature::Keypair;
-use batch_transfer;
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+use batch_transfer::{self, ErrorCode, FeeConfig, TransferInfo};
 
 mod utils_test;
-use utils_test::{get_test_program, get_bank_account};
-
-/// 测试设置手续费功能
-#[test]
-fn test_set_fee() {
-    // 获取程序和支付者
-    let (program, payer) = get_test_program();
-    
-    // 创建管理员账户
+use utils_test::get_bank_account;
+use utils_test::program_test_utils::*;
+
+/// 测试设置手续费配置功能
+#[tokio::test]
+async fn test_set_fee_config() {
     let admin = Keypair::new();
-    
-    // 获取银行账户的PDA
-    let (bank_account, _) = get_bank_account(&program.id());
-    
-    // 由于在本地测试网络中空投可能不可靠，我们只测试 utils_test 中的单元测试
-    // 这些测试不依赖于区块链状态
-    
-    // 测试 safe_add 函数
-    let result = batch_transfer::safe_add(10, 20);
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), 30);
-    
-    // 测试溢出情况
-    let result = batch_transfer::safe_add(u64::MAX, 1);
-    assert!(result.is_err());
-    
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let fee_config = FeeConfig {
+        flat_fee: 1_000,
+        bps: 100, // 1%
+        min_fee: 5_000,
+        max_fee: 1_000_000,
+    };
+    set_fee_config(&mut context, &admin, fee_config)
+        .await
+        .expect("设置手续费配置失败");
+
+    assert_eq!(get_bank_account_data(&mut context).await.fee_config, fee_config);
+
     println!("测试完成");
-} 
\ No newline at end of file
+}
+
+/// 测试无效的手续费配置被拒绝
+#[tokio::test]
+async fn test_set_fee_config_invalid() {
+    let admin = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    // 基点超过10000
+    let too_many_bps = FeeConfig {
+        bps: 10_001,
+        ..FeeConfig::default()
+    };
+    let result = set_fee_config(&mut context, &admin, too_many_bps).await;
+    assert_program_error(result, ErrorCode::InvalidFeeConfig);
+
+    // 最低手续费高于最高手续费
+    let inverted = FeeConfig {
+        min_fee: 10_000,
+        max_fee: 5_000,
+        ..FeeConfig::default()
+    };
+    let result = set_fee_config(&mut context, &admin, inverted).await;
+    assert_program_error(result, ErrorCode::InvalidFeeConfig);
+
+    assert_eq!(get_bank_account_data(&mut context).await.fee_config, FeeConfig::default());
+}
+
+/// 测试按转账总额收取基点手续费，并受上下限约束
+#[tokio::test]
+async fn test_bps_fee_applied_to_sol_batch() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let recipient1 = Keypair::new();
+    let recipient2 = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), 10 * LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+
+    let fee_config = FeeConfig {
+        flat_fee: 0,
+        bps: 100, // 1%
+        min_fee: 10_000,
+        max_fee: LAMPORTS_PER_SOL / 100,
+    };
+    set_fee_config(&mut context, &admin, fee_config)
+        .await
+        .expect("设置手续费配置失败");
+
+    // 1% 手续费：0.3 SOL 收取 0.003 SOL
+    let bank_balance_before = get_balance(&mut context, &bank_account).await;
+    let sender_balance_before = get_balance(&mut context, &sender.pubkey()).await;
+    let transfers = vec![
+        TransferInfo {
+            recipient: recipient1.pubkey(),
+            amount: LAMPORTS_PER_SOL / 10,
+        },
+        TransferInfo {
+            recipient: recipient2.pubkey(),
+            amount: LAMPORTS_PER_SOL / 5,
+        },
+    ];
+    send(&mut context, &[batch_transfer_sol_ix(&sender.pubkey(), transfers)], &[&sender])
+        .await
+        .expect("批量转账失败");
+
+    let fee = get_balance(&mut context, &bank_account).await - bank_balance_before;
+    assert_eq!(fee, 3 * LAMPORTS_PER_SOL / 1000, "手续费应为转账总额的1%");
+    assert_eq!(
+        sender_balance_before - get_balance(&mut context, &sender.pubkey()).await,
+        3 * LAMPORTS_PER_SOL / 10 + fee,
+        "发送者应支付转账总额加手续费"
+    );
+
+    // 小额转账按最低手续费收取
+    let bank_balance_before = get_balance(&mut context, &bank_account).await;
+    let transfers = vec![TransferInfo {
+        recipient: recipient1.pubkey(),
+        amount: 1_000,
+    }];
+    send(&mut context, &[batch_transfer_sol_ix(&sender.pubkey(), transfers)], &[&sender])
+        .await
+        .expect("批量转账失败");
+    assert_eq!(get_balance(&mut context, &bank_account).await - bank_balance_before, 10_000);
+
+    // 大额转账按最高手续费收取
+    let bank_balance_before = get_balance(&mut context, &bank_account).await;
+    let transfers = vec![TransferInfo {
+        recipient: recipient2.pubkey(),
+        amount: 5 * LAMPORTS_PER_SOL,
+    }];
+    send(&mut context, &[batch_transfer_sol_ix(&sender.pubkey(), transfers)], &[&sender])
+        .await
+        .expect("批量转账失败");
+    assert_eq!(
+        get_balance(&mut context, &bank_account).await - bank_balance_before,
+        LAMPORTS_PER_SOL / 100
+    );
+}
@@ -3,8 +3,6 @@ use anchor_client::{
         signature::{Keypair, Signer},
     },
 };
-use batch_transfer;
-use anchor_lang::prelude::*;
 
 mod utils_test;
 use utils_test::{get_test_program, get_bank_account};
@@ -20,7 +18,7 @@ fn test_set_fee_unauthorized() {
     let unauthorized_user = Keypair::new();
     
     // 获取银行账户的PDA
-    let (bank_account, _) = get_bank_account(&program.id());
+    let (_bank_account, _) = get_bank_account(&program.id());
     
     // 不需要实际区块链交互，直接测试权限检查逻辑
     println!("测试未授权用户权限检查");
@@ -28,8 +26,10 @@ fn test_set_fee_unauthorized() {
     // 模拟BankAccount结构
     let mut bank_account_data = batch_transfer::BankAccount {
         admin: admin.pubkey(),
-        fee: 0,
+        fee_config: batch_transfer::FeeConfig::default(),
         is_initialized: true,
+        version: batch_transfer::BankAccount::VERSION,
+        ..Default::default()
     };
     
     // 测试验证管理员是否有权限
@@ -45,23 +45,23 @@ fn test_set_fee_unauthorized() {
     
     // 模拟管理员设置费用 - 这应该成功
     if admin.pubkey() == bank_account_data.admin {
-        bank_account_data.fee = new_fee;
+        bank_account_data.fee_config.flat_fee = new_fee;
         println!("管理员成功设置费用为 {}", new_fee);
     }
-    assert_eq!(bank_account_data.fee, new_fee, "费用应该已被设置");
+    assert_eq!(bank_account_data.fee_config.flat_fee, new_fee, "费用应该已被设置");
     
     // 模拟未授权用户设置费用 - 在实际程序中这会失败
     // 在测试中我们模拟实际区块链上的权限检查
     let test_fee = 2000;
     if unauthorized_user.pubkey() == bank_account_data.admin {
-        bank_account_data.fee = test_fee;
+        bank_account_data.fee_config.flat_fee = test_fee;
         println!("费用被设置，但这不应该发生");
     } else {
         println!("未授权用户无法设置费用，测试通过");
     }
     
     // 确认费用没有被未授权用户更改
-    assert_eq!(bank_account_data.fee, new_fee, "费用不应被未授权用户修改");
+    assert_eq!(bank_account_data.fee_config.flat_fee, new_fee, "费用不应被未授权用户修改");
     
     println!("测试完成");
 } 
\ No newline at end of file
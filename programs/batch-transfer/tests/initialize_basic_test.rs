@@ -3,7 +3,6 @@ use anchor_client::{
         signature::{Keypair, Signer},
     },
 };
-use batch_transfer;
 use anchor_lang::prelude::*;
 
 mod utils_test;
@@ -13,13 +12,13 @@ use utils_test::{get_test_program, get_bank_account};
 #[test]
 fn test_initialize() {
     // 获取程序和支付者
-    let (program, payer) = get_test_program();
+    let (program, _payer) = get_test_program();
     
     // 创建管理员账户
     let admin = Keypair::new();
     
     // 获取银行账户的PDA
-    let (bank_account, bump) = get_bank_account(&program.id());
+    let (_bank_account, _bump) = get_bank_account(&program.id());
     
     println!("开始模拟初始化银行账户");
     
@@ -27,8 +26,10 @@ fn test_initialize() {
     // 1. 创建一个新的空银行账户
     let mut bank = batch_transfer::BankAccount {
         admin: Pubkey::default(),
-        fee: 0,
+        fee_config: batch_transfer::FeeConfig::default(),
         is_initialized: false,
+        version: batch_transfer::BankAccount::VERSION,
+        ..Default::default()
     };
     
     // 2. 检查账户未初始化
@@ -36,14 +37,14 @@ fn test_initialize() {
     
     // 3. 模拟初始化操作
     bank.admin = admin.pubkey();
-    bank.fee = 0;
+    bank.fee_config = batch_transfer::FeeConfig::default();
     bank.is_initialized = true;
     
     // 验证管理员已正确设置
     assert_eq!(bank.admin, admin.pubkey(), "管理员设置错误");
     
     // 验证初始手续费为0
-    assert_eq!(bank.fee, 0, "初始手续费应为0");
+    assert_eq!(bank.fee_config, batch_transfer::FeeConfig::default(), "初始手续费应为0");
     
     // 验证账户已标记为初始化
     assert!(bank.is_initialized, "账户应该已初始化");
@@ -1,7 +1,6 @@
 use batch_transfer::{self, TransferInfo};
 use anchor_client::solana_sdk::signer::keypair::Keypair;
 use anchor_client::solana_sdk::signer::Signer;
-use anchor_lang::prelude::*;
 
 mod utils_test;
 
@@ -17,7 +16,7 @@ fn test_large_batch_transfer_sol() {
     let (program, _payer) = utils_test::get_test_program();
     
     // 创建管理员和发送者账户
-    let admin = Keypair::new();
+    let _admin = Keypair::new();
     let _sender = Keypair::new();
     
     // 获取银行账户
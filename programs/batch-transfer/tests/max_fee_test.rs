@@ -0,0 +1,325 @@
+use anchor_client::anchor_lang::InstructionData;
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+use anchor_spl::token::spl_token;
+use batch_transfer::instruction as batch_instructions;
+use batch_transfer::{self, ErrorCode, FeeConfig, OperatorLimit, SplitInfo, TransferInfo};
+
+mod utils_test;
+use utils_test::{get_bank_account, get_vault};
+use utils_test::program_test_utils::*;
+
+/// 测试签名后手续费被调高时，SOL批量转账因超过 max_fee 而失败
+#[tokio::test]
+async fn test_batch_transfer_sol_max_fee() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let recipient = Keypair::new().pubkey();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), 10 * LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+
+    let transfers = vec![TransferInfo {
+        recipient,
+        amount: LAMPORTS_PER_SOL,
+    }];
+    let options = BatchOptions {
+        max_fee: Some(10_000),
+        ..BatchOptions::default()
+    };
+    let instruction = batch_transfer_sol_with_options_ix(&sender.pubkey(), transfers.clone(), options);
+
+    // 管理员在交易执行前调高手续费
+    let fee_config = FeeConfig {
+        flat_fee: 50_000,
+        ..FeeConfig::default()
+    };
+    set_fee_config(&mut context, &admin, fee_config)
+        .await
+        .expect("设置手续费失败");
+
+    let sender_balance_before = get_balance(&mut context, &sender.pubkey()).await;
+    let result = send(&mut context, &[instruction], &[&sender]).await;
+    assert_program_error(result, ErrorCode::FeeExceedsMaximum);
+    assert_eq!(get_balance(&mut context, &sender.pubkey()).await, sender_balance_before);
+    assert_eq!(get_balance(&mut context, &recipient).await, 0);
+
+    // 手续费恰好等于上限时成功
+    let options = BatchOptions {
+        max_fee: Some(50_000),
+        ..BatchOptions::default()
+    };
+    let bank_balance_before = get_balance(&mut context, &bank_account).await;
+    let instruction = batch_transfer_sol_with_options_ix(&sender.pubkey(), transfers, options);
+    send(&mut context, &[instruction], &[&sender])
+        .await
+        .expect("手续费未超过上限的批量转账失败");
+    assert_eq!(get_balance(&mut context, &recipient).await, LAMPORTS_PER_SOL);
+    assert_eq!(get_balance(&mut context, &bank_account).await - bank_balance_before, 50_000);
+}
+
+/// 测试代币批量转账的手续费超过 max_fee 时失败
+#[tokio::test]
+async fn test_batch_transfer_token_max_fee() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let mint = create_mint(&mut context, &spl_token::ID, &mint_authority.pubkey(), 6).await;
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+    let recipient = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+
+    let transfers = vec![TransferInfo {
+        recipient,
+        amount: 1_000,
+    }];
+    let options = BatchOptions {
+        max_fee: Some(9_999),
+        ..BatchOptions::default()
+    };
+    let instruction =
+        batch_transfer_token_with_options_ix(&sender.pubkey(), &sender_token, &mint, &spl_token::ID, transfers.clone(), options);
+    let result = send(&mut context, &[instruction], &[&sender]).await;
+    assert_program_error(result, ErrorCode::FeeExceedsMaximum);
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 1_000_000);
+
+    let options = BatchOptions {
+        max_fee: Some(10_000),
+        ..BatchOptions::default()
+    };
+    let instruction =
+        batch_transfer_token_with_options_ix(&sender.pubkey(), &sender_token, &mint, &spl_token::ID, transfers, options);
+    send(&mut context, &[instruction], &[&sender])
+        .await
+        .expect("手续费未超过上限的代币批量转账失败");
+    assert_eq!(get_token_balance(&mut context, &recipient).await, 1_000);
+}
+
+/// 测试等额批量转账SOL的手续费超过 max_fee 时失败
+#[tokio::test]
+async fn test_batch_transfer_sol_equal_max_fee() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let recipients = vec![Keypair::new().pubkey(), Keypair::new().pubkey()];
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), 10 * LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let equal_ix = |max_fee: u64| {
+        let mut instruction = batch_transfer_sol_equal_ix(&sender.pubkey(), &recipients, LAMPORTS_PER_SOL);
+        instruction.data = batch_instructions::BatchTransferSolEqual {
+            amount: LAMPORTS_PER_SOL,
+            batch_id: None,
+            max_fee,
+        }
+        .data();
+        instruction
+    };
+
+    let result = send(&mut context, &[equal_ix(9_999)], &[&sender]).await;
+    assert_program_error(result, ErrorCode::FeeExceedsMaximum);
+    assert_eq!(get_balance(&mut context, &recipients[0]).await, 0);
+
+    send(&mut context, &[equal_ix(10_000)], &[&sender])
+        .await
+        .expect("手续费未超过上限的等额批量转账失败");
+    assert_eq!(get_balance(&mut context, &recipients[1]).await, LAMPORTS_PER_SOL);
+}
+
+/// 测试按权重拆分批量转账代币的手续费超过 max_fee 时失败
+#[tokio::test]
+async fn test_batch_split_token_max_fee() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let mint = create_mint(&mut context, &spl_token::ID, &mint_authority.pubkey(), 6).await;
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+    let recipient = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+
+    let splits = vec![SplitInfo { recipient, weight: 1 }];
+    let split_ix = |max_fee: u64| {
+        let mut instruction =
+            batch_split_token_ix(&sender.pubkey(), &sender_token, &mint, &spl_token::ID, 1_000, splits.clone());
+        instruction.data = batch_instructions::BatchSplitToken {
+            total: 1_000,
+            splits: splits.clone(),
+            batch_id: None,
+            max_fee,
+        }
+        .data();
+        instruction
+    };
+
+    let result = send(&mut context, &[split_ix(9_999)], &[&sender]).await;
+    assert_program_error(result, ErrorCode::FeeExceedsMaximum);
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 1_000_000);
+
+    send(&mut context, &[split_ix(10_000)], &[&sender])
+        .await
+        .expect("手续费未超过上限的拆分批量转账失败");
+    assert_eq!(get_token_balance(&mut context, &recipient).await, 1_000);
+}
+
+/// 测试按钱包地址批量转账代币的手续费超过 max_fee 时失败
+#[tokio::test]
+async fn test_batch_transfer_token_to_wallets_max_fee() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+    let wallet = Keypair::new().pubkey();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let mint = create_mint(&mut context, &spl_token::ID, &mint_authority.pubkey(), 6).await;
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+    let transfers = vec![TransferInfo {
+        recipient: wallet,
+        amount: 1_000,
+    }];
+    let wallets_ix = |max_fee: u64| {
+        let mut instruction = batch_transfer_token_to_wallets_ix(
+            &sender.pubkey(),
+            &sender_token,
+            &mint,
+            &spl_token::ID,
+            false,
+            transfers.clone(),
+        );
+        instruction.data = batch_instructions::BatchTransferTokenToWallets {
+            transfers: transfers.clone(),
+            max_fee,
+        }
+        .data();
+        instruction
+    };
+
+    let result = send(&mut context, &[wallets_ix(9_999)], &[&sender]).await;
+    assert_program_error(result, ErrorCode::FeeExceedsMaximum);
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 1_000_000);
+
+    send(&mut context, &[wallets_ix(10_000)], &[&sender])
+        .await
+        .expect("手续费未超过上限的按钱包批量转账失败");
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 999_000);
+}
+
+/// 测试创建SOL分发的手续费超过 max_fee 时失败
+#[tokio::test]
+async fn test_create_distribution_max_fee() {
+    let admin = Keypair::new();
+    let creator = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &creator.pubkey(), 10 * LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let create_ix = |max_fee: u64| {
+        let mut instruction = create_distribution_ix(&creator.pubkey(), 1, 2, LAMPORTS_PER_SOL);
+        instruction.data = batch_instructions::CreateDistribution {
+            distribution_id: 1,
+            total_entries: 2,
+            total_amount: LAMPORTS_PER_SOL,
+            max_fee,
+        }
+        .data();
+        instruction
+    };
+
+    let creator_balance_before = get_balance(&mut context, &creator.pubkey()).await;
+    let result = send(&mut context, &[create_ix(9_999)], &[&creator]).await;
+    assert_program_error(result, ErrorCode::FeeExceedsMaximum);
+    assert_eq!(get_balance(&mut context, &creator.pubkey()).await, creator_balance_before);
+
+    send(&mut context, &[create_ix(10_000)], &[&creator])
+        .await
+        .expect("手续费未超过上限的创建分发失败");
+}
+
+/// 测试操作员从资金库批量转账SOL的手续费超过 max_fee 时失败
+#[tokio::test]
+async fn test_vault_batch_transfer_sol_max_fee() {
+    let admin = Keypair::new();
+    let owner = Keypair::new();
+    let operator = Keypair::new();
+    let recipient = Keypair::new().pubkey();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &owner.pubkey(), 10 * LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &operator.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let vault = get_vault(&batch_transfer::ID, &owner.pubkey(), 1);
+    let limits = vec![OperatorLimit {
+        mint: None,
+        max_per_batch: LAMPORTS_PER_SOL,
+        daily_cap: LAMPORTS_PER_SOL,
+    }];
+    send(
+        &mut context,
+        &[
+            create_vault_ix(&owner.pubkey(), 1),
+            deposit_vault_sol_ix(&owner.pubkey(), &vault, 5 * LAMPORTS_PER_SOL),
+            add_vault_operator_ix(&owner.pubkey(), &vault, &operator.pubkey(), limits),
+        ],
+        &[&owner],
+    )
+    .await
+    .expect("创建资金库并登记操作员失败");
+
+    let transfers = vec![TransferInfo {
+        recipient,
+        amount: LAMPORTS_PER_SOL / 2,
+    }];
+    let vault_ix = |max_fee: u64| {
+        let mut instruction = vault_batch_transfer_sol_ix(&operator.pubkey(), &vault, transfers.clone());
+        instruction.data = batch_instructions::VaultBatchTransferSol {
+            transfers: transfers.clone(),
+            max_fee,
+        }
+        .data();
+        instruction
+    };
+
+    let result = send(&mut context, &[vault_ix(9_999)], &[&operator]).await;
+    assert_program_error(result, ErrorCode::FeeExceedsMaximum);
+    assert_eq!(get_balance(&mut context, &recipient).await, 0);
+
+    send(&mut context, &[vault_ix(10_000)], &[&operator])
+        .await
+        .expect("手续费未超过上限的资金库批量转账失败");
+    assert_eq!(get_balance(&mut context, &recipient).await, LAMPORTS_PER_SOL / 2);
+}
@@ -0,0 +1,211 @@
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use anchor_spl::token::spl_token;
+use batch_transfer::merkle::{from_hex, to_hex, MerkleTree, ProofManifest};
+use batch_transfer::{self, merkle_leaf, verify_merkle_proof, TransferInfo};
+use std::str::FromStr;
+
+mod utils_test;
+use utils_test::get_distributor;
+use utils_test::program_test_utils::*;
+
+// 黄金向量使用的固定转账列表
+fn golden_transfers() -> Vec<TransferInfo> {
+    vec![
+        TransferInfo {
+            recipient: Pubkey::new_from_array([1u8; 32]),
+            amount: 1_000,
+        },
+        TransferInfo {
+            recipient: Pubkey::new_from_array([2u8; 32]),
+            amount: 2_000,
+        },
+        TransferInfo {
+            recipient: Pubkey::new_from_array([3u8; 32]),
+            amount: 3_000,
+        },
+    ]
+}
+
+// keccak256(0x00 || index(u64 LE) || recipient || amount(u64 LE))
+const GOLDEN_LEAVES: [&str; 3] = [
+    "78442e6ce86a2bd81ed3515e7c6a6a820eafb65d69c9dd079108b4026d6a9834",
+    "95adcf3e5cbb83c2bdbb1b7b23595ffbb76f988e4cd9c5db7188702d123e6f35",
+    "71e45325f38e3fa351f9cc5ef76a5d93cbcd19adc4100cd81f272da97908a845",
+];
+const GOLDEN_ROOT: &str = "47e265e461353446ad6975534bedf8ebaa932a4243ff9ed6de5bf83ea0976a09";
+
+/// 测试叶子哈希与黄金向量一致
+#[test]
+fn test_leaf_golden_vectors() {
+    for (index, transfer_info) in golden_transfers().iter().enumerate() {
+        assert_eq!(to_hex(&merkle_leaf(index as u64, transfer_info)), GOLDEN_LEAVES[index]);
+    }
+}
+
+/// 测试树根和证明与黄金向量一致
+#[test]
+fn test_tree_golden_vectors() {
+    let tree = MerkleTree::new(&golden_transfers()).unwrap();
+    assert_eq!(tree.len(), 3);
+    assert_eq!(to_hex(&tree.root()), GOLDEN_ROOT);
+
+    // 三个叶子时第三个叶子没有兄弟节点，直接进入上一层
+    let leaf = |i: usize| from_hex(GOLDEN_LEAVES[i]).unwrap();
+    let node01 = batch_transfer::merkle_node(&leaf(0), &leaf(1));
+    assert_eq!(tree.proof(0).unwrap(), vec![leaf(1), leaf(2)]);
+    assert_eq!(tree.proof(1).unwrap(), vec![leaf(0), leaf(2)]);
+    assert_eq!(tree.proof(2).unwrap(), vec![node01]);
+    assert_eq!(tree.proof(3), None);
+
+    // 单个叶子时树根即叶子，证明为空
+    let single = MerkleTree::new(&golden_transfers()[..1]).unwrap();
+    assert_eq!(to_hex(&single.root()), GOLDEN_LEAVES[0]);
+    assert!(single.proof(0).unwrap().is_empty());
+
+    assert!(MerkleTree::new(&[]).is_err());
+}
+
+/// 测试任意数量叶子生成的证明都能通过链上校验，篡改后校验失败
+#[test]
+fn test_proofs_verify_with_on_chain_verifier() {
+    for count in 1..=33u8 {
+        let transfers: Vec<TransferInfo> = (0..count)
+            .map(|i| TransferInfo {
+                recipient: Pubkey::new_from_array([i; 32]),
+                amount: u64::from(i) * 7 + 1,
+            })
+            .collect();
+        let tree = MerkleTree::new(&transfers).unwrap();
+        let root = tree.root();
+
+        for (index, transfer_info) in transfers.iter().enumerate() {
+            let proof = tree.proof(index).unwrap();
+            let leaf = merkle_leaf(index as u64, transfer_info);
+            assert!(verify_merkle_proof(&proof, &root, leaf), "{}个叶子时第{}个证明校验失败", count, index);
+
+            let tampered = TransferInfo {
+                recipient: transfer_info.recipient,
+                amount: transfer_info.amount + 1,
+            };
+            assert!(!verify_merkle_proof(&proof, &root, merkle_leaf(index as u64, &tampered)));
+            if count > 1 {
+                assert!(!verify_merkle_proof(&proof, &root, merkle_leaf(index as u64 + 1, transfer_info)));
+            }
+        }
+    }
+}
+
+/// 测试JSON和CSV格式的证明清单
+#[test]
+fn test_proof_manifest_golden_vectors() {
+    let manifest = ProofManifest::new(&golden_transfers()).unwrap();
+    assert_eq!(manifest.merkle_root, GOLDEN_ROOT);
+    assert_eq!(manifest.total, 6_000);
+    assert_eq!(manifest.claims.len(), 3);
+    assert_eq!(manifest.claims[2].recipient, Pubkey::new_from_array([3u8; 32]).to_string());
+
+    let json = manifest.to_json().unwrap();
+    let parsed: ProofManifest = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, manifest);
+
+    let csv = manifest.to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "index,recipient,amount,proof");
+    assert_eq!(
+        lines[1],
+        format!(
+            "0,{},1000,{};{}",
+            Pubkey::new_from_array([1u8; 32]),
+            GOLDEN_LEAVES[1],
+            GOLDEN_LEAVES[2]
+        )
+    );
+    assert_eq!(lines.len(), 4);
+
+    assert_eq!(from_hex(GOLDEN_ROOT).map(|root| to_hex(&root)), Some(GOLDEN_ROOT.to_string()));
+    assert_eq!(from_hex("zz"), None);
+}
+
+/// 测试链下证明清单中的证明可以在链上领取
+#[tokio::test]
+async fn test_manifest_proofs_claim_on_chain() {
+    let admin = Keypair::new();
+    let creator = Keypair::new();
+    let mint_authority = Keypair::new();
+    let claimants: Vec<Keypair> = (0..5).map(|_| Keypair::new()).collect();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &creator.pubkey(), LAMPORTS_PER_SOL);
+    for claimant in &claimants {
+        add_funded_account(&mut program_test, &claimant.pubkey(), LAMPORTS_PER_SOL);
+    }
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let mint = create_mint(&mut context, &spl_token::ID, &mint_authority.pubkey(), 6).await;
+    let creator_token = create_token_account(&mut context, &mint, &creator.pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &creator_token, 1_000_000).await;
+
+    let transfers: Vec<TransferInfo> = claimants
+        .iter()
+        .enumerate()
+        .map(|(i, claimant)| TransferInfo {
+            recipient: claimant.pubkey(),
+            amount: (i as u64 + 1) * 10_000,
+        })
+        .collect();
+
+    // 经JSON序列化后再解析，模拟链下生成清单、领取方读取清单的流程
+    let json = ProofManifest::new(&transfers).unwrap().to_json().unwrap();
+    let manifest: ProofManifest = serde_json::from_str(&json).unwrap();
+    let merkle_root = from_hex(&manifest.merkle_root).unwrap();
+
+    let expiry = get_unix_timestamp(&mut context).await + 3_600;
+    send(
+        &mut context,
+        &[create_distributor_ix(
+            &creator.pubkey(),
+            &creator_token,
+            &mint,
+            &spl_token::ID,
+            merkle_root,
+            manifest.total,
+            expiry,
+        )],
+        &[&creator],
+    )
+    .await
+    .expect("创建分发器失败");
+    let distributor = get_distributor(&batch_transfer::ID, &creator.pubkey(), &mint, &merkle_root);
+
+    for claim in &manifest.claims {
+        let claimant = claimants
+            .iter()
+            .find(|claimant| claimant.pubkey() == Pubkey::from_str(&claim.recipient).unwrap())
+            .unwrap();
+        let claimant_token = create_token_account(&mut context, &mint, &claimant.pubkey()).await;
+        let proof = claim.proof.iter().map(|hash| from_hex(hash).unwrap()).collect();
+        send(
+            &mut context,
+            &[claim_ix(
+                &claimant.pubkey(),
+                &distributor,
+                &mint,
+                &spl_token::ID,
+                &claimant_token,
+                claim.index,
+                claim.amount,
+                proof,
+            )],
+            &[claimant],
+        )
+        .await
+        .expect("使用链下证明领取失败");
+        assert_eq!(get_token_balance(&mut context, &claimant_token).await, claim.amount);
+    }
+}
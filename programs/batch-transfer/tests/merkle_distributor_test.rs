@@ -0,0 +1,315 @@
+use anchor_client::anchor_lang::InstructionData;
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use batch_transfer::merkle::MerkleTree;
+use batch_transfer::instruction as batch_instructions;
+use batch_transfer::{self, merkle_leaf, ClaimBitmap, ErrorCode, MerkleDistributor, TransferInfo};
+use solana_program_test::{BanksClientError, ProgramTestContext};
+
+mod utils_test;
+use utils_test::program_test_utils::*;
+use utils_test::{get_bank_account, get_claim_bitmap, get_distributor, get_distributor_vault};
+
+// 空投测试环境
+struct Setup {
+    context: ProgramTestContext,
+    creator: Keypair,
+    mint: Pubkey,
+    token_program: Pubkey,
+    creator_token: Pubkey,
+    claimants: Vec<Keypair>,
+    claimant_tokens: Vec<Pubkey>,
+    entries: Vec<(u64, TransferInfo)>,
+    merkle_root: [u8; 32],
+    proofs: Vec<Vec<[u8; 32]>>,
+    distributor: Pubkey,
+    expiry: i64,
+}
+
+// 创建三个领取者的空投，其中一个领取者的序号位于分块2
+async fn setup(token_program: Pubkey, sol_fee: u64) -> Setup {
+    let admin = Keypair::new();
+    let creator = Keypair::new();
+    let mint_authority = Keypair::new();
+    let claimants: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &creator.pubkey(), LAMPORTS_PER_SOL);
+    for claimant in &claimants {
+        add_funded_account(&mut program_test, &claimant.pubkey(), LAMPORTS_PER_SOL);
+    }
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, sol_fee).await;
+
+    let mint = create_mint(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+    let creator_token = create_token_account(&mut context, &mint, &creator.pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &creator_token, 1_000_000).await;
+    let mut claimant_tokens = Vec::new();
+    for claimant in &claimants {
+        claimant_tokens.push(create_token_account(&mut context, &mint, &claimant.pubkey()).await);
+    }
+
+    let entries: Vec<(u64, TransferInfo)> = [(0, 100_000), (1, 200_000), (5_000, 300_000)]
+        .iter()
+        .zip(&claimants)
+        .map(|(&(index, amount), claimant)| {
+            (
+                index,
+                TransferInfo {
+                    recipient: claimant.pubkey(),
+                    amount,
+                },
+            )
+        })
+        .collect();
+    let leaves: Vec<[u8; 32]> = entries.iter().map(|(index, info)| merkle_leaf(*index, info)).collect();
+    let tree = MerkleTree::from_leaves(leaves).unwrap();
+    let merkle_root = tree.root();
+    let proofs = (0..entries.len()).map(|i| tree.proof(i).unwrap()).collect();
+
+    let expiry = get_unix_timestamp(&mut context).await + 3_600;
+    send(
+        &mut context,
+        &[create_distributor_ix(
+            &creator.pubkey(),
+            &creator_token,
+            &mint,
+            &token_program,
+            merkle_root,
+            600_000,
+            expiry,
+        )],
+        &[&creator],
+    )
+    .await
+    .expect("创建分发器失败");
+    let distributor = get_distributor(&batch_transfer::ID, &creator.pubkey(), &mint, &merkle_root);
+
+    Setup {
+        context,
+        creator,
+        mint,
+        token_program,
+        creator_token,
+        claimants,
+        claimant_tokens,
+        entries,
+        merkle_root,
+        proofs,
+        distributor,
+        expiry,
+    }
+}
+
+impl Setup {
+    async fn claim(&mut self, claimant: usize, entry: usize, amount: u64) -> Result<(), BanksClientError> {
+        let instruction = claim_ix(
+            &self.claimants[claimant].pubkey(),
+            &self.distributor,
+            &self.mint,
+            &self.token_program,
+            &self.claimant_tokens[claimant],
+            self.entries[entry].0,
+            amount,
+            self.proofs[entry].clone(),
+        );
+        send(&mut self.context, &[instruction], &[&self.claimants[claimant]]).await
+    }
+}
+
+/// 测试创建分发器并由每个领取者凭证明领取
+#[tokio::test]
+async fn test_create_distributor_and_claim() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        let mut setup = setup(token_program, 10_000).await;
+
+        let distributor: MerkleDistributor = get_account_data(&mut setup.context, &setup.distributor).await;
+        assert_eq!(distributor.creator, setup.creator.pubkey());
+        assert_eq!(distributor.mint, setup.mint);
+        assert_eq!(distributor.merkle_root, setup.merkle_root);
+        assert_eq!(distributor.total, 600_000);
+        assert_eq!(distributor.claimed, 0);
+        assert_eq!(distributor.expiry, setup.expiry);
+
+        let vault = get_distributor_vault(&batch_transfer::ID, &setup.distributor);
+        assert_eq!(get_token_balance(&mut setup.context, &vault).await, 600_000);
+        assert_eq!(get_token_balance(&mut setup.context, &setup.creator_token).await, 400_000);
+        // 创建分发器时按银行账户的手续费配置收取一次SOL手续费
+        let rent_exempt_minimum = get_rent_exempt_minimum(&mut setup.context, &bank_account).await;
+        assert_eq!(get_balance(&mut setup.context, &bank_account).await, rent_exempt_minimum + 10_000);
+
+        for i in 0..3 {
+            let amount = setup.entries[i].1.amount;
+            setup.claim(i, i, amount).await.expect("领取失败");
+            assert_eq!(get_token_balance(&mut setup.context, &setup.claimant_tokens[i]).await, amount);
+        }
+
+        let distributor: MerkleDistributor = get_account_data(&mut setup.context, &setup.distributor).await;
+        assert_eq!(distributor.claimed, 600_000);
+        assert_eq!(get_token_balance(&mut setup.context, &vault).await, 0);
+
+        // 每个位图分块记录2048个序号，序号5000位于分块2
+        let bitmap: ClaimBitmap = get_account_data(
+            &mut setup.context,
+            &get_claim_bitmap(&batch_transfer::ID, &setup.distributor, 5_000),
+        )
+        .await;
+        assert_eq!(bitmap.distributor, setup.distributor);
+        assert_eq!(bitmap.chunk, 2);
+        let (byte, mask) = batch_transfer::claim_bitmap_position(5_000);
+        assert_eq!(bitmap.bits[byte], mask);
+    }
+}
+
+/// 测试重复领取、错误金额和冒用他人证明
+#[tokio::test]
+async fn test_claim_rejected() {
+    let mut setup = setup(spl_token::ID, 0).await;
+
+    setup.claim(0, 0, 100_000).await.expect("领取失败");
+    let result = setup.claim(0, 0, 100_000).await;
+    assert_program_error(result, ErrorCode::AlreadyClaimed);
+
+    // 金额与叶子不一致
+    let result = setup.claim(1, 1, 200_001).await;
+    assert_program_error(result, ErrorCode::InvalidMerkleProof);
+
+    // 使用他人的序号和证明
+    let result = setup.claim(2, 1, 200_000).await;
+    assert_program_error(result, ErrorCode::InvalidMerkleProof);
+
+    assert_eq!(get_token_balance(&mut setup.context, &setup.claimant_tokens[1]).await, 0);
+    assert_eq!(get_token_balance(&mut setup.context, &setup.claimant_tokens[2]).await, 0);
+}
+
+/// 测试到期后不能领取，创建者可以收回剩余代币
+#[tokio::test]
+async fn test_clawback_after_expiry() {
+    let mut setup = setup(spl_token::ID, 0).await;
+    setup.claim(0, 0, 100_000).await.expect("领取失败");
+
+    let clawback = |creator: &Pubkey, setup: &Setup| {
+        clawback_ix(
+            creator,
+            &setup.distributor,
+            &setup.mint,
+            &setup.token_program,
+            &setup.creator_token,
+        )
+    };
+
+    // 未到期时不能收回
+    let instruction = clawback(&setup.creator.pubkey(), &setup);
+    let creator = setup.creator.insecure_clone();
+    let result = send(&mut setup.context, &[instruction], &[&creator]).await;
+    assert_program_error(result, ErrorCode::DistributorNotExpired);
+
+    set_unix_timestamp(&mut setup.context, setup.expiry).await;
+
+    // 到期后不能再领取
+    let result = setup.claim(1, 1, 200_000).await;
+    assert_program_error(result, ErrorCode::DistributorExpired);
+
+    // 非创建者不能收回
+    let attacker = setup.claimants[1].insecure_clone();
+    let instruction = clawback(&attacker.pubkey(), &setup);
+    let result = send(&mut setup.context, &[instruction], &[&attacker]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    let instruction = clawback(&setup.creator.pubkey(), &setup);
+    send(&mut setup.context, &[instruction], &[&creator]).await.expect("收回失败");
+    assert_eq!(get_token_balance(&mut setup.context, &setup.creator_token).await, 400_000 + 500_000);
+
+    let distributor: MerkleDistributor = get_account_data(&mut setup.context, &setup.distributor).await;
+    assert!(distributor.clawed_back);
+    let vault = get_distributor_vault(&batch_transfer::ID, &setup.distributor);
+    assert_eq!(get_token_balance(&mut setup.context, &vault).await, 0);
+}
+
+/// 测试序号所在位图分块的计算，分块编号超出 u32 范围时报错而不是截断
+#[test]
+fn test_claim_bitmap_chunk() {
+    assert_eq!(batch_transfer::claim_bitmap_chunk(0).unwrap(), 0);
+    assert_eq!(batch_transfer::claim_bitmap_chunk(2_047).unwrap(), 0);
+    assert_eq!(batch_transfer::claim_bitmap_chunk(2_048).unwrap(), 1);
+
+    let last_index = (u64::from(u32::MAX) + 1) * 2_048 - 1;
+    assert_eq!(batch_transfer::claim_bitmap_chunk(last_index).unwrap(), u32::MAX);
+    assert!(batch_transfer::claim_bitmap_chunk(last_index + 1).is_err());
+    assert!(batch_transfer::claim_bitmap_chunk(u64::MAX).is_err());
+}
+
+/// 测试分块编号超出 u32 范围的序号被拒绝，不会与分块0共用同一个位图账户
+#[tokio::test]
+async fn test_claim_index_out_of_range() {
+    let mut setup = setup(spl_token::ID, 0).await;
+
+    // 该序号的分块编号为 2^32，截断为 u32 后与序号0同属分块0
+    let index = (u64::from(u32::MAX) + 1) * 2_048;
+    let mut instruction = claim_ix(
+        &setup.claimants[0].pubkey(),
+        &setup.distributor,
+        &setup.mint,
+        &setup.token_program,
+        &setup.claimant_tokens[0],
+        0,
+        100_000,
+        setup.proofs[0].clone(),
+    );
+    instruction.data = batch_instructions::Claim {
+        index,
+        amount: 100_000,
+        proof: setup.proofs[0].clone(),
+    }
+    .data();
+    let result = send(&mut setup.context, &[instruction], &[&setup.claimants[0]]).await;
+    assert_program_error(result, ErrorCode::InvalidClaimIndex);
+    assert_eq!(get_token_balance(&mut setup.context, &setup.claimant_tokens[0]).await, 0);
+
+    // 序号0的领取状态不受影响
+    setup.claim(0, 0, 100_000).await.expect("领取失败");
+    assert_eq!(get_token_balance(&mut setup.context, &setup.claimant_tokens[0]).await, 100_000);
+}
+
+/// 测试实际收取转账手续费的Token-2022代币不能创建分发器，金库收不到完整的空投总额
+#[tokio::test]
+async fn test_create_distributor_transfer_fee_mint_rejected() {
+    let admin = Keypair::new();
+    let creator = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &creator.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let mint = create_mint_with_transfer_fee(&mut context, &mint_authority.pubkey(), 6, 100, 1_000).await;
+    let creator_token = create_token_account(&mut context, &mint, &creator.pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &creator_token, 1_000_000).await;
+
+    let expiry = get_unix_timestamp(&mut context).await + 3_600;
+    let result = send(
+        &mut context,
+        &[create_distributor_ix(
+            &creator.pubkey(),
+            &creator_token,
+            &mint,
+            &spl_token_2022::ID,
+            [7; 32],
+            600_000,
+            expiry,
+        )],
+        &[&creator],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::InvalidDistributorConfig);
+    assert_eq!(get_token_balance(&mut context, &creator_token).await, 1_000_000);
+}
@@ -0,0 +1,195 @@
+use anchor_client::anchor_lang::{AnchorSerialize, Discriminator};
+use anchor_client::solana_sdk::{
+    account::Account,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+};
+use batch_transfer::{BankAccount, ErrorCode, FeeConfig, TransferInfo};
+use solana_program_test::ProgramTest;
+
+mod utils_test;
+use utils_test::get_bank_account;
+use utils_test::program_test_utils::*;
+
+// 写入一个由本程序所有、数据为 data 的银行账户
+fn add_bank_account(program_test: &mut ProgramTest, data: Vec<u8>) {
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+    program_test.add_account(
+        bank_account,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: batch_transfer::ID,
+            ..Account::default()
+        },
+    );
+}
+
+// 最初布局的银行账户数据：鉴别符 + admin + fee(u64) + is_initialized
+fn legacy_bank_account_data(admin: &Pubkey, fee: u64) -> Vec<u8> {
+    let mut data = BankAccount::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(admin.as_ref());
+    data.extend_from_slice(&fee.to_le_bytes());
+    data.push(1);
+    assert_eq!(data.len(), 8 + 32 + 8 + 1);
+    data
+}
+
+/// 测试最初布局的银行账户迁移后固定手续费保留，账户扩容到当前布局并可继续使用
+#[tokio::test]
+async fn test_migrate_legacy_bank_account() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let recipient = Keypair::new().pubkey();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), 10 * LAMPORTS_PER_SOL);
+    add_bank_account(&mut program_test, legacy_bank_account_data(&admin.pubkey(), 5_000));
+    let mut context = program_test.start_with_context().await;
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+
+    let transfers = vec![TransferInfo {
+        recipient,
+        amount: LAMPORTS_PER_SOL,
+    }];
+
+    // 迁移之前旧布局无法按当前结构反序列化
+    let result = send(&mut context, &[batch_transfer_sol_ix(&sender.pubkey(), transfers.clone())], &[&sender]).await;
+    assert!(result.is_err(), "旧布局的银行账户不应被接受");
+
+    // 非管理员不能迁移
+    let result = send(&mut context, &[migrate_bank_account_ix(&sender.pubkey())], &[&sender]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    let admin_balance_before = get_balance(&mut context, &admin.pubkey()).await;
+    let bank_balance_before = get_balance(&mut context, &bank_account).await;
+    send(&mut context, &[migrate_bank_account_ix(&admin.pubkey())], &[&admin])
+        .await
+        .expect("迁移银行账户失败");
+
+    let bank = get_bank_account_data(&mut context).await;
+    assert_eq!(bank.admin, admin.pubkey());
+    assert_eq!(
+        bank.fee_config,
+        FeeConfig {
+            flat_fee: 5_000,
+            ..FeeConfig::default()
+        }
+    );
+    assert!(bank.is_initialized);
+    assert_eq!(bank.pending_admin, None);
+    assert!(!bank.paused);
+    assert_eq!(bank.version, BankAccount::VERSION);
+
+    // 账户扩容到当前布局，新增租金由管理员支付
+    let account = context.banks_client.get_account(bank_account).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), 8 + BankAccount::LEN);
+    let rent_exempt_minimum = get_rent_exempt_minimum(&mut context, &bank_account).await;
+    assert_eq!(account.lamports, rent_exempt_minimum);
+    assert_eq!(
+        admin_balance_before - get_balance(&mut context, &admin.pubkey()).await,
+        rent_exempt_minimum - bank_balance_before
+    );
+
+    // 已是最新版本时不能重复迁移
+    let result = send(&mut context, &[migrate_bank_account_ix(&admin.pubkey())], &[&admin]).await;
+    assert_program_error(result, ErrorCode::BankAccountUpToDate);
+
+    // 迁移后按原固定手续费收费
+    send(&mut context, &[batch_transfer_sol_ix(&sender.pubkey(), transfers)], &[&sender])
+        .await
+        .expect("迁移后批量转账失败");
+    assert_eq!(get_balance(&mut context, &bank_account).await, rent_exempt_minimum + 5_000);
+    assert_eq!(get_balance(&mut context, &recipient).await, LAMPORTS_PER_SOL);
+}
+
+/// 测试加入版本号之前、末尾缺少字段的银行账户迁移后缺少的字段取默认值
+#[tokio::test]
+async fn test_migrate_unversioned_bank_account() {
+    let admin = Keypair::new();
+    let pending_admin = Keypair::new().pubkey();
+    let fee_config = FeeConfig {
+        flat_fee: 1_000,
+        bps: 25,
+        min_fee: 2_000,
+        max_fee: 50_000,
+    };
+
+    // 鉴别符 + admin + fee_config + is_initialized + pending_admin，不含 paused 和 version
+    let mut data = BankAccount::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(admin.pubkey().as_ref());
+    fee_config.serialize(&mut data).unwrap();
+    data.push(1);
+    Some(pending_admin).serialize(&mut data).unwrap();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_bank_account(&mut program_test, data);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[migrate_bank_account_ix(&admin.pubkey())], &[&admin])
+        .await
+        .expect("迁移银行账户失败");
+
+    let bank = get_bank_account_data(&mut context).await;
+    assert_eq!(bank.admin, admin.pubkey());
+    assert_eq!(bank.fee_config, fee_config);
+    assert_eq!(bank.pending_admin, Some(pending_admin));
+    assert!(!bank.paused);
+    assert_eq!(bank.version, BankAccount::VERSION);
+}
+
+/// 测试版本1的银行账户迁移后扩容，配置变更延迟为0且没有待生效的变更
+#[tokio::test]
+async fn test_migrate_version_1_bank_account() {
+    let admin = Keypair::new();
+    let fee_config = FeeConfig {
+        flat_fee: 1_000,
+        ..FeeConfig::default()
+    };
+
+    // 鉴别符 + admin + fee_config + is_initialized + pending_admin + paused + version，不含时间锁字段
+    let mut data = BankAccount::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(admin.pubkey().as_ref());
+    fee_config.serialize(&mut data).unwrap();
+    data.push(1);
+    None::<Pubkey>.serialize(&mut data).unwrap();
+    data.push(0);
+    data.push(1);
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_bank_account(&mut program_test, data);
+    let mut context = program_test.start_with_context().await;
+
+    send(&mut context, &[migrate_bank_account_ix(&admin.pubkey())], &[&admin])
+        .await
+        .expect("迁移银行账户失败");
+
+    let bank = get_bank_account_data(&mut context).await;
+    assert_eq!(bank.fee_config, fee_config);
+    assert_eq!(bank.config_delay, batch_transfer::DEFAULT_CONFIG_DELAY);
+    assert_eq!(bank.pending_config, None);
+    assert_eq!(bank.version, BankAccount::VERSION);
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+    let account = context.banks_client.get_account(bank_account).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), 8 + BankAccount::LEN);
+}
+
+/// 测试新初始化的银行账户已是最新版本
+#[tokio::test]
+async fn test_initialize_sets_current_version() {
+    let admin = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    assert_eq!(get_bank_account_data(&mut context).await.version, BankAccount::VERSION);
+    let result = send(&mut context, &[migrate_bank_account_ix(&admin.pubkey())], &[&admin]).await;
+    assert_program_error(result, ErrorCode::BankAccountUpToDate);
+}
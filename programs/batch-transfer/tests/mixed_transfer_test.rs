@@ -42,8 +42,13 @@ fn test_mixed_transfers() {
     // 模拟银行账户状态
     let _bank_account_data = batch_transfer::BankAccount {
         admin: _admin.pubkey(),
-        fee: 100, // 设置费用为1% (100 basis points)
+        fee_config: batch_transfer::FeeConfig {
+            bps: 100, // 设置费用为1% (100 basis points)
+            ..Default::default()
+        },
         is_initialized: true,
+        version: batch_transfer::BankAccount::VERSION,
+        ..Default::default()
     };
     
     // 模拟SOL和Token初始余额
@@ -58,13 +63,13 @@ fn test_mixed_transfers() {
     
     // 准备SOL转账数据
     let sol_transfer_amount = LAMPORTS_PER_SOL; // 1 SOL
-    let _sol_transfers = vec![TransferInfo {
+    let _sol_transfers = [TransferInfo {
         recipient: _recipient_sol.pubkey(),
         amount: sol_transfer_amount,
     }];
     
     // 计算SOL转账手续费
-    let sol_fee = _bank_account_data.fee * sol_transfer_amount / 10000; // 1% 费用
+    let sol_fee = batch_transfer::calculate_fee(&_bank_account_data.fee_config, sol_transfer_amount).unwrap(); // 1% 费用
     
     // 模拟SOL转账结果
     let sol_sender_balance_after = sol_balance - sol_transfer_amount - sol_fee;
@@ -83,7 +88,7 @@ fn test_mixed_transfers() {
     // 注意：在实际测试中，我们需要创建一个Token账户并关联给收款人
     
     // 计算Token转账手续费
-    let token_fee = _bank_account_data.fee * token_transfer_amount / 10000; // 1% 费用
+    let token_fee = batch_transfer::calculate_fee(&_bank_account_data.fee_config, token_transfer_amount).unwrap(); // 1% 费用
     
     // 模拟Token转账结果
     let token_sender_balance_after = token_balance - token_transfer_amount - token_fee;
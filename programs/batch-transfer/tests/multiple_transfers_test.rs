@@ -17,21 +17,26 @@ fn test_multiple_transfers() {
     
     // 创建测试账户
     let admin = Keypair::new();
-    let sender = Keypair::new();
+    let _sender = Keypair::new();
     let recipient1 = Keypair::new();
     let recipient2 = Keypair::new();
     let recipient3 = Keypair::new();
     
     // 获取银行账户PDA
-    let (bank_account, _) = get_bank_account(&program.id());
+    let (_bank_account, _) = get_bank_account(&program.id());
     
     println!("开始测试连续执行多次批量转账");
     
     // 模拟银行账户状态
     let bank_account_data = batch_transfer::BankAccount {
         admin: admin.pubkey(),
-        fee: 100, // 设置费用为1% (100 basis points)
+        fee_config: batch_transfer::FeeConfig {
+            bps: 100, // 设置费用为1% (100 basis points)
+            ..Default::default()
+        },
         is_initialized: true,
+        version: batch_transfer::BankAccount::VERSION,
+        ..Default::default()
     };
     
     // 模拟发送者初始SOL余额
@@ -47,23 +52,21 @@ fn test_multiple_transfers() {
     
     // 第一次批量转账
     println!("执行第一次批量转账");
-    let first_transfers = vec![
-        TransferInfo {
+    let first_transfers = [TransferInfo {
             recipient: recipient1.pubkey(),
             amount: LAMPORTS_PER_SOL, // 1 SOL
         },
         TransferInfo {
             recipient: recipient2.pubkey(),
             amount: LAMPORTS_PER_SOL / 2, // 0.5 SOL
-        },
-    ];
+        }];
     
     // 计算第一次转账总金额
     let first_total_amount = first_transfers.iter().fold(0, |acc, t| acc + t.amount);
     println!("第一次转账总金额: {} lamports", first_total_amount);
     
     // 计算第一次手续费
-    let first_fee = bank_account_data.fee * first_total_amount / 10000; // 1% 费用
+    let first_fee = batch_transfer::calculate_fee(&bank_account_data.fee_config, first_total_amount).unwrap(); // 1% 费用
     println!("第一次转账手续费: {} lamports", first_fee);
     
     // 更新余额
@@ -79,23 +82,21 @@ fn test_multiple_transfers() {
     
     // 第二次批量转账
     println!("执行第二次批量转账");
-    let second_transfers = vec![
-        TransferInfo {
+    let second_transfers = [TransferInfo {
             recipient: recipient2.pubkey(),
             amount: LAMPORTS_PER_SOL / 4, // 0.25 SOL
         },
         TransferInfo {
             recipient: recipient3.pubkey(),
             amount: LAMPORTS_PER_SOL * 2, // 2 SOL
-        },
-    ];
+        }];
     
     // 计算第二次转账总金额
     let second_total_amount = second_transfers.iter().fold(0, |acc, t| acc + t.amount);
     println!("第二次转账总金额: {} lamports", second_total_amount);
     
     // 计算第二次手续费
-    let second_fee = bank_account_data.fee * second_total_amount / 10000; // 1% 费用
+    let second_fee = batch_transfer::calculate_fee(&bank_account_data.fee_config, second_total_amount).unwrap(); // 1% 费用
     println!("第二次转账手续费: {} lamports", second_fee);
     
     // 更新余额
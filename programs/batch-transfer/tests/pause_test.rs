@@ -0,0 +1,121 @@
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+use anchor_spl::token::spl_token;
+use batch_transfer::{ErrorCode, FeeConfig, TransferInfo};
+
+mod utils_test;
+use utils_test::program_test_utils::*;
+
+/// 测试暂停期间拒绝批量转账，管理员操作不受影响，恢复后可以继续转账
+#[tokio::test]
+async fn test_pause_blocks_transfers() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let destination = Keypair::new();
+    let mint_authority = Keypair::new();
+    let recipient = Keypair::new().pubkey();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), 10 * LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &destination.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let mint = create_mint(&mut context, &spl_token::ID, &mint_authority.pubkey(), 6).await;
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    let recipient_token = create_token_account(&mut context, &mint, &recipient).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+    let sol_transfers = vec![TransferInfo {
+        recipient,
+        amount: LAMPORTS_PER_SOL,
+    }];
+    let token_transfers = vec![TransferInfo {
+        recipient: recipient_token,
+        amount: 1_000,
+    }];
+
+    // 暂停前先转账一次，累积可提取的手续费
+    send(&mut context, &[batch_transfer_sol_ix(&sender.pubkey(), sol_transfers.clone())], &[&sender])
+        .await
+        .expect("批量转账SOL失败");
+
+    send(&mut context, &[pause_ix(&admin.pubkey())], &[&admin])
+        .await
+        .expect("暂停失败");
+    assert!(get_bank_account_data(&mut context).await.paused);
+
+    // 暂停期间拒绝SOL和代币批量转账
+    let result = send(&mut context, &[batch_transfer_sol_ix(&sender.pubkey(), sol_transfers.clone())], &[&sender]).await;
+    assert_program_error(result, ErrorCode::Paused);
+    let instruction = batch_transfer_token_ix(
+        &sender.pubkey(),
+        &sender_token,
+        &mint,
+        &spl_token::ID,
+        token_transfers.clone(),
+    );
+    let result = send(&mut context, &[instruction], &[&sender]).await;
+    assert_program_error(result, ErrorCode::Paused);
+    assert_eq!(get_balance(&mut context, &recipient).await, LAMPORTS_PER_SOL);
+    assert_eq!(get_token_balance(&mut context, &recipient_token).await, 0);
+
+    // 暂停期间管理员仍可提取手续费和修改手续费配置
+    send(
+        &mut context,
+        &[withdraw_fee_ix(&admin.pubkey(), &destination.pubkey(), None)],
+        &[&admin],
+    )
+    .await
+    .expect("暂停期间提取手续费失败");
+    assert_eq!(get_balance(&mut context, &destination.pubkey()).await, LAMPORTS_PER_SOL + 10_000);
+    let fee_config = FeeConfig {
+        flat_fee: 20_000,
+        ..FeeConfig::default()
+    };
+    set_fee_config(&mut context, &admin, fee_config)
+        .await
+        .expect("暂停期间设置手续费失败");
+
+    // 恢复后可以继续转账
+    send(&mut context, &[unpause_ix(&admin.pubkey())], &[&admin])
+        .await
+        .expect("恢复失败");
+    assert!(!get_bank_account_data(&mut context).await.paused);
+    send(&mut context, &[batch_transfer_sol_ix(&sender.pubkey(), sol_transfers)], &[&sender])
+        .await
+        .expect("恢复后批量转账SOL失败");
+    let instruction = batch_transfer_token_ix(&sender.pubkey(), &sender_token, &mint, &spl_token::ID, token_transfers);
+    send(&mut context, &[instruction], &[&sender])
+        .await
+        .expect("恢复后批量转账代币失败");
+    assert_eq!(get_balance(&mut context, &recipient).await, 2 * LAMPORTS_PER_SOL);
+    assert_eq!(get_token_balance(&mut context, &recipient_token).await, 1_000);
+}
+
+/// 测试非管理员不能暂停或恢复合约
+#[tokio::test]
+async fn test_pause_unauthorized() {
+    let admin = Keypair::new();
+    let attacker = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &attacker.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let result = send(&mut context, &[pause_ix(&attacker.pubkey())], &[&attacker]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+    assert!(!get_bank_account_data(&mut context).await.paused);
+
+    send(&mut context, &[pause_ix(&admin.pubkey())], &[&admin])
+        .await
+        .expect("暂停失败");
+    let result = send(&mut context, &[unpause_ix(&attacker.pubkey())], &[&attacker]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+    assert!(get_bank_account_data(&mut context).await.paused);
+}
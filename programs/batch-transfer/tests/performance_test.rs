@@ -44,8 +44,13 @@ fn test_large_batch_performance() {
     // 模拟银行账户状态
     let _bank_account_data = batch_transfer::BankAccount {
         admin: _admin.pubkey(),
-        fee: 100, // 设置费用为1% (100 basis points)
+        fee_config: batch_transfer::FeeConfig {
+            bps: 100, // 设置费用为1% (100 basis points)
+            ..Default::default()
+        },
         is_initialized: true,
+        version: batch_transfer::BankAccount::VERSION,
+        ..Default::default()
     };
     
     // 模拟SOL初始余额（非常大以支持多次转账）
@@ -86,7 +91,7 @@ fn test_large_batch_performance() {
     
     // 计算总转账金额和总手续费
     let total_transfer_amount = transfer_amount * num_recipients as u64;
-    let total_fee = _bank_account_data.fee * total_transfer_amount / 10000; // 1% 费用
+    let total_fee = batch_transfer::calculate_fee(&_bank_account_data.fee_config, total_transfer_amount).unwrap(); // 1% 费用
     
     // 模拟检查发送者余额
     let sender_has_sufficient_balance = sol_balance >= (total_transfer_amount + total_fee);
@@ -36,8 +36,13 @@ fn test_overflow_protection() {
     // 模拟银行账户状态
     let _bank_account_data = batch_transfer::BankAccount {
         admin: _admin.pubkey(),
-        fee: 100, // 设置费用为1% (100 basis points)
+        fee_config: batch_transfer::FeeConfig {
+            bps: 100, // 设置费用为1% (100 basis points)
+            ..Default::default()
+        },
         is_initialized: true,
+        version: batch_transfer::BankAccount::VERSION,
+        ..Default::default()
     };
     
     // 模拟SOL余额
@@ -51,7 +56,7 @@ fn test_overflow_protection() {
     let max_amount = u64::MAX;
     
     // 准备转账数据
-    let _transfers = vec![TransferInfo {
+    let _transfers = [TransferInfo {
         recipient: _recipient.pubkey(),
         amount: max_amount,
     }];
@@ -66,8 +71,6 @@ fn test_overflow_protection() {
         Err(err) => {
             // 检查错误类型
             println!("预期的错误: {:?}", err);
-            // 断言错误确实发生了
-            assert!(true, "接收到预期的错误");
         }
     }
     
@@ -79,7 +82,7 @@ fn test_overflow_protection() {
     let special_amount = u64::MAX / 100;
     
     // 准备转账数据
-    let _fee_overflow_transfers = vec![TransferInfo {
+    let _fee_overflow_transfers = [TransferInfo {
         recipient: _recipient.pubkey(),
         amount: special_amount,
     }];
@@ -95,8 +98,6 @@ fn test_overflow_protection() {
         Err(err) => {
             // 检查错误类型
             println!("预期的错误: {:?}", err);
-            // 断言错误确实发生了
-            assert!(true, "接收到预期的错误");
         }
     }
     
@@ -136,8 +137,13 @@ fn test_reentrancy_protection() {
     // 模拟银行账户状态
     let _bank_account_data = batch_transfer::BankAccount {
         admin: _admin.pubkey(),
-        fee: 100, // 设置费用为1% (100 basis points)
+        fee_config: batch_transfer::FeeConfig {
+            bps: 100, // 设置费用为1% (100 basis points)
+            ..Default::default()
+        },
         is_initialized: true,
+        version: batch_transfer::BankAccount::VERSION,
+        ..Default::default()
     };
     
     // 模拟SOL余额
@@ -146,7 +152,7 @@ fn test_reentrancy_protection() {
     
     // 模拟转账数据
     let transfer_amount = LAMPORTS_PER_SOL / 10; // 0.1 SOL
-    let _transfers = vec![TransferInfo {
+    let _transfers = [TransferInfo {
         recipient: _recipient.pubkey(),
         amount: transfer_amount,
     }];
@@ -174,8 +180,6 @@ fn test_reentrancy_protection() {
         Err(err) => {
             // 检查错误类型
             println!("预期的错误: {:?}", err);
-            // 断言错误确实发生了
-            assert!(true, "接收到预期的错误");
         }
     }
     
@@ -0,0 +1,193 @@
+use anchor_client::solana_sdk::{
+    instruction::{AccountMeta, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use batch_transfer::{self, ErrorCode, TransferInfo};
+
+mod utils_test;
+use utils_test::get_bank_account;
+use utils_test::program_test_utils::*;
+
+// 批量转账指令中第一个接收者账户的位置：sender、bank_account、system_program、batch_receipt、fee_payer 之后
+const SOL_RECIPIENTS_OFFSET: usize = 5;
+
+/// 测试SOL批量转账跳过无效接收者，只按成功转账扣款和收取手续费
+#[tokio::test]
+async fn test_batch_transfer_sol_skip_invalid() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let recipient1 = Keypair::new().pubkey();
+    let recipient2 = Keypair::new().pubkey();
+    let recipient3 = Keypair::new().pubkey();
+    let recipient4 = Keypair::new().pubkey();
+    let recipient5 = Keypair::new().pubkey();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), 10 * LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+
+    let transfers = vec![
+        TransferInfo {
+            recipient: recipient1,
+            amount: LAMPORTS_PER_SOL,
+        },
+        // 传入的账户与接收者不一致
+        TransferInfo {
+            recipient: recipient2,
+            amount: LAMPORTS_PER_SOL,
+        },
+        // 新账户收到的金额低于租金豁免最低余额
+        TransferInfo {
+            recipient: recipient3,
+            amount: 100,
+        },
+        // 接收者账户不可写
+        TransferInfo {
+            recipient: recipient4,
+            amount: LAMPORTS_PER_SOL,
+        },
+        TransferInfo {
+            recipient: recipient5,
+            amount: 2 * LAMPORTS_PER_SOL,
+        },
+    ];
+    let build = |skip_invalid: bool| {
+        let mut instruction = if skip_invalid {
+            batch_transfer_sol_skip_invalid_ix(&sender.pubkey(), transfers.clone())
+        } else {
+            batch_transfer_sol_ix(&sender.pubkey(), transfers.clone())
+        };
+        instruction.accounts[SOL_RECIPIENTS_OFFSET + 1] = AccountMeta::new(Keypair::new().pubkey(), false);
+        instruction.accounts[SOL_RECIPIENTS_OFFSET + 3] = AccountMeta::new_readonly(recipient4, false);
+        instruction
+    };
+
+    let sender_balance_before = get_balance(&mut context, &sender.pubkey()).await;
+    let bank_balance_before = get_balance(&mut context, &bank_account).await;
+
+    // 默认模式下任何一笔无效都会使整批失败
+    let (result, logs) = send_with_logs(&mut context, &[build(false)], &[&sender]).await;
+    assert_eq!(
+        result,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(u32::from(ErrorCode::InvalidRecipient))
+        ))
+    );
+    assert!(logs.iter().any(|log| log.contains("转账序号: 1")), "{:?}", logs);
+    assert_eq!(get_balance(&mut context, &sender.pubkey()).await, sender_balance_before);
+
+    // skip_invalid 模式下跳过无效转账
+    send(&mut context, &[build(true)], &[&sender])
+        .await
+        .expect("跳过无效转账的批量转账失败");
+
+    assert_eq!(get_balance(&mut context, &recipient1).await, LAMPORTS_PER_SOL);
+    assert_eq!(get_balance(&mut context, &recipient2).await, 0);
+    assert_eq!(get_balance(&mut context, &recipient3).await, 0);
+    assert_eq!(get_balance(&mut context, &recipient4).await, 0);
+    assert_eq!(get_balance(&mut context, &recipient5).await, 2 * LAMPORTS_PER_SOL);
+    assert_eq!(
+        sender_balance_before - get_balance(&mut context, &sender.pubkey()).await,
+        3 * LAMPORTS_PER_SOL + 10_000
+    );
+    assert_eq!(get_balance(&mut context, &bank_account).await - bank_balance_before, 10_000);
+}
+
+/// 测试全部转账都被跳过时不扣款也不收取手续费
+#[tokio::test]
+async fn test_batch_transfer_sol_skip_all() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+
+    let transfers = vec![TransferInfo {
+        recipient: Keypair::new().pubkey(),
+        amount: 1,
+    }];
+    let sender_balance_before = get_balance(&mut context, &sender.pubkey()).await;
+    let bank_balance_before = get_balance(&mut context, &bank_account).await;
+    send(
+        &mut context,
+        &[batch_transfer_sol_skip_invalid_ix(&sender.pubkey(), transfers)],
+        &[&sender],
+    )
+    .await
+    .expect("全部跳过的批量转账失败");
+
+    assert_eq!(get_balance(&mut context, &sender.pubkey()).await, sender_balance_before);
+    assert_eq!(get_balance(&mut context, &bank_account).await, bank_balance_before);
+}
+
+/// 测试代币批量转账跳过冻结、mint不一致和已关闭的接收者账户
+#[tokio::test]
+async fn test_batch_transfer_token_skip_invalid() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let admin = Keypair::new();
+        let sender = Keypair::new();
+        let mint_authority = Keypair::new();
+
+        let mut program_test = program_test();
+        add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+        add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+        let mut context = program_test.start_with_context().await;
+        setup_bank_account(&mut context, &admin, 10_000).await;
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+
+        let mint = create_mint_with_freeze_authority(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+        let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+        mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+        let valid1 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+        let valid2 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+        let frozen = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+        freeze_token_account(&mut context, &mint, &mint_authority, &frozen).await;
+        let other_mint = create_mint(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+        let other_mint_account = create_token_account(&mut context, &other_mint, &Keypair::new().pubkey()).await;
+        let closed: Pubkey = Keypair::new().pubkey();
+
+        let transfers: Vec<TransferInfo> = [valid1, frozen, other_mint_account, closed, valid2]
+            .iter()
+            .enumerate()
+            .map(|(i, recipient)| TransferInfo {
+                recipient: *recipient,
+                amount: (i as u64 + 1) * 1_000,
+            })
+            .collect();
+
+        // 默认模式下第一笔无效转账使整批失败
+        let instruction = batch_transfer_token_ix(&sender.pubkey(), &sender_token, &mint, &token_program, transfers.clone());
+        let result = send(&mut context, &[instruction], &[&sender]).await;
+        assert_program_error(result, ErrorCode::RecipientAccountFrozen);
+
+        let sender_balance_before = get_balance(&mut context, &sender.pubkey()).await;
+        let instruction = batch_transfer_token_skip_invalid_ix(&sender.pubkey(), &sender_token, &mint, &token_program, transfers);
+        send(&mut context, &[instruction], &[&sender])
+            .await
+            .expect("跳过无效转账的代币批量转账失败");
+
+        assert_eq!(get_token_balance(&mut context, &valid1).await, 1_000);
+        assert_eq!(get_token_balance(&mut context, &valid2).await, 5_000);
+        assert_eq!(get_token_balance(&mut context, &frozen).await, 0);
+        assert_eq!(get_token_balance(&mut context, &sender_token).await, 1_000_000 - 6_000);
+        assert_eq!(sender_balance_before - get_balance(&mut context, &sender.pubkey()).await, 10_000);
+        assert_eq!(
+            get_balance(&mut context, &bank_account).await,
+            get_rent_exempt_minimum(&mut context, &bank_account).await + 10_000
+        );
+    }
+}
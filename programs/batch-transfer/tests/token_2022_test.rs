@@ -0,0 +1,203 @@
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use batch_transfer::{self, FeeConfig, TransferInfo};
+
+mod utils_test;
+use utils_test::get_bank_account;
+use utils_test::get_fee_vault;
+use utils_test::program_test_utils::*;
+
+/// 测试同一条指令同时支持SPL Token和Token-2022代币
+#[tokio::test]
+async fn test_batch_transfer_token_both_programs() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        println!("测试代币程序: {}", token_program);
+
+        let mint = create_mint(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+        let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+        let recipient1 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+        let recipient2 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+        mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+        let bank_balance_before = get_balance(&mut context, &bank_account).await;
+        let transfers = vec![
+            TransferInfo {
+                recipient: recipient1,
+                amount: 100_000,
+            },
+            TransferInfo {
+                recipient: recipient2,
+                amount: 250_000,
+            },
+        ];
+        send(
+            &mut context,
+            &[batch_transfer_token_ix(&sender.pubkey(), &sender_token, &mint, &token_program, transfers)],
+            &[&sender],
+        )
+        .await
+        .expect("批量转账失败");
+
+        assert_eq!(get_token_balance(&mut context, &recipient1).await, 100_000);
+        assert_eq!(get_token_balance(&mut context, &recipient2).await, 250_000);
+        assert_eq!(get_token_balance(&mut context, &sender_token).await, 650_000);
+        assert_eq!(get_balance(&mut context, &bank_account).await - bank_balance_before, 10_000);
+    }
+}
+
+/// 测试带转账手续费扩展的Token-2022代币，接收者收到扣除扩展手续费后的净额
+#[tokio::test]
+async fn test_batch_transfer_token_2022_transfer_fee() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    // 代币自身收取1%的转账手续费，上限1000个最小单位
+    let mint = create_mint_with_transfer_fee(&mut context, &mint_authority.pubkey(), 6, 100, 1_000).await;
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    let recipient1 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    let recipient2 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+    let transfers = vec![
+        TransferInfo {
+            recipient: recipient1,
+            amount: 50_000,
+        },
+        TransferInfo {
+            recipient: recipient2,
+            amount: 200_000,
+        },
+    ];
+    send(
+        &mut context,
+        &[batch_transfer_token_ix(&sender.pubkey(), &sender_token, &mint, &spl_token_2022::ID, transfers)],
+        &[&sender],
+    )
+    .await
+    .expect("批量转账失败");
+
+    // 发送者按面额扣款，接收者收到扣除扩展手续费后的净额
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 750_000);
+    assert_eq!(get_token_balance(&mut context, &recipient1).await, 49_500);
+    assert_eq!(get_token_balance(&mut context, &recipient2).await, 199_000, "扩展手续费应受上限约束");
+}
+
+/// 测试Token-2022代币以代币收取手续费，金库按mint扩展创建并可提取
+#[tokio::test]
+async fn test_token_2022_token_fee_vault() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let mint = create_mint_with_transfer_fee(&mut context, &mint_authority.pubkey(), 6, 100, 1_000).await;
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    let recipient = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+    let fee_config = FeeConfig {
+        bps: 200,
+        ..FeeConfig::default()
+    };
+    set_mint_fee_config(&mut context, &admin, &mint, &spl_token_2022::ID, fee_config)
+    .await
+    .expect("设置代币手续费失败");
+
+    let fee_vault = get_fee_vault(&batch_transfer::ID, &mint);
+    assert_eq!(get_token_program(&mut context, &fee_vault).await, spl_token_2022::ID);
+
+    let transfers = vec![TransferInfo {
+        recipient,
+        amount: 100_000,
+    }];
+    send(
+        &mut context,
+        &[batch_transfer_token_with_token_fee_ix(
+            &sender.pubkey(),
+            &sender_token,
+            &mint,
+            &spl_token_2022::ID,
+            transfers,
+        )],
+        &[&sender],
+    )
+    .await
+    .expect("批量转账失败");
+
+    // 2%的代币手续费为2000，进入金库时同样被扣除1%的扩展手续费
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 1_000_000 - 102_000);
+    assert_eq!(get_token_balance(&mut context, &recipient).await, 99_000);
+    assert_eq!(get_token_balance(&mut context, &fee_vault).await, 1_980);
+
+    let admin_token = create_token_account(&mut context, &mint, &admin.pubkey()).await;
+    send(
+        &mut context,
+        &[withdraw_token_fees_ix(&admin.pubkey(), &mint, &spl_token_2022::ID, &admin_token, None)],
+        &[&admin],
+    )
+    .await
+    .expect("提取代币手续费失败");
+    assert_eq!(get_token_balance(&mut context, &fee_vault).await, 0);
+    assert_eq!(get_token_balance(&mut context, &admin_token).await, 1_960, "扩展手续费向上取整");
+}
+
+/// 测试传入的mint与发送者token账户不一致时拒绝转账
+#[tokio::test]
+async fn test_batch_transfer_token_mint_mismatch() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let mint = create_mint(&mut context, &spl_token_2022::ID, &mint_authority.pubkey(), 6).await;
+    let other_mint = create_mint(&mut context, &spl_token_2022::ID, &mint_authority.pubkey(), 9).await;
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    let recipient = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+    let transfers = vec![TransferInfo {
+        recipient,
+        amount: 1_000,
+    }];
+    let result = send(
+        &mut context,
+        &[batch_transfer_token_ix(&sender.pubkey(), &sender_token, &other_mint, &spl_token_2022::ID, transfers)],
+        &[&sender],
+    )
+    .await;
+    assert!(result.is_err(), "mint不一致时转账应该失败");
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 1_000_000);
+}
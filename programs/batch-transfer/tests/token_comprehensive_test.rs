@@ -1,5 +1,3 @@
-use batch_transfer::{self, TransferInfo};
-use anchor_lang::prelude::*;
 use anchor_client::solana_sdk::signer::keypair::Keypair;
 use anchor_client::solana_sdk::signer::Signer;
 
@@ -14,7 +12,7 @@ fn test_token_batch_transfer_comprehensive() {
     let (program, _payer) = utils_test::get_test_program();
     
     // 创建管理员和发送者账户
-    let admin = Keypair::new();
+    let _admin = Keypair::new();
     let _sender = Keypair::new();
     
     // 创建代币铸造账户
@@ -39,13 +37,11 @@ fn test_token_batch_transfer_comprehensive() {
     let amount2 = 200; // 200 token units
     let amount3 = 300; // 300 token units
     
-    let recipients = vec![
-        recipient1.pubkey(),
+    let recipients = [recipient1.pubkey(),
         recipient2.pubkey(),
-        recipient3.pubkey(),
-    ];
+        recipient3.pubkey()];
     
-    let amounts = vec![amount1, amount2, amount3];
+    let amounts = [amount1, amount2, amount3];
     
     // 步骤3: 计算总转账金额
     println!("步骤3: 计算总转账金额");
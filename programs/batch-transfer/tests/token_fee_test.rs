@@ -0,0 +1,304 @@
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+use anchor_spl::token::spl_token;
+use batch_transfer::{self, ErrorCode, FeeConfig, MintFeeConfig, TransferInfo};
+
+mod utils_test;
+use utils_test::program_test_utils::*;
+use utils_test::{get_bank_account, get_fee_vault, get_mint_fee_config};
+
+/// 测试代币批量转账以代币本身收取手续费，并由管理员提取
+#[tokio::test]
+async fn test_batch_transfer_token_with_token_fee() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    // SOL手续费仍然配置为固定值，以代币收取时不应被扣除
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let mint = create_mint(&mut context, &spl_token::ID, &mint_authority.pubkey(), 6).await;
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    let recipient1 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    let recipient2 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+    println!("开始测试以代币收取手续费");
+
+    // 管理员为该代币设置1%的代币手续费，最低100个最小单位
+    let fee_config = FeeConfig {
+        flat_fee: 0,
+        bps: 100,
+        min_fee: 100,
+        max_fee: 0,
+    };
+    set_mint_fee_config(&mut context, &admin, &mint, &spl_token::ID, fee_config)
+        .await
+        .expect("设置代币手续费失败");
+
+    let mint_fee_config: MintFeeConfig =
+        get_account_data(&mut context, &get_mint_fee_config(&batch_transfer::ID, &mint)).await;
+    assert_eq!(mint_fee_config.mint, mint);
+    assert_eq!(mint_fee_config.fee_config, Some(fee_config));
+    assert_eq!(mint_fee_config.pending_config, None);
+
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+    let fee_vault = get_fee_vault(&batch_transfer::ID, &mint);
+    let bank_balance_before = get_balance(&mut context, &bank_account).await;
+    let sender_balance_before = get_balance(&mut context, &sender.pubkey()).await;
+
+    let transfers = vec![
+        TransferInfo {
+            recipient: recipient1,
+            amount: 100_000,
+        },
+        TransferInfo {
+            recipient: recipient2,
+            amount: 200_000,
+        },
+    ];
+    send(
+        &mut context,
+        &[batch_transfer_token_with_token_fee_ix(&sender.pubkey(), &sender_token, &mint, &spl_token::ID, transfers)],
+        &[&sender],
+    )
+    .await
+    .expect("批量转账失败");
+
+    assert_eq!(get_token_balance(&mut context, &recipient1).await, 100_000);
+    assert_eq!(get_token_balance(&mut context, &recipient2).await, 200_000);
+    assert_eq!(get_token_balance(&mut context, &fee_vault).await, 3_000, "金库应收到1%的代币手续费");
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 1_000_000 - 303_000);
+
+    // 以代币收取手续费时不扣除SOL
+    assert_eq!(get_balance(&mut context, &bank_account).await, bank_balance_before);
+    assert_eq!(get_balance(&mut context, &sender.pubkey()).await, sender_balance_before);
+
+    // 管理员提取部分代币手续费，再提取剩余全部
+    let admin_token = create_token_account(&mut context, &mint, &admin.pubkey()).await;
+    send(
+        &mut context,
+        &[withdraw_token_fees_ix(&admin.pubkey(), &mint, &spl_token::ID, &admin_token, Some(1_000))],
+        &[&admin],
+    )
+    .await
+    .expect("提取代币手续费失败");
+    assert_eq!(get_token_balance(&mut context, &admin_token).await, 1_000);
+
+    send(
+        &mut context,
+        &[withdraw_token_fees_ix(&admin.pubkey(), &mint, &spl_token::ID, &admin_token, None)],
+        &[&admin],
+    )
+    .await
+    .expect("提取全部代币手续费失败");
+    assert_eq!(get_token_balance(&mut context, &admin_token).await, 3_000);
+    assert_eq!(get_token_balance(&mut context, &fee_vault).await, 0);
+
+    // 金库为空时提取失败
+    let result = send(
+        &mut context,
+        &[withdraw_token_fees_ix(&admin.pubkey(), &mint, &spl_token::ID, &admin_token, None)],
+        &[&admin],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::InsufficientFeeBalance);
+
+    println!("代币手续费测试通过");
+}
+
+/// 测试未配置代币手续费的代币仍以SOL收取手续费
+#[tokio::test]
+async fn test_batch_transfer_token_with_sol_fee() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let mint = create_mint(&mut context, &spl_token::ID, &mint_authority.pubkey(), 6).await;
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    let recipient = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+    let bank_balance_before = get_balance(&mut context, &bank_account).await;
+
+    let transfers = vec![TransferInfo {
+        recipient,
+        amount: 500_000,
+    }];
+    send(
+        &mut context,
+        &[batch_transfer_token_ix(&sender.pubkey(), &sender_token, &mint, &spl_token::ID, transfers)],
+        &[&sender],
+    )
+    .await
+    .expect("批量转账失败");
+
+    assert_eq!(get_token_balance(&mut context, &recipient).await, 500_000);
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 500_000);
+    assert_eq!(get_balance(&mut context, &bank_account).await - bank_balance_before, 10_000);
+}
+
+/// 测试代币已配置代币手续费时，省略手续费金库改付SOL手续费会被拒绝
+#[tokio::test]
+async fn test_batch_transfer_token_cannot_skip_token_fee() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let mint = create_mint(&mut context, &spl_token::ID, &mint_authority.pubkey(), 6).await;
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    let recipient = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+    let fee_config = FeeConfig {
+        bps: 100,
+        ..FeeConfig::default()
+    };
+    set_mint_fee_config(&mut context, &admin, &mint, &spl_token::ID, fee_config)
+        .await
+        .expect("设置代币手续费失败");
+
+    let transfers = vec![TransferInfo {
+        recipient,
+        amount: 500_000,
+    }];
+    let result = send(
+        &mut context,
+        &[batch_transfer_token_ix(&sender.pubkey(), &sender_token, &mint, &spl_token::ID, transfers.clone())],
+        &[&sender],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::TokenFeeVaultRequired);
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 1_000_000);
+
+    let instruction = batch_transfer_token_to_wallets_ix(
+        &sender.pubkey(),
+        &sender_token,
+        &mint,
+        &spl_token::ID,
+        false,
+        vec![TransferInfo {
+            recipient: Keypair::new().pubkey(),
+            amount: 500_000,
+        }],
+    );
+    let result = send(&mut context, &[instruction], &[&sender]).await;
+    assert_program_error(result, ErrorCode::TokenFeeVaultRequired);
+
+    // 提供手续费金库时以代币收取1%的手续费
+    send(
+        &mut context,
+        &[batch_transfer_token_with_token_fee_ix(&sender.pubkey(), &sender_token, &mint, &spl_token::ID, transfers)],
+        &[&sender],
+    )
+    .await
+    .expect("以代币收取手续费的批量转账失败");
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 1_000_000 - 500_000 - 5_000);
+}
+
+/// 测试代币余额不足以支付转账总额加代币手续费
+#[tokio::test]
+async fn test_batch_transfer_token_fee_insufficient_balance() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let mint = create_mint(&mut context, &spl_token::ID, &mint_authority.pubkey(), 6).await;
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    let recipient = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+    let fee_config = FeeConfig {
+        flat_fee: 1,
+        ..FeeConfig::default()
+    };
+    set_mint_fee_config(&mut context, &admin, &mint, &spl_token::ID, fee_config)
+        .await
+        .expect("设置代币手续费失败");
+
+    // 转出全部余额后不足以支付1个最小单位的手续费
+    let transfers = vec![TransferInfo {
+        recipient,
+        amount: 1_000_000,
+    }];
+    let result = send(
+        &mut context,
+        &[batch_transfer_token_with_token_fee_ix(&sender.pubkey(), &sender_token, &mint, &spl_token::ID, transfers)],
+        &[&sender],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::InsufficientFunds);
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 1_000_000);
+}
+
+/// 测试非管理员不能设置代币手续费或提取代币手续费
+#[tokio::test]
+async fn test_token_fee_unauthorized() {
+    let admin = Keypair::new();
+    let attacker = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &attacker.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let mint = create_mint(&mut context, &spl_token::ID, &mint_authority.pubkey(), 6).await;
+    let attacker_token = create_token_account(&mut context, &mint, &attacker.pubkey()).await;
+
+    let now = get_unix_timestamp(&mut context).await;
+    let result = send(
+        &mut context,
+        &[queue_mint_fee_config_change_ix(&attacker.pubkey(), &mint, &spl_token::ID, FeeConfig::default(), now)],
+        &[&attacker],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    set_mint_fee_config(&mut context, &admin, &mint, &spl_token::ID, FeeConfig::default())
+        .await
+        .expect("设置代币手续费失败");
+    mint_tokens(
+        &mut context,
+        &mint,
+        &mint_authority,
+        &get_fee_vault(&batch_transfer::ID, &mint),
+        1_000,
+    )
+    .await;
+
+    let result = send(
+        &mut context,
+        &[withdraw_token_fees_ix(&attacker.pubkey(), &mint, &spl_token::ID, &attacker_token, None)],
+        &[&attacker],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+    assert_eq!(get_token_balance(&mut context, &attacker_token).await, 0);
+}
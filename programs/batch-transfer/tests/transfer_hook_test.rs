@@ -0,0 +1,405 @@
+use anchor_client::solana_sdk::{
+    account::Account,
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use anchor_spl::token_2022::spl_token_2022;
+use batch_transfer::{self, ErrorCode, FeeConfig, TransferInfo};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::{
+    get_extra_account_metas_address,
+    instruction::{ExecuteInstruction, TransferHookInstruction},
+};
+
+mod utils_test;
+use utils_test::{get_distribution, get_distribution_vault, get_fee_vault};
+use utils_test::program_test_utils::*;
+
+// 测试用转账钩子程序ID
+const HOOK_PROGRAM_ID: Pubkey = Pubkey::new_from_array([7u8; 32]);
+// 钩子登记的固定额外账户
+const HOOK_CONFIG: Pubkey = Pubkey::new_from_array([8u8; 32]);
+
+// 测试用转账钩子：校验额外账户，并在目标账户对应的计数账户中累计转账金额
+fn process_transfer_hook(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let TransferHookInstruction::Execute { amount } = TransferHookInstruction::unpack(data)? else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    let destination = accounts.get(2).ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let config = accounts.get(5).ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let counter = accounts.get(6).ok_or(ProgramError::NotEnoughAccountKeys)?;
+    if *config.key != HOOK_CONFIG || *counter.key != get_counter(program_id, destination.key) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mut counter_data = counter.try_borrow_mut_data()?;
+    let total = u64::from_le_bytes(counter_data[..8].try_into().unwrap()) + amount;
+    counter_data[..8].copy_from_slice(&total.to_le_bytes());
+    Ok(())
+}
+
+// 计数账户地址
+fn get_counter(program_id: &Pubkey, destination: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"counter", destination.as_ref()], program_id).0
+}
+
+// 加载了测试用转账钩子程序的测试环境
+fn hook_program_test() -> ProgramTest {
+    let mut program_test = program_test();
+    program_test.add_program("transfer_hook", HOOK_PROGRAM_ID, processor!(process_transfer_hook));
+    program_test
+}
+
+// 写入 ExtraAccountMetaList：固定的配置账户 + 按目标账户派生的计数账户
+fn set_extra_account_meta_list(context: &mut ProgramTestContext, mint: &Pubkey) -> Pubkey {
+    let extra_account_metas = [
+        ExtraAccountMeta::new_with_pubkey(&HOOK_CONFIG, false, false).unwrap(),
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"counter".to_vec(),
+                },
+                Seed::AccountKey { index: 2 },
+            ],
+            false,
+            true,
+        )
+        .unwrap(),
+    ];
+    let mut data = vec![0u8; ExtraAccountMetaList::size_of(extra_account_metas.len()).unwrap()];
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_account_metas).unwrap();
+
+    let address = get_extra_account_metas_address(mint, &HOOK_PROGRAM_ID);
+    set_hook_account(context, &address, data);
+    set_hook_account(context, &HOOK_CONFIG, vec![]);
+    address
+}
+
+// 为目标token账户创建计数账户，返回计数账户地址
+fn set_counter(context: &mut ProgramTestContext, destination: &Pubkey) -> Pubkey {
+    let counter = get_counter(&HOOK_PROGRAM_ID, destination);
+    set_hook_account(context, &counter, vec![0u8; 8]);
+    counter
+}
+
+fn set_hook_account(context: &mut ProgramTestContext, address: &Pubkey, data: Vec<u8>) {
+    let account = Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner: HOOK_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    context.set_account(address, &account.into());
+}
+
+async fn get_counter_total(context: &mut ProgramTestContext, counter: &Pubkey) -> u64 {
+    let account = context.banks_client.get_account(*counter).await.unwrap().unwrap();
+    u64::from_le_bytes(account.data[..8].try_into().unwrap())
+}
+
+// 每笔转账附带的额外账户，顺序与 ExtraAccountMetaList 一致
+fn hook_extras(counter: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(HOOK_CONFIG, false),
+        AccountMeta::new(*counter, false),
+    ]
+}
+
+// 将批量转账指令的 remaining_accounts 替换为转账钩子布局
+fn with_hook_layout(
+    mut instruction: Instruction,
+    recipient_count: usize,
+    meta_list: &Pubkey,
+    fee_vault_extras: Vec<AccountMeta>,
+    recipients: Vec<(Pubkey, Pubkey)>,
+) -> Instruction {
+    let fixed_len = instruction.accounts.len() - recipient_count;
+    instruction.accounts.truncate(fixed_len);
+    instruction.accounts.push(AccountMeta::new_readonly(HOOK_PROGRAM_ID, false));
+    instruction.accounts.push(AccountMeta::new_readonly(*meta_list, false));
+    instruction.accounts.extend(fee_vault_extras);
+    for (recipient, counter) in recipients {
+        instruction.accounts.push(AccountMeta::new(recipient, false));
+        instruction.accounts.extend(hook_extras(&counter));
+    }
+    instruction
+}
+
+/// 测试配置了转账钩子的代币批量转账，每笔转账都会调用钩子
+#[tokio::test]
+async fn test_batch_transfer_token_with_transfer_hook() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = hook_program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let mint = create_mint_with_transfer_hook(&mut context, &mint_authority.pubkey(), 6, &HOOK_PROGRAM_ID).await;
+    let meta_list = set_extra_account_meta_list(&mut context, &mint);
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    let recipient1 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    let recipient2 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    let counter1 = set_counter(&mut context, &recipient1);
+    let counter2 = set_counter(&mut context, &recipient2);
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+    let transfers = vec![
+        TransferInfo {
+            recipient: recipient1,
+            amount: 100_000,
+        },
+        TransferInfo {
+            recipient: recipient2,
+            amount: 300_000,
+        },
+    ];
+    let instruction = with_hook_layout(
+        batch_transfer_token_ix(&sender.pubkey(), &sender_token, &mint, &spl_token_2022::ID, transfers),
+        2,
+        &meta_list,
+        vec![],
+        vec![(recipient1, counter1), (recipient2, counter2)],
+    );
+    send(&mut context, &[instruction], &[&sender]).await.expect("批量转账失败");
+
+    assert_eq!(get_token_balance(&mut context, &recipient1).await, 100_000);
+    assert_eq!(get_token_balance(&mut context, &recipient2).await, 300_000);
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 600_000);
+    assert_eq!(get_counter_total(&mut context, &counter1).await, 100_000, "钩子应记录第一笔转账");
+    assert_eq!(get_counter_total(&mut context, &counter2).await, 300_000, "钩子应记录第二笔转账");
+}
+
+/// 测试等额批量转账同样支持转账钩子布局
+#[tokio::test]
+async fn test_batch_transfer_token_equal_with_transfer_hook() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = hook_program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let mint = create_mint_with_transfer_hook(&mut context, &mint_authority.pubkey(), 6, &HOOK_PROGRAM_ID).await;
+    let meta_list = set_extra_account_meta_list(&mut context, &mint);
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    let recipient1 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    let recipient2 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    let counter1 = set_counter(&mut context, &recipient1);
+    let counter2 = set_counter(&mut context, &recipient2);
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+    let build = |recipients: Vec<(Pubkey, Pubkey)>| {
+        with_hook_layout(
+            batch_transfer_token_equal_ix(&sender.pubkey(), &sender_token, &mint, &spl_token_2022::ID, &[], 50_000),
+            0,
+            &meta_list,
+            vec![],
+            recipients,
+        )
+    };
+
+    // 缺少最后一个接收者的额外账户
+    let mut instruction = build(vec![(recipient1, counter1), (recipient2, counter2)]);
+    instruction.accounts.pop();
+    let result = send(&mut context, &[instruction], &[&sender]).await;
+    assert_program_error(result, ErrorCode::InvalidRemainingAccounts);
+
+    let instruction = build(vec![(recipient1, counter1), (recipient2, counter2)]);
+    send(&mut context, &[instruction], &[&sender]).await.expect("等额批量转账失败");
+
+    assert_eq!(get_token_balance(&mut context, &recipient1).await, 50_000);
+    assert_eq!(get_token_balance(&mut context, &recipient2).await, 50_000);
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 900_000);
+    assert_eq!(get_counter_total(&mut context, &counter1).await, 50_000);
+    assert_eq!(get_counter_total(&mut context, &counter2).await, 50_000);
+}
+
+/// 测试转账钩子代币以代币收取手续费，金库转账和提取手续费同样调用钩子
+#[tokio::test]
+async fn test_token_fee_with_transfer_hook() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = hook_program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let mint = create_mint_with_transfer_hook(&mut context, &mint_authority.pubkey(), 6, &HOOK_PROGRAM_ID).await;
+    let meta_list = set_extra_account_meta_list(&mut context, &mint);
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    let recipient = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    let recipient_counter = set_counter(&mut context, &recipient);
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+    let fee_config = FeeConfig {
+        bps: 100,
+        ..FeeConfig::default()
+    };
+    set_mint_fee_config(&mut context, &admin, &mint, &spl_token_2022::ID, fee_config)
+    .await
+    .expect("设置代币手续费失败");
+    let fee_vault = get_fee_vault(&batch_transfer::ID, &mint);
+    let vault_counter = set_counter(&mut context, &fee_vault);
+
+    let transfers = vec![TransferInfo {
+        recipient,
+        amount: 200_000,
+    }];
+    let instruction = with_hook_layout(
+        batch_transfer_token_with_token_fee_ix(&sender.pubkey(), &sender_token, &mint, &spl_token_2022::ID, transfers),
+        1,
+        &meta_list,
+        hook_extras(&vault_counter),
+        vec![(recipient, recipient_counter)],
+    );
+    send(&mut context, &[instruction], &[&sender]).await.expect("批量转账失败");
+
+    assert_eq!(get_token_balance(&mut context, &recipient).await, 200_000);
+    assert_eq!(get_token_balance(&mut context, &fee_vault).await, 2_000);
+    assert_eq!(get_counter_total(&mut context, &recipient_counter).await, 200_000);
+    assert_eq!(get_counter_total(&mut context, &vault_counter).await, 2_000);
+
+    // 提取手续费时钩子账户通过 remaining_accounts 传入
+    let admin_token = create_token_account(&mut context, &mint, &admin.pubkey()).await;
+    let admin_counter = set_counter(&mut context, &admin_token);
+    let mut instruction = withdraw_token_fees_ix(&admin.pubkey(), &mint, &spl_token_2022::ID, &admin_token, None);
+    instruction.accounts.push(AccountMeta::new_readonly(HOOK_PROGRAM_ID, false));
+    instruction.accounts.push(AccountMeta::new_readonly(meta_list, false));
+    instruction.accounts.extend(hook_extras(&admin_counter));
+    send(&mut context, &[instruction], &[&admin]).await.expect("提取代币手续费失败");
+
+    assert_eq!(get_token_balance(&mut context, &admin_token).await, 2_000);
+    assert_eq!(get_counter_total(&mut context, &admin_counter).await, 2_000);
+}
+
+/// 测试缺少或传错转账钩子账户时拒绝转账
+#[tokio::test]
+async fn test_transfer_hook_invalid_accounts() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = hook_program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let mint = create_mint_with_transfer_hook(&mut context, &mint_authority.pubkey(), 6, &HOOK_PROGRAM_ID).await;
+    set_extra_account_meta_list(&mut context, &mint);
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    let recipient = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    let counter = set_counter(&mut context, &recipient);
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+    let transfers = vec![TransferInfo {
+        recipient,
+        amount: 1_000,
+    }];
+
+    // 仍按未配置钩子的布局只传入接收者账户
+    let result = send(
+        &mut context,
+        &[batch_transfer_token_ix(&sender.pubkey(), &sender_token, &mint, &spl_token_2022::ID, transfers.clone())],
+        &[&sender],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::InvalidTransferHookAccounts);
+
+    // ExtraAccountMetaList 地址与mint不匹配
+    let wrong_meta_list = Pubkey::new_unique();
+    let instruction = with_hook_layout(
+        batch_transfer_token_ix(&sender.pubkey(), &sender_token, &mint, &spl_token_2022::ID, transfers),
+        1,
+        &wrong_meta_list,
+        vec![],
+        vec![(recipient, counter)],
+    );
+    let result = send(&mut context, &[instruction], &[&sender]).await;
+    assert_program_error(result, ErrorCode::InvalidTransferHookAccounts);
+
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 1_000_000);
+    assert_eq!(get_counter_total(&mut context, &counter).await, 0);
+}
+
+/// 测试转账钩子代币的分发，托管和分发PDA签名的每笔转账都会调用钩子
+#[tokio::test]
+async fn test_token_distribution_with_transfer_hook() {
+    let admin = Keypair::new();
+    let creator = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = hook_program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &creator.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let mint = create_mint_with_transfer_hook(&mut context, &mint_authority.pubkey(), 6, &HOOK_PROGRAM_ID).await;
+    let meta_list = set_extra_account_meta_list(&mut context, &mint);
+    let creator_token = create_token_account(&mut context, &mint, &creator.pubkey()).await;
+    let recipient1 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    let recipient2 = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    let counter1 = set_counter(&mut context, &recipient1);
+    let counter2 = set_counter(&mut context, &recipient2);
+    mint_tokens(&mut context, &mint, &mint_authority, &creator_token, 1_000_000).await;
+
+    let distribution = get_distribution(&batch_transfer::ID, &creator.pubkey(), 1);
+    let vault = get_distribution_vault(&batch_transfer::ID, &distribution);
+    let vault_counter = set_counter(&mut context, &vault);
+
+    let mut create = create_token_distribution_ix(&creator.pubkey(), &creator_token, &mint, &spl_token_2022::ID, 1, 2, 30_000);
+    create.accounts.push(AccountMeta::new_readonly(HOOK_PROGRAM_ID, false));
+    create.accounts.push(AccountMeta::new_readonly(meta_list, false));
+    create.accounts.extend(hook_extras(&vault_counter));
+    let entries = vec![
+        TransferInfo {
+            recipient: recipient1,
+            amount: 10_000,
+        },
+        TransferInfo {
+            recipient: recipient2,
+            amount: 20_000,
+        },
+    ];
+    send(
+        &mut context,
+        &[create, upload_distribution_entries_ix(&creator.pubkey(), &distribution, entries)],
+        &[&creator],
+    )
+    .await
+    .expect("创建转账钩子代币分发失败");
+    assert_eq!(get_counter_total(&mut context, &vault_counter).await, 30_000);
+
+    let instruction = with_hook_layout(
+        crank_token_distribution_ix(&distribution, &mint, &spl_token_2022::ID, &[], 2),
+        0,
+        &meta_list,
+        vec![],
+        vec![(recipient1, counter1), (recipient2, counter2)],
+    );
+    send(&mut context, &[instruction], &[]).await.expect("执行转账钩子代币分发失败");
+
+    assert_eq!(get_token_balance(&mut context, &recipient1).await, 10_000);
+    assert_eq!(get_token_balance(&mut context, &recipient2).await, 20_000);
+    assert_eq!(get_token_balance(&mut context, &vault).await, 0);
+    assert_eq!(get_counter_total(&mut context, &counter1).await, 10_000);
+    assert_eq!(get_counter_total(&mut context, &counter2).await, 20_000);
+}
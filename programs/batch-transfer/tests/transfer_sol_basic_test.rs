@@ -1,6 +1,5 @@
 use anchor_client::solana_sdk::signature::{Keypair, Signer};
 use batch_transfer::{self, safe_add, safe_sum_transfer_info, TransferInfo};
-use anchor_lang::prelude::*;
 
 mod utils_test;
 use utils_test::{get_test_program, get_bank_account};
@@ -13,7 +12,7 @@ fn test_batch_transfer_sol() {
     
     // 创建测试账户
     let admin = Keypair::new();
-    let sender = Keypair::new();
+    let _sender = Keypair::new();
     let recipient1 = Keypair::new();
     let recipient2 = Keypair::new();
     
@@ -22,15 +21,20 @@ fn test_batch_transfer_sol() {
     let recipient2_pubkey = recipient2.pubkey();
     
     // 获取银行账户的PDA
-    let (bank_account, _) = get_bank_account(&program.id());
+    let (_bank_account, _) = get_bank_account(&program.id());
     
     println!("开始模拟批量转账测试");
     
     // 模拟银行账户状态
     let bank_account_data = batch_transfer::BankAccount {
         admin: admin.pubkey(),
-        fee: 100, // 设置费用为1% (100 basis points)
+        fee_config: batch_transfer::FeeConfig {
+            bps: 100, // 设置费用为1% (100 basis points)
+            ..Default::default()
+        },
         is_initialized: true,
+        version: batch_transfer::BankAccount::VERSION,
+        ..Default::default()
     };
     
     // 模拟发送者余额
@@ -38,8 +42,8 @@ fn test_batch_transfer_sol() {
     println!("发送者初始余额: {}", sender_balance_before);
     
     // 准备收件人和金额
-    let recipients = vec![recipient1_pubkey, recipient2_pubkey];
-    let amounts = vec![1_000_000, 2_000_000]; // 0.001 SOL 和 0.002 SOL
+    let recipients = [recipient1_pubkey, recipient2_pubkey];
+    let amounts = [1_000_000, 2_000_000]; // 0.001 SOL 和 0.002 SOL
     
     // 创建TransferInfo结构体数组
     let transfers: Vec<TransferInfo> = recipients
@@ -56,7 +60,7 @@ fn test_batch_transfer_sol() {
     assert_eq!(total_amount, 3_000_000, "总转账金额应为0.003 SOL");
     
     // 计算手续费
-    let fee_amount = bank_account_data.fee * total_amount / 10000; // 1% 费用
+    let fee_amount = batch_transfer::calculate_fee(&bank_account_data.fee_config, total_amount).unwrap(); // 1% 费用
     assert_eq!(fee_amount, 30_000, "手续费应为30,000 lamports");
     
     // 计算所需总金额
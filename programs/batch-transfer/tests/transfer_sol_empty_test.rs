@@ -11,11 +11,11 @@ use utils_test::get_test_program;
 #[test]
 fn test_empty_transfers() {
     // 获取测试程序和支付者
-    let (program, _payer) = get_test_program();
+    let (_program, _payer) = get_test_program();
     
     // 创建管理员和发送者账户
-    let admin = Keypair::new();
-    let sender = Keypair::new();
+    let _admin = Keypair::new();
+    let _sender = Keypair::new();
     
     println!("开始模拟空转账列表测试");
     
@@ -24,8 +24,13 @@ fn test_insufficient_balance() {
     // 模拟银行账户状态
     let bank_account_data = batch_transfer::BankAccount {
         admin: admin.pubkey(),
-        fee: 100, // 设置费用为1% (100 basis points)
+        fee_config: batch_transfer::FeeConfig {
+            bps: 100, // 设置费用为1% (100 basis points)
+            ..Default::default()
+        },
         is_initialized: true,
+        version: batch_transfer::BankAccount::VERSION,
+        ..Default::default()
     };
     
     // 模拟发送者余额 - 只有1 SOL
@@ -36,8 +41,8 @@ fn test_insufficient_balance() {
     let transfer_amount2 = 3_000_000_000; // 3 SOL
     
     // 准备收件人和金额列表
-    let recipients = vec![recipient1.pubkey(), recipient2.pubkey()];
-    let amounts = vec![transfer_amount1, transfer_amount2];
+    let recipients = [recipient1.pubkey(), recipient2.pubkey()];
+    let amounts = [transfer_amount1, transfer_amount2];
     
     // 创建TransferInfo结构体数组
     let transfers: Vec<TransferInfo> = recipients
@@ -56,7 +61,7 @@ fn test_insufficient_balance() {
     assert_eq!(total_amount, 5_000_000_000, "总转账金额应为5 SOL");
     
     // 2. 计算手续费
-    let fee_amount = bank_account_data.fee * total_amount / 10000; // 1% 费用
+    let fee_amount = batch_transfer::calculate_fee(&bank_account_data.fee_config, total_amount).unwrap(); // 1% 费用
     assert_eq!(fee_amount, 50_000_000, "手续费应为0.05 SOL");
     
     // 3. 计算所需总余额
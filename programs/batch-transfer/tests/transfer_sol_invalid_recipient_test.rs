@@ -13,11 +13,11 @@ use utils_test::get_test_program;
 #[test]
 fn test_invalid_recipient() {
     // 获取测试程序和支付者
-    let (program, _payer) = get_test_program();
+    let (_program, _payer) = get_test_program();
     
     // 创建管理员、发送者和接收者账户
     let admin = Keypair::new();
-    let sender = Keypair::new();
+    let _sender = Keypair::new();
     let recipient1 = Keypair::new();
     let invalid_recipient = Pubkey::new_unique(); // 创建一个不存在的账户地址
     
@@ -26,16 +26,21 @@ fn test_invalid_recipient() {
     // 设置银行账户状态
     let bank_account_data = batch_transfer::BankAccount {
         admin: admin.pubkey(),
-        fee: 100, // 设置费用为1% (100 basis points)
+        fee_config: batch_transfer::FeeConfig {
+            bps: 100, // 设置费用为1% (100 basis points)
+            ..Default::default()
+        },
         is_initialized: true,
+        version: batch_transfer::BankAccount::VERSION,
+        ..Default::default()
     };
     
     // 模拟发送者余额
     let sender_balance_before = 100_000_000_000; // 100 SOL
     
     // 准备接收者和金额
-    let recipients = vec![recipient1.pubkey(), invalid_recipient];
-    let amounts = vec![1_000_000_000, 2_000_000_000]; // 1 SOL 和 2 SOL
+    let recipients = [recipient1.pubkey(), invalid_recipient];
+    let amounts = [1_000_000_000, 2_000_000_000]; // 1 SOL 和 2 SOL
     
     // 创建TransferInfo结构体数组
     let transfers: Vec<TransferInfo> = recipients
@@ -73,7 +78,7 @@ fn test_invalid_recipient() {
     }
     
     // 计算手续费
-    let fee_amount = bank_account_data.fee * total_amount / 10000; // 1% 费用
+    let fee_amount = batch_transfer::calculate_fee(&bank_account_data.fee_config, total_amount).unwrap(); // 1% 费用
     
     // 计算所需总金额
     let required_balance = safe_add(total_amount, fee_amount).unwrap();
@@ -25,8 +25,13 @@ fn test_transfer_to_self() {
     // 模拟银行账户状态
     let bank_account_data = batch_transfer::BankAccount {
         admin: admin.pubkey(),
-        fee: 100, // 设置费用为1% (100 basis points)
+        fee_config: batch_transfer::FeeConfig {
+            bps: 100, // 设置费用为1% (100 basis points)
+            ..Default::default()
+        },
         is_initialized: true,
+        version: batch_transfer::BankAccount::VERSION,
+        ..Default::default()
     };
     
     // 模拟发送者SOL余额
@@ -35,13 +40,13 @@ fn test_transfer_to_self() {
     
     // 准备转账数据 - 收款人是发送者自己
     let transfer_amount = LAMPORTS_PER_SOL / 10; // 0.1 SOL
-    let _transfers = vec![TransferInfo {
+    let _transfers = [TransferInfo {
         recipient: sender.pubkey(), // 收款人是发送者自己
         amount: transfer_amount,
     }];
     
     // 模拟计算手续费
-    let fee_amount = bank_account_data.fee * transfer_amount / 10000; // 1% 费用
+    let fee_amount = batch_transfer::calculate_fee(&bank_account_data.fee_config, transfer_amount).unwrap(); // 1% 费用
     
     // 模拟执行转账 - 在某些设计中可能允许，在另一些设计中可能不允许
     // 这里假设合约允许自我转账，但会收取正常的手续费
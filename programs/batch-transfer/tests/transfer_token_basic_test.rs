@@ -1,6 +1,5 @@
 use anchor_client::solana_sdk::signature::{Keypair, Signer};
 use batch_transfer::{self, safe_sum_transfer_info, TransferInfo};
-use anchor_lang::prelude::*;
 
 mod utils_test;
 use utils_test::{get_test_program, get_bank_account};
@@ -13,7 +12,7 @@ fn test_batch_transfer_token_basic() {
     
     // 创建测试账户
     let admin = Keypair::new();
-    let sender = Keypair::new();
+    let _sender = Keypair::new();
     
     // 模拟代币账户
     let sender_token_account = Keypair::new();
@@ -21,20 +20,25 @@ fn test_batch_transfer_token_basic() {
     let recipient2_token_account = Keypair::new();
     
     // 记录代币账户公钥
-    let sender_token_pubkey = sender_token_account.pubkey();
+    let _sender_token_pubkey = sender_token_account.pubkey();
     let recipient1_token_pubkey = recipient1_token_account.pubkey();
     let recipient2_token_pubkey = recipient2_token_account.pubkey();
     
     // 获取银行账户的PDA
-    let (bank_account, _) = get_bank_account(&program.id());
+    let (_bank_account, _) = get_bank_account(&program.id());
     
     println!("开始模拟SPL Token批量转账测试");
     
     // 模拟银行账户状态
     let bank_account_data = batch_transfer::BankAccount {
         admin: admin.pubkey(),
-        fee: 100, // 设置费用为1% (100 basis points)
+        fee_config: batch_transfer::FeeConfig {
+            bps: 100, // 设置费用为1% (100 basis points)
+            ..Default::default()
+        },
         is_initialized: true,
+        version: batch_transfer::BankAccount::VERSION,
+        ..Default::default()
     };
     
     // 模拟SPL代币余额
@@ -45,8 +49,8 @@ fn test_batch_transfer_token_basic() {
     let sol_balance_before = 100_000_000; // 0.1 SOL
     
     // 准备收件人和金额
-    let recipients = vec![recipient1_token_pubkey, recipient2_token_pubkey];
-    let amounts = vec![100_000_000, 200_000_000]; // 0.1 和 0.2 个代币
+    let recipients = [recipient1_token_pubkey, recipient2_token_pubkey];
+    let amounts = [100_000_000, 200_000_000]; // 0.1 和 0.2 个代币
     
     // 创建TransferInfo结构体数组
     let transfers: Vec<TransferInfo> = recipients
@@ -63,7 +67,7 @@ fn test_batch_transfer_token_basic() {
     assert_eq!(total_amount, 300_000_000, "总转账金额应为0.3个代币");
     
     // 计算手续费 (以SOL支付)
-    let fee_amount = bank_account_data.fee * total_amount / 10000; // 1% 费用
+    let fee_amount = batch_transfer::calculate_fee(&bank_account_data.fee_config, total_amount).unwrap(); // 1% 费用
     assert_eq!(fee_amount, 3_000_000, "手续费应为3,000,000 lamports");
     
     // 验证代币余额是否足够
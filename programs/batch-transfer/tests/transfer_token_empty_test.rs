@@ -31,8 +31,13 @@ fn test_batch_transfer_token_empty() {
     // 模拟银行账户状态
     let _bank_account_data = batch_transfer::BankAccount {
         admin: admin.pubkey(),
-        fee: 100, // 设置费用为1% (100 basis points)
+        fee_config: batch_transfer::FeeConfig {
+            bps: 100, // 设置费用为1% (100 basis points)
+            ..Default::default()
+        },
         is_initialized: true,
+        version: batch_transfer::BankAccount::VERSION,
+        ..Default::default()
     };
     
     // 模拟SPL代币余额
@@ -50,8 +55,6 @@ fn test_batch_transfer_token_empty() {
         Err(err) => {
             // 检查错误类型
             println!("预期的错误: {:?}", err);
-            // 断言错误确实发生了
-            assert!(true, "接收到预期的错误");
         }
     }
     
@@ -3,7 +3,6 @@ use anchor_client::solana_sdk::{
         program_error::ProgramError,
     };
 use batch_transfer::{self, safe_sum_transfer_info, TransferInfo};
-use anchor_lang::prelude::*;
 
 mod utils_test;
 use utils_test::{get_test_program, get_bank_account};
@@ -16,26 +15,31 @@ fn test_batch_transfer_token_insufficient_balance() {
     
     // 创建测试账户
     let admin = Keypair::new();
-    let sender = Keypair::new();
+    let _sender = Keypair::new();
     
     // 模拟代币账户
     let sender_token_account = Keypair::new();
     let recipient_token_account = Keypair::new();
     
     // 记录代币账户公钥
-    let sender_token_pubkey = sender_token_account.pubkey();
+    let _sender_token_pubkey = sender_token_account.pubkey();
     let recipient_token_pubkey = recipient_token_account.pubkey();
     
     // 获取银行账户的PDA
-    let (bank_account, _) = get_bank_account(&program.id());
+    let (_bank_account, _) = get_bank_account(&program.id());
     
     println!("开始测试SPL Token余额不足的情况");
     
     // 模拟银行账户状态
-    let bank_account_data = batch_transfer::BankAccount {
+    let _bank_account_data = batch_transfer::BankAccount {
         admin: admin.pubkey(),
-        fee: 100, // 设置费用为1% (100 basis points)
+        fee_config: batch_transfer::FeeConfig {
+            bps: 100, // 设置费用为1% (100 basis points)
+            ..Default::default()
+        },
         is_initialized: true,
+        version: batch_transfer::BankAccount::VERSION,
+        ..Default::default()
     };
     
     // 模拟SPL代币余额 - 设置一个较小的余额
@@ -43,7 +47,7 @@ fn test_batch_transfer_token_insufficient_balance() {
     println!("发送者代币余额: {}", token_balance);
     
     // 模拟SOL余额
-    let sol_balance = 100_000_000; // 0.1 SOL
+    let _sol_balance = 100_000_000; // 0.1 SOL
     
     // 准备转账数据 - 金额超过余额
     let transfer_amount = 100_000_000; // 0.1 个代币，超过余额
@@ -68,8 +72,6 @@ fn test_batch_transfer_token_insufficient_balance() {
         Err(err) => {
             // 这里检查错误类型，在实际合约执行中会是InsufficientFunds错误
             println!("预期的错误: {:?}", err);
-            // 由于这是模拟测试，我们只断言错误确实发生了
-            assert!(true, "接收到预期的错误");
         }
     }
     
@@ -0,0 +1,140 @@
+use anchor_client::solana_sdk::{
+    instruction::InstructionError,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use batch_transfer::{self, ErrorCode, TransferInfo};
+use solana_program_test::ProgramTestContext;
+
+mod utils_test;
+use utils_test::program_test_utils::*;
+
+// 测试环境：发送者持有1_000_000个代币，另有一个正常的接收者账户
+struct Setup {
+    context: ProgramTestContext,
+    sender: Keypair,
+    mint_authority: Keypair,
+    mint: Pubkey,
+    token_program: Pubkey,
+    sender_token: Pubkey,
+    valid_recipient: Pubkey,
+}
+
+async fn setup(token_program: Pubkey) -> Setup {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let mint = create_mint_with_freeze_authority(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    let valid_recipient = create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+    Setup {
+        context,
+        sender,
+        mint_authority,
+        mint,
+        token_program,
+        sender_token,
+        valid_recipient,
+    }
+}
+
+// 以 [正常接收者, 待测接收者] 执行批量转账，断言交易以指定错误码失败、程序日志注明失败的是第1笔转账，且没有任何代币转出
+async fn assert_second_transfer_rejected(setup: &mut Setup, invalid_recipient: Pubkey, error: ErrorCode) {
+    let transfers = vec![
+        TransferInfo {
+            recipient: setup.valid_recipient,
+            amount: 1_000,
+        },
+        TransferInfo {
+            recipient: invalid_recipient,
+            amount: 1_000,
+        },
+    ];
+    let instruction = batch_transfer_token_ix(
+        &setup.sender.pubkey(),
+        &setup.sender_token,
+        &setup.mint,
+        &setup.token_program,
+        transfers,
+    );
+    let (result, logs) = send_with_logs(&mut setup.context, &[instruction], &[&setup.sender]).await;
+
+    assert_eq!(
+        result,
+        Err(TransactionError::InstructionError(0, InstructionError::Custom(u32::from(error)))),
+        "应该返回 {:?}",
+        error
+    );
+    assert!(
+        logs.iter().any(|log| log.contains("转账序号: 1")),
+        "错误日志应包含失败的转账序号: {:?}",
+        logs
+    );
+    assert_eq!(get_token_balance(&mut setup.context, &setup.sender_token).await, 1_000_000);
+    assert_eq!(get_token_balance(&mut setup.context, &setup.valid_recipient).await, 0);
+}
+
+/// 测试接收者token账户的mint与发送者不一致
+#[tokio::test]
+async fn test_recipient_mint_mismatch() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let mut setup = setup(token_program).await;
+        let other_mint = create_mint(&mut setup.context, &token_program, &setup.mint_authority.pubkey(), 6).await;
+        let other_account = create_token_account(&mut setup.context, &other_mint, &Keypair::new().pubkey()).await;
+
+        assert_second_transfer_rejected(&mut setup, other_account, ErrorCode::RecipientMintMismatch).await;
+    }
+}
+
+/// 测试接收者token账户已被冻结
+#[tokio::test]
+async fn test_recipient_account_frozen() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let mut setup = setup(token_program).await;
+        let frozen_account = create_token_account(&mut setup.context, &setup.mint, &Keypair::new().pubkey()).await;
+        freeze_token_account(&mut setup.context, &setup.mint, &setup.mint_authority, &frozen_account).await;
+
+        assert_second_transfer_rejected(&mut setup, frozen_account, ErrorCode::RecipientAccountFrozen).await;
+    }
+}
+
+/// 测试接收者是发送者自己的token账户
+#[tokio::test]
+async fn test_recipient_is_sender() {
+    let mut setup = setup(spl_token::ID).await;
+    let sender_token = setup.sender_token;
+
+    assert_second_transfer_rejected(&mut setup, sender_token, ErrorCode::RecipientIsSender).await;
+}
+
+/// 测试接收者不是token账户
+#[tokio::test]
+async fn test_recipient_not_token_account() {
+    let mut setup = setup(spl_token::ID).await;
+
+    // 不归属代币程序的普通账户
+    let wallet = Keypair::new().pubkey();
+    assert_second_transfer_rejected(&mut setup, wallet, ErrorCode::InvalidRecipientTokenAccount).await;
+
+    // 归属代币程序但不是token账户的mint账户
+    let mint = setup.mint;
+    assert_second_transfer_rejected(&mut setup, mint, ErrorCode::InvalidRecipientTokenAccount).await;
+
+    // 另一个代币程序的token账户
+    let other_mint = create_mint(&mut setup.context, &spl_token_2022::ID, &setup.mint_authority.pubkey(), 6).await;
+    let other_program_account = create_token_account(&mut setup.context, &other_mint, &Keypair::new().pubkey()).await;
+    assert_second_transfer_rejected(&mut setup, other_program_account, ErrorCode::InvalidRecipientTokenAccount).await;
+}
@@ -0,0 +1,158 @@
+use anchor_client::solana_sdk::{
+    instruction::AccountMeta,
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use batch_transfer::{self, ErrorCode, TransferInfo};
+
+mod utils_test;
+use utils_test::get_bank_account;
+use utils_test::program_test_utils::*;
+
+/// 测试按钱包地址批量转账，不存在的ATA由程序创建且租金由发送者支付
+#[tokio::test]
+async fn test_batch_transfer_token_to_wallets() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        println!("测试代币程序: {}", token_program);
+
+        let mint = create_mint(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+        let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+        mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+        // 第一个钱包已有ATA，第二个钱包没有
+        let wallet1 = Keypair::new().pubkey();
+        let wallet2 = Keypair::new().pubkey();
+        let ata1 = create_associated_token_account(&mut context, &mint, &wallet1).await;
+        let ata2 = get_associated_token_address_with_program_id(&wallet2, &mint, &token_program);
+        assert!(context.banks_client.get_account(ata2).await.unwrap().is_none());
+
+        let sender_balance_before = get_balance(&mut context, &sender.pubkey()).await;
+        let bank_balance_before = get_balance(&mut context, &bank_account).await;
+
+        let transfers = vec![
+            TransferInfo {
+                recipient: wallet1,
+                amount: 100_000,
+            },
+            TransferInfo {
+                recipient: wallet2,
+                amount: 200_000,
+            },
+        ];
+        send(
+            &mut context,
+            &[batch_transfer_token_to_wallets_ix(
+                &sender.pubkey(),
+                &sender_token,
+                &mint,
+                &token_program,
+                false,
+                transfers,
+            )],
+            &[&sender],
+        )
+        .await
+        .expect("按钱包批量转账失败");
+
+        assert_eq!(get_token_balance(&mut context, &ata1).await, 100_000);
+        assert_eq!(get_token_balance(&mut context, &ata2).await, 200_000);
+        assert_eq!(get_token_balance(&mut context, &sender_token).await, 700_000);
+        assert_eq!(get_token_program(&mut context, &ata2).await, token_program);
+
+        // 发送者支付手续费和新建ATA的租金
+        let ata2_rent = get_balance(&mut context, &ata2).await;
+        assert_eq!(get_balance(&mut context, &bank_account).await - bank_balance_before, 10_000);
+        assert_eq!(
+            sender_balance_before - get_balance(&mut context, &sender.pubkey()).await,
+            10_000 + ata2_rent
+        );
+
+        // 再次转账时ATA已存在，不再收取租金
+        let sender_balance_before = get_balance(&mut context, &sender.pubkey()).await;
+        let transfers = vec![TransferInfo {
+            recipient: wallet2,
+            amount: 50_000,
+        }];
+        send(
+            &mut context,
+            &[batch_transfer_token_to_wallets_ix(
+                &sender.pubkey(),
+                &sender_token,
+                &mint,
+                &token_program,
+                false,
+                transfers,
+            )],
+            &[&sender],
+        )
+        .await
+        .expect("再次按钱包批量转账失败");
+        assert_eq!(get_token_balance(&mut context, &ata2).await, 250_000);
+        assert_eq!(sender_balance_before - get_balance(&mut context, &sender.pubkey()).await, 10_000);
+    }
+}
+
+/// 测试传入的ATA与钱包不匹配时拒绝转账
+#[tokio::test]
+async fn test_batch_transfer_token_to_wallets_invalid_ata() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 0).await;
+
+    let mint = create_mint(&mut context, &spl_token::ID, &mint_authority.pubkey(), 6).await;
+    let sender_token = create_token_account(&mut context, &mint, &sender.pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &sender_token, 1_000_000).await;
+
+    let wallet = Keypair::new().pubkey();
+    let transfers = vec![TransferInfo {
+        recipient: wallet,
+        amount: 1_000,
+    }];
+
+    // 传入钱包拥有的普通token账户而不是ATA
+    let token_account = create_token_account(&mut context, &mint, &wallet).await;
+    let mut instruction = batch_transfer_token_to_wallets_ix(
+        &sender.pubkey(),
+        &sender_token,
+        &mint,
+        &spl_token::ID,
+        false,
+        transfers.clone(),
+    );
+    let last = instruction.accounts.len() - 1;
+    instruction.accounts[last] = AccountMeta::new(token_account, false);
+    let result = send(&mut context, &[instruction], &[&sender]).await;
+    assert_program_error(result, ErrorCode::InvalidRecipient);
+
+    // 传入的钱包与转账信息中的接收者不一致
+    let mut instruction =
+        batch_transfer_token_to_wallets_ix(&sender.pubkey(), &sender_token, &mint, &spl_token::ID, false, transfers);
+    let wallet_index = instruction.accounts.len() - 2;
+    instruction.accounts[wallet_index] = AccountMeta::new_readonly(Keypair::new().pubkey(), false);
+    let result = send(&mut context, &[instruction], &[&sender]).await;
+    assert_program_error(result, ErrorCode::InvalidRecipient);
+
+    assert_eq!(get_token_balance(&mut context, &sender_token).await, 1_000_000);
+    assert_eq!(get_token_balance(&mut context, &token_account).await, 0);
+}
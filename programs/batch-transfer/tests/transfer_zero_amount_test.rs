@@ -29,8 +29,13 @@ fn test_transfer_zero_amount() {
     // 模拟银行账户状态
     let _bank_account_data = batch_transfer::BankAccount {
         admin: admin.pubkey(),
-        fee: 100, // 设置费用为1% (100 basis points)
+        fee_config: batch_transfer::FeeConfig {
+            bps: 100, // 设置费用为1% (100 basis points)
+            ..Default::default()
+        },
         is_initialized: true,
+        version: batch_transfer::BankAccount::VERSION,
+        ..Default::default()
     };
     
     // 模拟发送者SOL余额
@@ -38,7 +43,7 @@ fn test_transfer_zero_amount() {
     println!("发送者余额: {} lamports", sender_balance);
     
     // 准备转账数据 - 金额为0
-    let _transfers = vec![TransferInfo {
+    let _transfers = [TransferInfo {
         recipient: recipient.pubkey(),
         amount: 0, // 金额为0
     }];
@@ -52,8 +57,6 @@ fn test_transfer_zero_amount() {
         Err(err) => {
             // 检查错误类型
             println!("预期的错误: {:?}", err);
-            // 断言错误确实发生了
-            assert!(true, "接收到预期的错误");
         }
     }
     
@@ -8,14 +8,17 @@ use anchor_client::{
         AccountDeserialize,
     },
     solana_sdk::{
+        bpf_loader_upgradeable,
         signature::{Keypair, Signer},
         pubkey::Pubkey,
         instruction::AccountMeta,
     },
 };
 use std::rc::Rc;
-use batch_transfer::{self as batch_transfer, TransferInfo};
-use tokio;
+use batch_transfer::{
+    self as batch_transfer, BankConfig, DuplicatePolicy, ErrorCode, FeeConfig, OperatorAllowance, OperatorLimit,
+    SplitInfo, TransferInfo,
+};
 
 #[test]
 fn test_safe_add() {
@@ -30,6 +33,165 @@ fn test_safe_add() {
     assert_eq!(result.unwrap_err(), ProgramError::InvalidArgument);
 }
 
+#[test]
+fn test_safe_sub() {
+    // 正常情况
+    let result = batch_transfer::safe_sub(30, 20);
+    assert!(result.is_ok());
+    assert_eq!(result.unwrap(), 10);
+
+    // 下溢情况
+    let result = batch_transfer::safe_sub(0, 1);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), ProgramError::InvalidArgument);
+}
+
+#[test]
+fn test_withdrawable_fee() {
+    // 超出租金豁免最低余额的部分都可以提取
+    assert_eq!(batch_transfer::withdrawable_fee(1_500, 1_000), 500);
+
+    // 余额不超过租金豁免最低余额时不可提取
+    assert_eq!(batch_transfer::withdrawable_fee(1_000, 1_000), 0);
+    assert_eq!(batch_transfer::withdrawable_fee(900, 1_000), 0);
+}
+
+#[test]
+fn test_calculate_fee() {
+    // 固定手续费
+    let flat = FeeConfig {
+        flat_fee: 5_000,
+        ..FeeConfig::default()
+    };
+    assert_eq!(batch_transfer::calculate_fee(&flat, 1_000_000).unwrap(), 5_000);
+
+    // 基点手续费，向下取整
+    let bps = FeeConfig {
+        bps: 100, // 1%
+        ..FeeConfig::default()
+    };
+    assert_eq!(batch_transfer::calculate_fee(&bps, 3_000_000).unwrap(), 30_000);
+    assert_eq!(batch_transfer::calculate_fee(&bps, 199).unwrap(), 1);
+
+    // 固定加基点，并受上下限约束
+    let capped = FeeConfig {
+        flat_fee: 1_000,
+        bps: 50,
+        min_fee: 2_000,
+        max_fee: 10_000,
+    };
+    assert_eq!(batch_transfer::calculate_fee(&capped, 100).unwrap(), 2_000);
+    assert_eq!(batch_transfer::calculate_fee(&capped, 1_000_000).unwrap(), 6_000);
+    assert_eq!(batch_transfer::calculate_fee(&capped, 100_000_000).unwrap(), 10_000);
+
+    // 大额转账不会在乘法中溢出
+    let full = FeeConfig {
+        bps: 10_000,
+        ..FeeConfig::default()
+    };
+    assert_eq!(batch_transfer::calculate_fee(&full, u64::MAX).unwrap(), u64::MAX);
+
+    // 固定手续费与基点手续费相加溢出
+    let overflow = FeeConfig {
+        flat_fee: 1,
+        bps: 10_000,
+        ..FeeConfig::default()
+    };
+    let result = batch_transfer::calculate_fee(&overflow, u64::MAX);
+    assert_eq!(result.unwrap_err(), ProgramError::InvalidArgument);
+}
+
+#[test]
+fn test_validate_fee_config() {
+    let valid = FeeConfig {
+        flat_fee: 0,
+        bps: 10_000,
+        min_fee: 100,
+        max_fee: 100,
+    };
+    assert!(batch_transfer::validate_fee_config(&valid).is_ok());
+
+    // 基点超过100%
+    let too_many_bps = FeeConfig {
+        bps: 10_001,
+        ..FeeConfig::default()
+    };
+    assert!(batch_transfer::validate_fee_config(&too_many_bps).is_err());
+
+    // 下限高于上限
+    let inverted = FeeConfig {
+        min_fee: 200,
+        max_fee: 100,
+        ..FeeConfig::default()
+    };
+    assert!(batch_transfer::validate_fee_config(&inverted).is_err());
+
+    // 上限为0表示不设上限
+    let uncapped = FeeConfig {
+        min_fee: 200,
+        max_fee: 0,
+        ..FeeConfig::default()
+    };
+    assert!(batch_transfer::validate_fee_config(&uncapped).is_ok());
+}
+
+#[test]
+fn test_bypasses_config_delay() {
+    let current = BankConfig {
+        fee_config: FeeConfig {
+            flat_fee: 1_000,
+            bps: 100,
+            min_fee: 500,
+            max_fee: 10_000,
+        },
+        config_delay: 3_600,
+        allow_fee_decrease_bypass: true,
+    };
+    let with_fee = |fee_config: FeeConfig| BankConfig { fee_config, ..current };
+
+    // 各项都不高于当前配置，或配置不变
+    assert!(batch_transfer::bypasses_config_delay(&current, &current));
+    let lower = FeeConfig {
+        flat_fee: 0,
+        bps: 50,
+        min_fee: 0,
+        max_fee: 5_000,
+    };
+    assert!(batch_transfer::bypasses_config_delay(&current, &with_fee(lower)));
+
+    // 任何一项提高都需要等待延迟
+    for higher in [
+        FeeConfig { flat_fee: 1_001, ..current.fee_config },
+        FeeConfig { bps: 101, ..current.fee_config },
+        FeeConfig { min_fee: 501, ..current.fee_config },
+        FeeConfig { max_fee: 10_001, ..current.fee_config },
+        // 上限改为0即取消上限，同样视为提高
+        FeeConfig { max_fee: 0, ..current.fee_config },
+    ] {
+        assert!(!batch_transfer::bypasses_config_delay(&current, &with_fee(higher)));
+    }
+
+    // 当前不设上限时，设置任意上限都视为降低
+    let uncapped = BankConfig {
+        fee_config: FeeConfig { max_fee: 0, ..current.fee_config },
+        ..current
+    };
+    assert!(batch_transfer::bypasses_config_delay(&uncapped, &current));
+
+    // 缩短延迟需要等待延迟，延长延迟不需要
+    assert!(!batch_transfer::bypasses_config_delay(&current, &BankConfig { config_delay: 0, ..current }));
+    assert!(batch_transfer::bypasses_config_delay(&current, &BankConfig { config_delay: 7_200, ..current }));
+
+    // 未开启降费跳过延迟时，降低手续费也需要等待延迟；开启需要等待延迟，关闭不需要
+    let strict = BankConfig {
+        allow_fee_decrease_bypass: false,
+        ..current
+    };
+    assert!(!batch_transfer::bypasses_config_delay(&strict, &strict));
+    assert!(!batch_transfer::bypasses_config_delay(&strict, &with_fee(lower)));
+    assert!(batch_transfer::bypasses_config_delay(&current, &strict));
+}
+
 #[test]
 fn test_safe_sum_transfer_info() {
     // 创建测试数据
@@ -61,7 +223,171 @@ fn test_safe_sum_transfer_info() {
     assert_eq!(result.unwrap_err(), ProgramError::InvalidArgument);
 }
 
+#[test]
+fn test_apply_duplicate_policy() {
+    let (a, b, c) = {
+        let mut keys = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        keys.sort();
+        (keys[0], keys[1], keys[2])
+    };
+    let transfer = |recipient: Pubkey, amount: u64| TransferInfo { recipient, amount };
+    let summarize = |transfers: Vec<TransferInfo>| -> Vec<(Pubkey, u64)> {
+        transfers.iter().map(|t| (t.recipient, t.amount)).collect()
+    };
+    let error_code = |result: anchor_client::anchor_lang::Result<Vec<TransferInfo>>| match result {
+        Err(anchor_client::anchor_lang::error::Error::AnchorError(error)) => error.error_code_number,
+        _ => panic!("应返回 AnchorError"),
+    };
+
+    // Allow 不检查顺序和重复
+    let transfers = vec![transfer(b, 1), transfer(a, 2), transfer(b, 3)];
+    let result = batch_transfer::apply_duplicate_policy(transfers, DuplicatePolicy::Allow).unwrap();
+    assert_eq!(summarize(result), vec![(b, 1), (a, 2), (b, 3)]);
+
+    // Reject 接受严格升序的列表，拒绝重复和未排序的列表
+    let transfers = vec![transfer(a, 1), transfer(b, 2), transfer(c, 3)];
+    let result = batch_transfer::apply_duplicate_policy(transfers, DuplicatePolicy::Reject).unwrap();
+    assert_eq!(summarize(result), vec![(a, 1), (b, 2), (c, 3)]);
+    let transfers = vec![transfer(a, 1), transfer(b, 2), transfer(b, 3)];
+    let result = batch_transfer::apply_duplicate_policy(transfers, DuplicatePolicy::Reject);
+    assert_eq!(error_code(result), u32::from(ErrorCode::DuplicateRecipient));
+    let transfers = vec![transfer(b, 1), transfer(a, 2)];
+    let result = batch_transfer::apply_duplicate_policy(transfers, DuplicatePolicy::Reject);
+    assert_eq!(error_code(result), u32::from(ErrorCode::TransfersNotSorted));
+
+    // Merge 合并相邻的重复接收者，仍要求升序
+    let transfers = vec![transfer(a, 1), transfer(a, 2), transfer(b, 3), transfer(c, 4), transfer(c, 5)];
+    let result = batch_transfer::apply_duplicate_policy(transfers, DuplicatePolicy::Merge).unwrap();
+    assert_eq!(summarize(result), vec![(a, 3), (b, 3), (c, 9)]);
+    let transfers = vec![transfer(a, 1), transfer(b, 2), transfer(a, 3)];
+    let result = batch_transfer::apply_duplicate_policy(transfers, DuplicatePolicy::Merge);
+    assert_eq!(error_code(result), u32::from(ErrorCode::TransfersNotSorted));
+
+    // 合并后的金额溢出
+    let transfers = vec![transfer(a, u64::MAX), transfer(a, 1)];
+    assert!(batch_transfer::apply_duplicate_policy(transfers, DuplicatePolicy::Merge).is_err());
+}
+
+#[test]
+fn test_split_by_weight() {
+    let split = |weight: u64| SplitInfo {
+        recipient: Pubkey::new_unique(),
+        weight,
+    };
+    let amounts = |total: u64, splits: &[SplitInfo]| -> Vec<u64> {
+        batch_transfer::split_by_weight(total, splits)
+            .unwrap()
+            .iter()
+            .map(|transfer| transfer.amount)
+            .collect()
+    };
+
+    // 整除时按比例分配
+    assert_eq!(amounts(600, &[split(1), split(2), split(3)]), vec![100, 200, 300]);
+
+    // 10 按 1:1:1 拆分，零头 1 分给序号最小的接收者
+    assert_eq!(amounts(10, &[split(1), split(1), split(1)]), vec![4, 3, 3]);
+
+    // 100 按 1:2:3:4:5 拆分，余数最大的接收者先分得零头
+    let result = amounts(100, &[split(1), split(2), split(3), split(4), split(5)]);
+    assert_eq!(result, vec![7, 13, 20, 27, 33]);
+    assert_eq!(result.iter().sum::<u64>(), 100);
+
+    // 权重为 0 的接收者分得 0
+    assert_eq!(amounts(7, &[split(0), split(1)]), vec![0, 7]);
+
+    // 大额总数与权重不溢出，且总和不变
+    let result = amounts(u64::MAX, &[split(u64::MAX / 3), split(u64::MAX / 3), split(u64::MAX / 3)]);
+    assert_eq!(result.iter().map(|amount| *amount as u128).sum::<u128>(), u64::MAX as u128);
+
+    // 权重总和为 0、权重总和溢出或列表为空
+    assert!(batch_transfer::split_by_weight(100, &[split(0), split(0)]).is_err());
+    assert!(batch_transfer::split_by_weight(100, &[split(u64::MAX), split(1)]).is_err());
+    assert!(batch_transfer::split_by_weight(100, &[]).is_err());
+}
+
+#[test]
+fn test_operator_allowance() {
+    const HOUR: i64 = 3_600;
+    let mut allowance = OperatorAllowance::new(OperatorLimit {
+        mint: None,
+        max_per_batch: 400,
+        daily_cap: 1_000,
+    });
+    let start = 1_700_000_000 / HOUR * HOUR;
+    let error_code = |result: Result<(), ErrorCode>| result.err().map(u32::from);
+
+    // 超过单批上限
+    assert_eq!(error_code(allowance.consume(401, start)), Some(u32::from(ErrorCode::OperatorBatchLimitExceeded)));
+
+    // 24小时内累计不超过上限
+    assert!(allowance.consume(400, start).is_ok());
+    assert!(allowance.consume(400, start + 10 * HOUR).is_ok());
+    assert_eq!(allowance.spent_in_window(start + 10 * HOUR), 800);
+    assert_eq!(error_code(allowance.consume(201, start + 23 * HOUR)), Some(u32::from(ErrorCode::OperatorDailyCapExceeded)));
+    assert!(allowance.consume(200, start + 23 * HOUR).is_ok());
+
+    // 窗口滚动：第一笔滑出窗口后释放对应额度，其余仍计入
+    assert_eq!(allowance.spent_in_window(start + 24 * HOUR), 600);
+    assert_eq!(error_code(allowance.consume(401, start + 24 * HOUR)), Some(u32::from(ErrorCode::OperatorBatchLimitExceeded)));
+    assert!(allowance.consume(400, start + 24 * HOUR).is_ok());
+    assert_eq!(error_code(allowance.consume(1, start + 24 * HOUR)), Some(u32::from(ErrorCode::OperatorDailyCapExceeded)));
+
+    // 长时间未使用后窗口清零
+    assert_eq!(allowance.spent_in_window(start + 100 * HOUR), 0);
+    assert!(allowance.consume(400, start + 100 * HOUR).is_ok());
+    assert_eq!(allowance.spent_in_window(start + 100 * HOUR + HOUR - 1), 400);
+}
+
+#[test]
+fn test_operator_allowance_bucket_boundary() {
+    const HOUR: i64 = 3_600;
+    let mut allowance = OperatorAllowance::new(OperatorLimit {
+        mint: None,
+        max_per_batch: 1_000,
+        daily_cap: 1_000,
+    });
+    let start = 1_700_000_000 / HOUR * HOUR;
+    let error_code = |result: Result<(), ErrorCode>| result.err().map(u32::from);
+    let cap_exceeded = Some(u32::from(ErrorCode::OperatorDailyCapExceeded));
+
+    // 整点前最后一秒转出，记入该小时的分桶，23小时零1秒后随分桶滑出窗口
+    assert!(allowance.consume(1_000, start + HOUR - 1).is_ok());
+    assert_eq!(allowance.spent_in_window(start + 24 * HOUR - 1), 1_000);
+    assert_eq!(error_code(allowance.consume(1, start + 24 * HOUR - 1)), cap_exceeded);
+    assert_eq!(allowance.spent_in_window(start + 24 * HOUR), 0);
+
+    // 整点第一秒转出，记入新的分桶，满24小时后才滑出窗口
+    let after_boundary = start + 24 * HOUR;
+    assert!(allowance.consume(1_000, after_boundary).is_ok());
+    assert_eq!(error_code(allowance.consume(1, after_boundary + 24 * HOUR - 1)), cap_exceeded);
+    assert!(allowance.consume(1_000, after_boundary + 24 * HOUR).is_ok());
+}
+
+#[test]
+fn test_validate_operator_limits() {
+    let mint = Pubkey::new_unique();
+    let limit = |mint: Option<Pubkey>, max_per_batch: u64, daily_cap: u64| OperatorLimit {
+        mint,
+        max_per_batch,
+        daily_cap,
+    };
+
+    assert!(batch_transfer::validate_operator_limits(&[limit(None, 1, 1), limit(Some(mint), 10, 100)]).is_ok());
+
+    // 空列表、单批上限为0、单批上限高于24小时上限、同一资产重复登记、条数过多
+    assert!(batch_transfer::validate_operator_limits(&[]).is_err());
+    assert!(batch_transfer::validate_operator_limits(&[limit(None, 0, 100)]).is_err());
+    assert!(batch_transfer::validate_operator_limits(&[limit(None, 101, 100)]).is_err());
+    assert!(batch_transfer::validate_operator_limits(&[limit(Some(mint), 1, 1), limit(Some(mint), 2, 2)]).is_err());
+    let too_many: Vec<OperatorLimit> = (0..=batch_transfer::MAX_OPERATOR_LIMITS)
+        .map(|_| limit(Some(Pubkey::new_unique()), 1, 1))
+        .collect();
+    assert!(batch_transfer::validate_operator_limits(&too_many).is_err());
+}
+
 // 获取测试程序和支付者
+#[allow(dead_code)]
 pub fn get_test_program() -> (Program<Rc<Keypair>>, Rc<Keypair>) {
     // 程序ID
     let program_id = batch_transfer::ID;
@@ -87,13 +413,110 @@ pub fn get_bank_account(program_id: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[b"bank_account"], program_id)
 }
 
+// 获取代币手续费配置的PDA
+#[allow(dead_code)]
+pub fn get_mint_fee_config(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"mint_fee_config", mint.as_ref()], program_id).0
+}
+
+// 获取代币手续费金库的PDA
+#[allow(dead_code)]
+pub fn get_fee_vault(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"fee_vault", mint.as_ref()], program_id).0
+}
+
+// 获取Merkle空投分发器的PDA
+#[allow(dead_code)]
+pub fn get_distributor(program_id: &Pubkey, creator: &Pubkey, mint: &Pubkey, merkle_root: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"distributor", creator.as_ref(), mint.as_ref(), merkle_root.as_ref()],
+        program_id,
+    )
+    .0
+}
+
+// 获取分发器金库的PDA
+#[allow(dead_code)]
+pub fn get_distributor_vault(program_id: &Pubkey, distributor: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"distributor_vault", distributor.as_ref()], program_id).0
+}
+
+// 获取序号所在领取位图分块的PDA
+#[allow(dead_code)]
+pub fn get_claim_bitmap(program_id: &Pubkey, distributor: &Pubkey, index: u64) -> Pubkey {
+    let chunk = batch_transfer::claim_bitmap_chunk(index).unwrap();
+    Pubkey::find_program_address(&[b"claim_bitmap", distributor.as_ref(), &chunk.to_le_bytes()], program_id).0
+}
+
+// 获取锁仓账户的PDA
+#[allow(dead_code)]
+pub fn get_vesting(program_id: &Pubkey, creator: &Pubkey, mint: &Pubkey, recipient: &Pubkey, start: i64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"vesting", creator.as_ref(), mint.as_ref(), recipient.as_ref(), &start.to_le_bytes()],
+        program_id,
+    )
+    .0
+}
+
+// 获取批次回执的PDA
+#[allow(dead_code)]
+pub fn get_batch_receipt(program_id: &Pubkey, sender: &Pubkey, batch_id: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[b"batch_receipt", sender.as_ref(), batch_id.as_ref()], program_id).0
+}
+
+// 获取分发账户的PDA
+#[allow(dead_code)]
+pub fn get_distribution(program_id: &Pubkey, creator: &Pubkey, distribution_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"distribution", creator.as_ref(), &distribution_id.to_le_bytes()],
+        program_id,
+    )
+    .0
+}
+
+// 获取分发金库的PDA
+#[allow(dead_code)]
+pub fn get_distribution_vault(program_id: &Pubkey, distribution: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"distribution_vault", distribution.as_ref()], program_id).0
+}
+
+// 获取资金库的PDA
+#[allow(dead_code)]
+pub fn get_vault(program_id: &Pubkey, owner: &Pubkey, vault_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", owner.as_ref(), &vault_id.to_le_bytes()], program_id).0
+}
+
+// 获取资金库某个代币的token账户PDA
+#[allow(dead_code)]
+pub fn get_vault_token_account(program_id: &Pubkey, vault: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault_token", vault.as_ref(), mint.as_ref()], program_id).0
+}
+
+// 获取资金库操作员的PDA
+#[allow(dead_code)]
+pub fn get_vault_operator(program_id: &Pubkey, vault: &Pubkey, operator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault_operator", vault.as_ref(), operator.as_ref()], program_id).0
+}
+
+// 获取程序的 ProgramData 账户地址
+#[allow(dead_code)]
+pub fn get_program_data(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
 // 辅助函数：用于创建和初始化测试环境
 #[cfg(test)]
 pub mod test_utils {
     use super::*;
     use batch_transfer::accounts as batch_accounts;
     use batch_transfer::instruction as batch_instructions;
-    use batch_transfer::TransferInfo;
+    use batch_transfer::{BankAccount, BankConfig, DuplicatePolicy, FeeConfig, TransferInfo};
+    use anchor_client::solana_sdk::{
+        account::from_account,
+        clock::Clock,
+        signature::Signature,
+        sysvar,
+    };
     
     // 初始化银行账户
     #[allow(dead_code)]
@@ -108,6 +531,7 @@ pub mod test_utils {
             .accounts(batch_accounts::Initialize {
                 bank_account,
                 deployer: admin.pubkey(),
+                program_data: get_program_data(&program.id()),
                 system_program: system_program::ID,
             })
             .args(batch_instructions::Initialize {
@@ -120,28 +544,59 @@ pub mod test_utils {
         Ok(())
     }
     
-    // 设置费用
-    #[allow(dead_code)]
-    pub async fn set_fee(
+    // 提交手续费配置变更，保持当前的变更延迟和降费设置；可以立即生效时在同一笔交易中执行，
+    // 否则只提交，需在 当前时间 + config_delay 之后再执行
+    fn send_fee_config_change(
         program: &Program<Rc<Keypair>>,
         admin: &Keypair,
-        fee: u64,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+        fee_config: FeeConfig,
+    ) -> Result<Signature, Box<dyn std::error::Error>> {
         let (bank_account, _) = get_bank_account(&program.id());
-        
-        let sig = program
+        let bank: BankAccount = program.account(bank_account)?;
+        let clock_account = program.rpc().get_account(&sysvar::clock::ID)?;
+        let clock: Clock = from_account(&clock_account).ok_or("无法解析Clock")?;
+        let new_config = BankConfig {
+            fee_config,
+            ..bank.config()
+        };
+        let bypasses_delay = batch_transfer::bypasses_config_delay(&bank.config(), &new_config);
+        let eta = if bypasses_delay || bank.config_delay == 0 {
+            clock.unix_timestamp
+        } else {
+            clock.unix_timestamp + bank.config_delay
+        };
+
+        let request = program
             .request()
-            .accounts(batch_accounts::SetFee {
+            .accounts(batch_accounts::ConfigChange {
                 bank_account,
                 admin: admin.pubkey(),
             })
-            .args(batch_instructions::SetFee {
-                fee,
-            })
+            .args(batch_instructions::QueueConfigChange { new_config, eta });
+        if eta > clock.unix_timestamp {
+            return Ok(request.signer(admin).send()?);
+        }
+        let queue_ix = request.instructions()?.remove(0);
+        let sig = program
+            .request()
+            .instruction(queue_ix)
+            .accounts(batch_accounts::ExecuteConfigChange { bank_account })
+            .args(batch_instructions::ExecuteConfigChange {})
             .signer(admin)
             .send()?;
+        Ok(sig)
+    }
+
+    // 设置手续费配置
+    #[allow(dead_code)]
+    pub async fn set_fee_config(
+        program: &Program<Rc<Keypair>>,
+        admin: &Keypair,
+        fee_config: FeeConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let sig = send_fee_config_change(program, admin, fee_config)?;
             
-        println!("Fee set to {}: {}", fee, sig);
+        println!("Fee config set to {:?}: {}", fee_config, sig);
         Ok(())
     }
     
@@ -152,6 +607,7 @@ pub mod test_utils {
         sender: &Keypair,
         recipients: &[Pubkey],
         amounts: &[u64],
+        max_fee: u64,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let (bank_account, _) = get_bank_account(&program.id());
         
@@ -170,6 +626,8 @@ pub mod test_utils {
             AccountMeta::new(sender.pubkey(), true),  // sender - 签名者且可变
             AccountMeta::new(bank_account, false),    // bank_account
             AccountMeta::new_readonly(system_program::ID, false), // system_program
+            AccountMeta::new_readonly(batch_transfer::ID, false), // batch_receipt - 未提供批次ID时以程序ID占位
+            AccountMeta::new_readonly(batch_transfer::ID, false), // fee_payer - 由发送者支付手续费时以程序ID占位
         ];
         
         // 添加所有收款人账户
@@ -182,6 +640,10 @@ pub mod test_utils {
             .accounts(accounts)
             .args(batch_instructions::BatchTransferSol {
                 transfers,
+                skip_invalid: false,
+                duplicate_policy: DuplicatePolicy::Allow,
+                batch_id: None,
+                max_fee,
             })
             .signer(sender)
             .send()?;
@@ -280,6 +742,7 @@ pub mod test_utils {
             .accounts(batch_accounts::Initialize {
                 bank_account,
                 deployer: admin.pubkey(),
+                program_data: get_program_data(&program.id()),
                 system_program: system_program::ID,
             })
             .args(batch_instructions::Initialize {
@@ -293,29 +756,1677 @@ pub mod test_utils {
         Ok(())
     }
     
-    // 设置费用（阻塞版）
+    // 设置手续费配置（阻塞版）
     #[allow(dead_code)]
-    pub fn set_fee_blocking(
+    pub fn set_fee_config_blocking(
         program: &Program<Rc<Keypair>>,
         admin: &Keypair,
-        fee: u64,
+        fee_config: FeeConfig,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let (bank_account, _) = get_bank_account(&program.id());
-        
-        let sig = program
-            .request()
-            .accounts(batch_accounts::SetFee {
-                bank_account,
-                admin: admin.pubkey(),
-            })
-            .args(batch_instructions::SetFee {
-                fee,
-            })
-            .signer(admin)
-            .send()?;
+        let sig = send_fee_config_change(program, admin, fee_config)?;
             
-        println!("Fee set to {}: {}", fee, sig);
+        println!("Fee config set to {:?}: {}", fee_config, sig);
         std::thread::sleep(std::time::Duration::from_millis(500));
         Ok(())
     }
-} 
\ No newline at end of file
+} 
+// 辅助函数：基于 solana-program-test 的进程内测试环境，不依赖本地验证节点
+#[cfg(test)]
+pub mod program_test_utils {
+    use super::*;
+    use anchor_client::anchor_lang::{
+        prelude::AccountInfo,
+        InstructionData,
+        ToAccountMetas,
+    };
+    use anchor_client::solana_sdk::{
+        account::Account,
+        bpf_loader_upgradeable::UpgradeableLoaderState,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        native_token::LAMPORTS_PER_SOL,
+        instruction::{Instruction, InstructionError},
+        program_pack::Pack,
+        system_instruction,
+        transaction::{Transaction, TransactionError},
+    };
+    use anchor_spl::associated_token::{get_associated_token_address_with_program_id, spl_associated_token_account};
+    use anchor_spl::token::spl_token;
+    use anchor_spl::token_2022::spl_token_2022;
+    use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+    use batch_transfer::accounts as batch_accounts;
+    use batch_transfer::instruction as batch_instructions;
+    use batch_transfer::{
+        BankAccount, BankConfig, DuplicatePolicy, ErrorCode, FeeConfig, OperatorLimit, SplitInfo, VestingInfo,
+    };
+    use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+
+    // anchor 生成的入口函数要求 accounts 与 AccountInfo 生命周期一致，这里包装成 processor! 需要的签名
+    fn process_instruction<'a, 'info>(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+        data: &[u8],
+    ) -> ProgramResult {
+        // SAFETY: 'info 比 'a 长，入口函数只在本次调用期间使用 accounts，缩短 AccountInfo 的生命周期不会让引用越过其有效期
+        let accounts = unsafe { std::mem::transmute::<&'a [AccountInfo<'info>], &'a [AccountInfo<'a>]>(accounts) };
+        batch_transfer::entry(program_id, accounts, data)
+    }
+
+    // 创建加载了本程序的测试环境
+    #[allow(dead_code)]
+    pub fn program_test() -> ProgramTest {
+        ProgramTest::new(
+            "batch_transfer",
+            batch_transfer::ID,
+            processor!(process_instruction),
+        )
+    }
+
+    // 启动测试环境
+    #[allow(dead_code)]
+    pub async fn start() -> ProgramTestContext {
+        program_test().start_with_context().await
+    }
+
+    // 给测试账户预置SOL
+    #[allow(dead_code)]
+    pub fn add_funded_account(program_test: &mut ProgramTest, pubkey: &Pubkey, lamports: u64) {
+        program_test.add_account(
+            *pubkey,
+            Account {
+                lamports,
+                owner: system_program::ID,
+                ..Account::default()
+            },
+        );
+    }
+
+    // 写入程序的 ProgramData 账户，模拟可升级部署下的升级权限
+    #[allow(dead_code)]
+    pub fn set_program_data(context: &mut ProgramTestContext, upgrade_authority: Option<Pubkey>) {
+        let state = UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: upgrade_authority,
+        };
+        let account = Account::new_data(LAMPORTS_PER_SOL, &state, &bpf_loader_upgradeable::ID)
+            .expect("序列化 ProgramData 失败");
+        context.set_account(&get_program_data(&batch_transfer::ID), &account.into());
+    }
+
+    // 发送交易，payer 支付交易费；每次使用新的 blockhash，避免相同交易被去重
+    #[allow(dead_code)]
+    pub async fn send(
+        context: &mut ProgramTestContext,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let blockhash = context.get_new_latest_blockhash().await?;
+        let mut all_signers: Vec<&Keypair> = vec![&context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        context.banks_client.process_transaction(transaction).await
+    }
+
+    // 发送交易并返回执行结果和程序日志
+    #[allow(dead_code)]
+    pub async fn send_with_logs(
+        context: &mut ProgramTestContext,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> (Result<(), TransactionError>, Vec<String>) {
+        let blockhash = context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers: Vec<&Keypair> = vec![&context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        let result = context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .unwrap();
+        let logs = result.metadata.map(|metadata| metadata.log_messages).unwrap_or_default();
+        (result.result, logs)
+    }
+
+    // 断言交易因指定的程序错误失败
+    #[allow(dead_code)]
+    pub fn assert_program_error(result: Result<(), BanksClientError>, error: ErrorCode) {
+        match result {
+            Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+                _,
+                InstructionError::Custom(code),
+            ))) => assert_eq!(code, u32::from(error), "返回了错误的错误码"),
+            other => panic!("交易应该返回 {:?}，实际结果: {:?}", error, other),
+        }
+    }
+
+    // 获取账户余额
+    #[allow(dead_code)]
+    pub async fn get_balance(context: &mut ProgramTestContext, pubkey: &Pubkey) -> u64 {
+        context.banks_client.get_balance(*pubkey).await.unwrap()
+    }
+
+    // 获取账户当前数据长度对应的租金豁免最低余额
+    #[allow(dead_code)]
+    pub async fn get_rent_exempt_minimum(context: &mut ProgramTestContext, pubkey: &Pubkey) -> u64 {
+        let account = context
+            .banks_client
+            .get_account(*pubkey)
+            .await
+            .unwrap()
+            .expect("账户不存在");
+        let rent = context.banks_client.get_rent().await.unwrap();
+        rent.minimum_balance(account.data.len())
+    }
+
+    // 获取并反序列化程序账户数据
+    #[allow(dead_code)]
+    pub async fn get_account_data<T: AccountDeserialize>(
+        context: &mut ProgramTestContext,
+        pubkey: &Pubkey,
+    ) -> T {
+        let account = context
+            .banks_client
+            .get_account(*pubkey)
+            .await
+            .unwrap()
+            .expect("账户不存在");
+        T::try_deserialize(&mut account.data.as_ref()).unwrap()
+    }
+
+    // 获取银行账户数据
+    #[allow(dead_code)]
+    pub async fn get_bank_account_data(context: &mut ProgramTestContext) -> BankAccount {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        get_account_data(context, &bank_account).await
+    }
+
+    // 初始化指令
+    #[allow(dead_code)]
+    pub fn initialize_ix(admin: &Pubkey) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::Initialize {
+                bank_account,
+                deployer: *admin,
+                program_data: get_program_data(&batch_transfer::ID),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::Initialize { admin: *admin }.data(),
+        }
+    }
+
+    // 提交配置变更指令
+    #[allow(dead_code)]
+    pub fn queue_config_change_ix(admin: &Pubkey, new_config: BankConfig, eta: i64) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::ConfigChange {
+                bank_account,
+                admin: *admin,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::QueueConfigChange { new_config, eta }.data(),
+        }
+    }
+
+    // 执行配置变更指令
+    #[allow(dead_code)]
+    pub fn execute_config_change_ix() -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::ExecuteConfigChange { bank_account }.to_account_metas(None),
+            data: batch_instructions::ExecuteConfigChange {}.data(),
+        }
+    }
+
+    // 取消配置变更指令
+    #[allow(dead_code)]
+    pub fn cancel_config_change_ix(admin: &Pubkey) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::ConfigChange {
+                bank_account,
+                admin: *admin,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::CancelConfigChange {}.data(),
+        }
+    }
+
+    // 在同一笔交易中提交并执行手续费配置变更，保持当前的变更延迟；
+    // 只在延迟为0或新配置只降低手续费时能立即生效
+    #[allow(dead_code)]
+    pub async fn set_fee_config(
+        context: &mut ProgramTestContext,
+        admin: &Keypair,
+        fee_config: FeeConfig,
+    ) -> Result<(), BanksClientError> {
+        let new_config = BankConfig {
+            fee_config,
+            ..get_bank_account_data(context).await.config()
+        };
+        let eta = get_unix_timestamp(context).await;
+        send(
+            context,
+            &[queue_config_change_ix(&admin.pubkey(), new_config, eta), execute_config_change_ix()],
+            &[admin],
+        )
+        .await
+    }
+
+    // 批量转账的可选参数，默认不跳过无效转账、不检查重复接收者、不带批次ID、由发送者支付手续费、不限制手续费上限
+    #[derive(Clone, Copy, Default)]
+    pub struct BatchOptions {
+        pub skip_invalid: bool,
+        pub duplicate_policy: DuplicatePolicy,
+        pub batch_id: Option<[u8; 32]>,
+        pub fee_payer: Option<Pubkey>,
+        pub max_fee: Option<u64>,
+    }
+
+    // 按重复接收者策略生成接收者账户，Merge 时相邻的重复接收者只传一次
+    fn recipient_metas(transfers: &[TransferInfo], options: BatchOptions) -> Vec<AccountMeta> {
+        let mut metas: Vec<AccountMeta> = Vec::with_capacity(transfers.len());
+        for transfer in transfers {
+            if options.duplicate_policy == DuplicatePolicy::Merge
+                && metas.last().is_some_and(|meta| meta.pubkey == transfer.recipient)
+            {
+                continue;
+            }
+            metas.push(AccountMeta::new(transfer.recipient, false));
+        }
+        metas
+    }
+
+    // 批量转账SOL指令
+    #[allow(dead_code)]
+    pub fn batch_transfer_sol_ix(sender: &Pubkey, transfers: Vec<TransferInfo>) -> Instruction {
+        batch_transfer_sol_with_options_ix(sender, transfers, BatchOptions::default())
+    }
+
+    // 批量转账SOL指令，跳过校验失败的转账
+    #[allow(dead_code)]
+    pub fn batch_transfer_sol_skip_invalid_ix(sender: &Pubkey, transfers: Vec<TransferInfo>) -> Instruction {
+        let options = BatchOptions {
+            skip_invalid: true,
+            ..BatchOptions::default()
+        };
+        batch_transfer_sol_with_options_ix(sender, transfers, options)
+    }
+
+    // 批量转账SOL指令，指定可选参数
+    #[allow(dead_code)]
+    pub fn batch_transfer_sol_with_options_ix(
+        sender: &Pubkey,
+        transfers: Vec<TransferInfo>,
+        options: BatchOptions,
+    ) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        let mut accounts = batch_accounts::BatchTransferSol {
+            sender: *sender,
+            bank_account,
+            system_program: system_program::ID,
+            batch_receipt: options
+                .batch_id
+                .map(|batch_id| get_batch_receipt(&batch_transfer::ID, sender, &batch_id)),
+            fee_payer: options.fee_payer,
+        }
+        .to_account_metas(None);
+        accounts.extend(recipient_metas(&transfers, options));
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts,
+            data: batch_instructions::BatchTransferSol {
+                transfers,
+                skip_invalid: options.skip_invalid,
+                duplicate_policy: options.duplicate_policy,
+                batch_id: options.batch_id,
+                max_fee: options.max_fee.unwrap_or(u64::MAX),
+            }
+            .data(),
+        }
+    }
+
+    // 等额批量转账SOL指令，remaining_accounts 为接收者
+    #[allow(dead_code)]
+    pub fn batch_transfer_sol_equal_ix(sender: &Pubkey, recipients: &[Pubkey], amount: u64) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        let mut accounts = batch_accounts::BatchTransferSol {
+            sender: *sender,
+            bank_account,
+            system_program: system_program::ID,
+            batch_receipt: None,
+            fee_payer: None,
+        }
+        .to_account_metas(None);
+        accounts.extend(recipients.iter().map(|recipient| AccountMeta::new(*recipient, false)));
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts,
+            data: batch_instructions::BatchTransferSolEqual {
+                amount,
+                batch_id: None,
+                max_fee: u64::MAX,
+            }.data(),
+        }
+    }
+
+    // 按权重拆分总额批量转账SOL指令
+    #[allow(dead_code)]
+    pub fn batch_split_sol_ix(sender: &Pubkey, total: u64, splits: Vec<SplitInfo>) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        let mut accounts = batch_accounts::BatchTransferSol {
+            sender: *sender,
+            bank_account,
+            system_program: system_program::ID,
+            batch_receipt: None,
+            fee_payer: None,
+        }
+        .to_account_metas(None);
+        accounts.extend(splits.iter().map(|split| AccountMeta::new(split.recipient, false)));
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts,
+            data: batch_instructions::BatchSplitSol {
+                total,
+                splits,
+                batch_id: None,
+                max_fee: u64::MAX,
+            }.data(),
+        }
+    }
+
+    // 关闭批次回执指令
+    #[allow(dead_code)]
+    pub fn close_batch_receipts_ix(sender: &Pubkey, receipts: &[Pubkey]) -> Instruction {
+        let mut accounts = batch_accounts::CloseBatchReceipts { sender: *sender }.to_account_metas(None);
+        accounts.extend(receipts.iter().map(|receipt| AccountMeta::new(*receipt, false)));
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts,
+            data: batch_instructions::CloseBatchReceipts {}.data(),
+        }
+    }
+
+    // 提取手续费指令
+    #[allow(dead_code)]
+    pub fn withdraw_fee_ix(admin: &Pubkey, destination: &Pubkey, amount: Option<u64>) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::WithdrawFee {
+                bank_account,
+                admin: *admin,
+                destination: *destination,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::WithdrawFee { amount }.data(),
+        }
+    }
+
+    // 提名新管理员指令
+    #[allow(dead_code)]
+    pub fn propose_admin_ix(admin: &Pubkey, new_admin: &Pubkey) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::ProposeAdmin {
+                bank_account,
+                admin: *admin,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::ProposeAdmin {
+                new_admin: *new_admin,
+            }
+            .data(),
+        }
+    }
+
+    // 接受管理员权限指令
+    #[allow(dead_code)]
+    pub fn accept_admin_ix(new_admin: &Pubkey) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::AcceptAdmin {
+                bank_account,
+                new_admin: *new_admin,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::AcceptAdmin {}.data(),
+        }
+    }
+
+    // 取消管理员提名指令
+    #[allow(dead_code)]
+    pub fn cancel_admin_transfer_ix(admin: &Pubkey) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::CancelAdminTransfer {
+                bank_account,
+                admin: *admin,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::CancelAdminTransfer {}.data(),
+        }
+    }
+
+    // 迁移银行账户布局指令
+    #[allow(dead_code)]
+    pub fn migrate_bank_account_ix(admin: &Pubkey) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::MigrateBankAccount {
+                bank_account,
+                admin: *admin,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::MigrateBankAccount {}.data(),
+        }
+    }
+
+    // 暂停合约指令
+    #[allow(dead_code)]
+    pub fn pause_ix(admin: &Pubkey) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::SetPaused {
+                bank_account,
+                admin: *admin,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::Pause {}.data(),
+        }
+    }
+
+    // 恢复合约指令
+    #[allow(dead_code)]
+    pub fn unpause_ix(admin: &Pubkey) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::SetPaused {
+                bank_account,
+                admin: *admin,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::Unpause {}.data(),
+        }
+    }
+
+    // 批量转账SPL Token指令，手续费以SOL收取
+    #[allow(dead_code)]
+    pub fn batch_transfer_token_ix(
+        sender: &Pubkey,
+        token_account: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        transfers: Vec<TransferInfo>,
+    ) -> Instruction {
+        batch_transfer_token_ix_with_fee_accounts(sender, token_account, mint, token_program, false, BatchOptions::default(), transfers)
+    }
+
+    // 批量转账SPL Token指令，手续费以SOL收取，跳过校验失败的转账
+    #[allow(dead_code)]
+    pub fn batch_transfer_token_skip_invalid_ix(
+        sender: &Pubkey,
+        token_account: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        transfers: Vec<TransferInfo>,
+    ) -> Instruction {
+        let options = BatchOptions {
+            skip_invalid: true,
+            ..BatchOptions::default()
+        };
+        batch_transfer_token_ix_with_fee_accounts(sender, token_account, mint, token_program, false, options, transfers)
+    }
+
+    // 批量转账SPL Token指令，手续费以SOL收取，指定可选参数
+    #[allow(dead_code)]
+    pub fn batch_transfer_token_with_options_ix(
+        sender: &Pubkey,
+        token_account: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        transfers: Vec<TransferInfo>,
+        options: BatchOptions,
+    ) -> Instruction {
+        batch_transfer_token_ix_with_fee_accounts(sender, token_account, mint, token_program, false, options, transfers)
+    }
+
+    // 批量转账SPL Token指令，手续费以代币收取
+    #[allow(dead_code)]
+    pub fn batch_transfer_token_with_token_fee_ix(
+        sender: &Pubkey,
+        token_account: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        transfers: Vec<TransferInfo>,
+    ) -> Instruction {
+        batch_transfer_token_ix_with_fee_accounts(sender, token_account, mint, token_program, true, BatchOptions::default(), transfers)
+    }
+
+    fn batch_transfer_token_ix_with_fee_accounts(
+        sender: &Pubkey,
+        token_account: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        token_fee: bool,
+        options: BatchOptions,
+        transfers: Vec<TransferInfo>,
+    ) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        let mut accounts = batch_accounts::BatchTransferToken {
+            sender: *sender,
+            bank_account,
+            token_account: *token_account,
+            mint: *mint,
+            token_program: *token_program,
+            system_program: system_program::ID,
+            mint_fee_config: get_mint_fee_config(&batch_transfer::ID, mint),
+            fee_vault: token_fee.then(|| get_fee_vault(&batch_transfer::ID, mint)),
+            batch_receipt: options
+                .batch_id
+                .map(|batch_id| get_batch_receipt(&batch_transfer::ID, sender, &batch_id)),
+            fee_payer: options.fee_payer,
+        }
+        .to_account_metas(None);
+        accounts.extend(recipient_metas(&transfers, options));
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts,
+            data: batch_instructions::BatchTransferToken {
+                transfers,
+                skip_invalid: options.skip_invalid,
+                duplicate_policy: options.duplicate_policy,
+                batch_id: options.batch_id,
+                max_fee: options.max_fee.unwrap_or(u64::MAX),
+            }
+            .data(),
+        }
+    }
+
+    // 等额批量转账SPL Token指令，手续费以SOL收取，remaining_accounts 为接收者token账户
+    #[allow(dead_code)]
+    pub fn batch_transfer_token_equal_ix(
+        sender: &Pubkey,
+        token_account: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        recipients: &[Pubkey],
+        amount: u64,
+    ) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        let mut accounts = batch_accounts::BatchTransferToken {
+            sender: *sender,
+            bank_account,
+            token_account: *token_account,
+            mint: *mint,
+            token_program: *token_program,
+            system_program: system_program::ID,
+            mint_fee_config: get_mint_fee_config(&batch_transfer::ID, mint),
+            fee_vault: None,
+            batch_receipt: None,
+            fee_payer: None,
+        }
+        .to_account_metas(None);
+        accounts.extend(recipients.iter().map(|recipient| AccountMeta::new(*recipient, false)));
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts,
+            data: batch_instructions::BatchTransferTokenEqual {
+                amount,
+                batch_id: None,
+                max_fee: u64::MAX,
+            }.data(),
+        }
+    }
+
+    // 按权重拆分总额批量转账SPL Token指令，手续费以SOL收取
+    #[allow(dead_code)]
+    pub fn batch_split_token_ix(
+        sender: &Pubkey,
+        token_account: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        total: u64,
+        splits: Vec<SplitInfo>,
+    ) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        let mut accounts = batch_accounts::BatchTransferToken {
+            sender: *sender,
+            bank_account,
+            token_account: *token_account,
+            mint: *mint,
+            token_program: *token_program,
+            system_program: system_program::ID,
+            mint_fee_config: get_mint_fee_config(&batch_transfer::ID, mint),
+            fee_vault: None,
+            batch_receipt: None,
+            fee_payer: None,
+        }
+        .to_account_metas(None);
+        accounts.extend(splits.iter().map(|split| AccountMeta::new(split.recipient, false)));
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts,
+            data: batch_instructions::BatchSplitToken {
+                total,
+                splits,
+                batch_id: None,
+                max_fee: u64::MAX,
+            }.data(),
+        }
+    }
+
+    // 按钱包地址批量转账SPL Token指令，remaining_accounts 依次为 [接收者钱包, 接收者ATA]
+    #[allow(dead_code)]
+    pub fn batch_transfer_token_to_wallets_ix(
+        sender: &Pubkey,
+        token_account: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        token_fee: bool,
+        transfers: Vec<TransferInfo>,
+    ) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        let mut accounts = batch_accounts::BatchTransferTokenToWallets {
+            sender: *sender,
+            bank_account,
+            token_account: *token_account,
+            mint: *mint,
+            token_program: *token_program,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            mint_fee_config: get_mint_fee_config(&batch_transfer::ID, mint),
+            fee_vault: token_fee.then(|| get_fee_vault(&batch_transfer::ID, mint)),
+        }
+        .to_account_metas(None);
+        for transfer in &transfers {
+            accounts.push(AccountMeta::new_readonly(transfer.recipient, false));
+            accounts.push(AccountMeta::new(
+                get_associated_token_address_with_program_id(&transfer.recipient, mint, token_program),
+                false,
+            ));
+        }
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts,
+            data: batch_instructions::BatchTransferTokenToWallets {
+                transfers,
+                max_fee: u64::MAX,
+            }.data(),
+        }
+    }
+
+    // 提交代币手续费配置变更指令
+    #[allow(dead_code)]
+    pub fn queue_mint_fee_config_change_ix(
+        admin: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        fee_config: FeeConfig,
+        eta: i64,
+    ) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::QueueMintFeeConfigChange {
+                bank_account,
+                admin: *admin,
+                mint: *mint,
+                mint_fee_config: get_mint_fee_config(&batch_transfer::ID, mint),
+                fee_vault: get_fee_vault(&batch_transfer::ID, mint),
+                token_program: *token_program,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::QueueMintFeeConfigChange { fee_config, eta }.data(),
+        }
+    }
+
+    // 执行代币手续费配置变更指令
+    #[allow(dead_code)]
+    pub fn execute_mint_fee_config_change_ix(mint: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::ExecuteMintFeeConfigChange {
+                mint_fee_config: get_mint_fee_config(&batch_transfer::ID, mint),
+            }
+            .to_account_metas(None),
+            data: batch_instructions::ExecuteMintFeeConfigChange {}.data(),
+        }
+    }
+
+    // 取消代币手续费配置变更指令
+    #[allow(dead_code)]
+    pub fn cancel_mint_fee_config_change_ix(admin: &Pubkey, mint: &Pubkey) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::CancelMintFeeConfigChange {
+                bank_account,
+                admin: *admin,
+                mint_fee_config: get_mint_fee_config(&batch_transfer::ID, mint),
+            }
+            .to_account_metas(None),
+            data: batch_instructions::CancelMintFeeConfigChange {}.data(),
+        }
+    }
+
+    // 在同一笔交易中提交并执行代币手续费配置变更，只在变更延迟为0或新配置只降低手续费时能立即生效
+    #[allow(dead_code)]
+    pub async fn set_mint_fee_config(
+        context: &mut ProgramTestContext,
+        admin: &Keypair,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        fee_config: FeeConfig,
+    ) -> Result<(), BanksClientError> {
+        let eta = get_unix_timestamp(context).await;
+        send(
+            context,
+            &[
+                queue_mint_fee_config_change_ix(&admin.pubkey(), mint, token_program, fee_config, eta),
+                execute_mint_fee_config_change_ix(mint),
+            ],
+            &[admin],
+        )
+        .await
+    }
+
+    // 提取代币手续费指令
+    #[allow(dead_code)]
+    pub fn withdraw_token_fees_ix(
+        admin: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        destination: &Pubkey,
+        amount: Option<u64>,
+    ) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::WithdrawTokenFees {
+                bank_account,
+                admin: *admin,
+                mint: *mint,
+                fee_vault: get_fee_vault(&batch_transfer::ID, mint),
+                destination: *destination,
+                token_program: *token_program,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::WithdrawTokenFees { amount }.data(),
+        }
+    }
+
+    // 创建Merkle空投分发器指令
+    #[allow(dead_code)]
+    pub fn create_distributor_ix(
+        creator: &Pubkey,
+        creator_token_account: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        merkle_root: [u8; 32],
+        total: u64,
+        expiry: i64,
+    ) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        let distributor = get_distributor(&batch_transfer::ID, creator, mint, &merkle_root);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::CreateDistributor {
+                creator: *creator,
+                bank_account,
+                distributor,
+                vault: get_distributor_vault(&batch_transfer::ID, &distributor),
+                mint: *mint,
+                creator_token_account: *creator_token_account,
+                token_program: *token_program,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::CreateDistributor {
+                merkle_root,
+                total,
+                expiry,
+                max_fee: u64::MAX,
+            }
+            .data(),
+        }
+    }
+
+    // 领取Merkle空投指令
+    #[allow(dead_code, clippy::too_many_arguments)]
+    pub fn claim_ix(
+        claimant: &Pubkey,
+        distributor: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        claimant_token_account: &Pubkey,
+        index: u64,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Instruction {
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::Claim {
+                claimant: *claimant,
+                distributor: *distributor,
+                claim_bitmap: get_claim_bitmap(&batch_transfer::ID, distributor, index),
+                vault: get_distributor_vault(&batch_transfer::ID, distributor),
+                mint: *mint,
+                claimant_token_account: *claimant_token_account,
+                token_program: *token_program,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::Claim { index, amount, proof }.data(),
+        }
+    }
+
+    // 收回Merkle空投剩余代币指令
+    #[allow(dead_code)]
+    pub fn clawback_ix(
+        creator: &Pubkey,
+        distributor: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        destination: &Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::Clawback {
+                creator: *creator,
+                distributor: *distributor,
+                vault: get_distributor_vault(&batch_transfer::ID, distributor),
+                mint: *mint,
+                destination: *destination,
+                token_program: *token_program,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::Clawback {}.data(),
+        }
+    }
+
+    // 批量创建锁仓指令，remaining_accounts 依次为每个锁仓的 [锁仓PDA, 锁仓ATA]
+    #[allow(dead_code)]
+    pub fn batch_create_vesting_ix(
+        sender: &Pubkey,
+        token_account: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        schedules: Vec<VestingInfo>,
+    ) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        let mut accounts = batch_accounts::BatchCreateVesting {
+            sender: *sender,
+            bank_account,
+            token_account: *token_account,
+            mint: *mint,
+            token_program: *token_program,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None);
+        for schedule in &schedules {
+            let vesting = get_vesting(&batch_transfer::ID, sender, mint, &schedule.recipient, schedule.start);
+            accounts.push(AccountMeta::new(vesting, false));
+            accounts.push(AccountMeta::new(
+                get_associated_token_address_with_program_id(&vesting, mint, token_program),
+                false,
+            ));
+        }
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts,
+            data: batch_instructions::BatchCreateVesting {
+                schedules,
+                max_fee: u64::MAX,
+            }
+            .data(),
+        }
+    }
+
+    // 提取已释放锁仓代币指令
+    #[allow(dead_code)]
+    pub fn withdraw_vested_ix(
+        recipient: &Pubkey,
+        vesting: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        recipient_token_account: &Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::WithdrawVested {
+                recipient: *recipient,
+                vesting: *vesting,
+                vault: get_associated_token_address_with_program_id(vesting, mint, token_program),
+                mint: *mint,
+                recipient_token_account: *recipient_token_account,
+                token_program: *token_program,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::WithdrawVested {}.data(),
+        }
+    }
+
+    // 撤销锁仓指令
+    #[allow(dead_code)]
+    pub fn revoke_vesting_ix(
+        creator: &Pubkey,
+        vesting: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        destination: &Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::RevokeVesting {
+                creator: *creator,
+                vesting: *vesting,
+                vault: get_associated_token_address_with_program_id(vesting, mint, token_program),
+                mint: *mint,
+                destination: *destination,
+                token_program: *token_program,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::RevokeVesting {}.data(),
+        }
+    }
+
+    // 创建SOL分发指令
+    #[allow(dead_code)]
+    pub fn create_distribution_ix(
+        creator: &Pubkey,
+        distribution_id: u64,
+        total_entries: u32,
+        total_amount: u64,
+    ) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::CreateDistribution {
+                creator: *creator,
+                bank_account,
+                distribution: get_distribution(&batch_transfer::ID, creator, distribution_id),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::CreateDistribution {
+                distribution_id,
+                total_entries,
+                total_amount,
+                max_fee: u64::MAX,
+            }
+            .data(),
+        }
+    }
+
+    // 创建代币分发指令
+    #[allow(dead_code, clippy::too_many_arguments)]
+    pub fn create_token_distribution_ix(
+        creator: &Pubkey,
+        creator_token_account: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        distribution_id: u64,
+        total_entries: u32,
+        total_amount: u64,
+    ) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        let distribution = get_distribution(&batch_transfer::ID, creator, distribution_id);
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::CreateTokenDistribution {
+                creator: *creator,
+                bank_account,
+                distribution,
+                vault: get_distribution_vault(&batch_transfer::ID, &distribution),
+                mint: *mint,
+                creator_token_account: *creator_token_account,
+                token_program: *token_program,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::CreateTokenDistribution {
+                distribution_id,
+                total_entries,
+                total_amount,
+                max_fee: u64::MAX,
+            }
+            .data(),
+        }
+    }
+
+    // 上传分发转账列表指令
+    #[allow(dead_code)]
+    pub fn upload_distribution_entries_ix(
+        creator: &Pubkey,
+        distribution: &Pubkey,
+        entries: Vec<TransferInfo>,
+    ) -> Instruction {
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::UploadDistributionEntries {
+                creator: *creator,
+                distribution: *distribution,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::UploadDistributionEntries { entries }.data(),
+        }
+    }
+
+    // 执行SOL分发指令，recipients 为本次执行的接收者
+    #[allow(dead_code)]
+    pub fn crank_distribution_ix(distribution: &Pubkey, recipients: &[Pubkey], max_items: u32) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        let mut accounts = batch_accounts::CrankDistribution {
+            bank_account,
+            distribution: *distribution,
+            vault: None,
+            mint: None,
+            token_program: None,
+        }
+        .to_account_metas(None);
+        accounts.extend(recipients.iter().map(|recipient| AccountMeta::new(*recipient, false)));
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts,
+            data: batch_instructions::CrankDistribution { max_items }.data(),
+        }
+    }
+
+    // 执行代币分发指令，recipients 为本次执行的接收者token账户
+    #[allow(dead_code)]
+    pub fn crank_token_distribution_ix(
+        distribution: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        recipients: &[Pubkey],
+        max_items: u32,
+    ) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        let mut accounts = batch_accounts::CrankDistribution {
+            bank_account,
+            distribution: *distribution,
+            vault: Some(get_distribution_vault(&batch_transfer::ID, distribution)),
+            mint: Some(*mint),
+            token_program: Some(*token_program),
+        }
+        .to_account_metas(None);
+        accounts.extend(recipients.iter().map(|recipient| AccountMeta::new(*recipient, false)));
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts,
+            data: batch_instructions::CrankDistribution { max_items }.data(),
+        }
+    }
+
+    // 取消SOL分发指令
+    #[allow(dead_code)]
+    pub fn cancel_distribution_ix(creator: &Pubkey, distribution: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::CancelDistribution {
+                creator: *creator,
+                distribution: *distribution,
+                vault: None,
+                mint: None,
+                destination: None,
+                token_program: None,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::CancelDistribution {}.data(),
+        }
+    }
+
+    // 取消代币分发指令，剩余代币转入 destination
+    #[allow(dead_code)]
+    pub fn cancel_token_distribution_ix(
+        creator: &Pubkey,
+        distribution: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        destination: &Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::CancelDistribution {
+                creator: *creator,
+                distribution: *distribution,
+                vault: Some(get_distribution_vault(&batch_transfer::ID, distribution)),
+                mint: Some(*mint),
+                destination: Some(*destination),
+                token_program: Some(*token_program),
+            }
+            .to_account_metas(None),
+            data: batch_instructions::CancelDistribution {}.data(),
+        }
+    }
+
+    // 创建资金库指令
+    #[allow(dead_code)]
+    pub fn create_vault_ix(owner: &Pubkey, vault_id: u64) -> Instruction {
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::CreateVault {
+                owner: *owner,
+                vault: get_vault(&batch_transfer::ID, owner, vault_id),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::CreateVault { vault_id }.data(),
+        }
+    }
+
+    // 向资金库存入SOL指令
+    #[allow(dead_code)]
+    pub fn deposit_vault_sol_ix(depositor: &Pubkey, vault: &Pubkey, amount: u64) -> Instruction {
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::DepositVaultSol {
+                depositor: *depositor,
+                vault: *vault,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::DepositVaultSol { amount }.data(),
+        }
+    }
+
+    // 向资金库存入代币指令
+    #[allow(dead_code)]
+    pub fn deposit_vault_token_ix(
+        depositor: &Pubkey,
+        vault: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        depositor_token_account: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::DepositVaultToken {
+                depositor: *depositor,
+                vault: *vault,
+                vault_token_account: get_vault_token_account(&batch_transfer::ID, vault, mint),
+                mint: *mint,
+                depositor_token_account: *depositor_token_account,
+                token_program: *token_program,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::DepositVaultToken { amount }.data(),
+        }
+    }
+
+    // 从资金库提取SOL指令
+    #[allow(dead_code)]
+    pub fn withdraw_vault_sol_ix(owner: &Pubkey, vault: &Pubkey, amount: u64) -> Instruction {
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::WithdrawVaultSol {
+                owner: *owner,
+                vault: *vault,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::WithdrawVaultSol { amount }.data(),
+        }
+    }
+
+    // 从资金库提取代币指令
+    #[allow(dead_code)]
+    pub fn withdraw_vault_token_ix(
+        owner: &Pubkey,
+        vault: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        destination: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::WithdrawVaultToken {
+                owner: *owner,
+                vault: *vault,
+                vault_token_account: get_vault_token_account(&batch_transfer::ID, vault, mint),
+                mint: *mint,
+                destination: *destination,
+                token_program: *token_program,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::WithdrawVaultToken { amount }.data(),
+        }
+    }
+
+    // 登记资金库操作员指令
+    #[allow(dead_code)]
+    pub fn add_vault_operator_ix(
+        owner: &Pubkey,
+        vault: &Pubkey,
+        operator: &Pubkey,
+        limits: Vec<OperatorLimit>,
+    ) -> Instruction {
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::AddVaultOperator {
+                owner: *owner,
+                vault: *vault,
+                vault_operator: get_vault_operator(&batch_transfer::ID, vault, operator),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: batch_instructions::AddVaultOperator {
+                operator: *operator,
+                limits,
+            }
+            .data(),
+        }
+    }
+
+    // 撤销资金库操作员指令
+    #[allow(dead_code)]
+    pub fn revoke_vault_operator_ix(owner: &Pubkey, vault: &Pubkey, operator: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts: batch_accounts::RevokeVaultOperator {
+                owner: *owner,
+                vault: *vault,
+                vault_operator: get_vault_operator(&batch_transfer::ID, vault, operator),
+            }
+            .to_account_metas(None),
+            data: batch_instructions::RevokeVaultOperator {}.data(),
+        }
+    }
+
+    // 操作员从资金库批量转账SOL指令
+    #[allow(dead_code)]
+    pub fn vault_batch_transfer_sol_ix(operator: &Pubkey, vault: &Pubkey, transfers: Vec<TransferInfo>) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        let mut accounts = batch_accounts::VaultBatchTransferSol {
+            operator: *operator,
+            vault: *vault,
+            vault_operator: get_vault_operator(&batch_transfer::ID, vault, operator),
+            bank_account,
+        }
+        .to_account_metas(None);
+        accounts.extend(transfers.iter().map(|transfer| AccountMeta::new(transfer.recipient, false)));
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts,
+            data: batch_instructions::VaultBatchTransferSol {
+                transfers,
+                max_fee: u64::MAX,
+            }
+            .data(),
+        }
+    }
+
+    // 操作员从资金库批量转账代币指令
+    #[allow(dead_code)]
+    pub fn vault_batch_transfer_token_ix(
+        operator: &Pubkey,
+        vault: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        transfers: Vec<TransferInfo>,
+    ) -> Instruction {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        let mut accounts = batch_accounts::VaultBatchTransferToken {
+            operator: *operator,
+            vault: *vault,
+            vault_operator: get_vault_operator(&batch_transfer::ID, vault, operator),
+            bank_account,
+            vault_token_account: get_vault_token_account(&batch_transfer::ID, vault, mint),
+            mint: *mint,
+            token_program: *token_program,
+        }
+        .to_account_metas(None);
+        accounts.extend(transfers.iter().map(|transfer| AccountMeta::new(transfer.recipient, false)));
+        Instruction {
+            program_id: batch_transfer::ID,
+            accounts,
+            data: batch_instructions::VaultBatchTransferToken {
+                transfers,
+                max_fee: u64::MAX,
+            }
+            .data(),
+        }
+    }
+
+    // 获取当前链上时间
+    #[allow(dead_code)]
+    pub async fn get_unix_timestamp(context: &mut ProgramTestContext) -> i64 {
+        context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp
+    }
+
+    // 修改链上时间
+    #[allow(dead_code)]
+    pub async fn set_unix_timestamp(context: &mut ProgramTestContext, unix_timestamp: i64) {
+        let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        context.set_sysvar(&clock);
+    }
+
+    // 创建代币mint，token_program 可以是SPL Token或Token-2022
+    #[allow(dead_code)]
+    pub async fn create_mint(
+        context: &mut ProgramTestContext,
+        token_program: &Pubkey,
+        authority: &Pubkey,
+        decimals: u8,
+    ) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = context.banks_client.get_rent().await.unwrap();
+        let instructions = [
+            system_instruction::create_account(
+                &context.payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                token_program,
+            ),
+            spl_token_2022::instruction::initialize_mint2(token_program, &mint.pubkey(), authority, None, decimals)
+                .unwrap(),
+        ];
+        send(context, &instructions, &[&mint]).await.expect("创建mint失败");
+        mint.pubkey()
+    }
+
+    // 创建带冻结权限的代币mint，冻结权限与铸造权限为同一账户
+    #[allow(dead_code)]
+    pub async fn create_mint_with_freeze_authority(
+        context: &mut ProgramTestContext,
+        token_program: &Pubkey,
+        authority: &Pubkey,
+        decimals: u8,
+    ) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = context.banks_client.get_rent().await.unwrap();
+        let instructions = [
+            system_instruction::create_account(
+                &context.payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                token_program,
+            ),
+            spl_token_2022::instruction::initialize_mint2(
+                token_program,
+                &mint.pubkey(),
+                authority,
+                Some(authority),
+                decimals,
+            )
+            .unwrap(),
+        ];
+        send(context, &instructions, &[&mint]).await.expect("创建mint失败");
+        mint.pubkey()
+    }
+
+    // 冻结token账户
+    #[allow(dead_code)]
+    pub async fn freeze_token_account(
+        context: &mut ProgramTestContext,
+        mint: &Pubkey,
+        freeze_authority: &Keypair,
+        account: &Pubkey,
+    ) {
+        let token_program = get_token_program(context, mint).await;
+        let instruction = spl_token_2022::instruction::freeze_account(
+            &token_program,
+            account,
+            mint,
+            &freeze_authority.pubkey(),
+            &[],
+        )
+        .unwrap();
+        send(context, &[instruction], &[freeze_authority]).await.expect("冻结token账户失败");
+    }
+
+    // 所有者授权代理人从token账户转出不超过 amount 的代币
+    #[allow(dead_code)]
+    pub async fn approve_delegate(
+        context: &mut ProgramTestContext,
+        account: &Pubkey,
+        owner: &Keypair,
+        delegate: &Pubkey,
+        amount: u64,
+    ) {
+        let token_program = get_token_program(context, account).await;
+        let instruction = spl_token_2022::instruction::approve(
+            &token_program,
+            account,
+            delegate,
+            &owner.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+        send(context, &[instruction], &[owner]).await.expect("授权代理人失败");
+    }
+
+    // 创建带转账手续费扩展的Token-2022 mint
+    #[allow(dead_code)]
+    pub async fn create_mint_with_transfer_fee(
+        context: &mut ProgramTestContext,
+        authority: &Pubkey,
+        decimals: u8,
+        transfer_fee_bps: u16,
+        maximum_fee: u64,
+    ) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = context.banks_client.get_rent().await.unwrap();
+        let space =
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[ExtensionType::TransferFeeConfig])
+                .unwrap();
+        let instructions = [
+            system_instruction::create_account(
+                &context.payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(space),
+                space as u64,
+                &spl_token_2022::ID,
+            ),
+            spl_token_2022::extension::transfer_fee::instruction::initialize_transfer_fee_config(
+                &spl_token_2022::ID,
+                &mint.pubkey(),
+                Some(authority),
+                Some(authority),
+                transfer_fee_bps,
+                maximum_fee,
+            )
+            .unwrap(),
+            spl_token_2022::instruction::initialize_mint2(&spl_token_2022::ID, &mint.pubkey(), authority, None, decimals)
+                .unwrap(),
+        ];
+        send(context, &instructions, &[&mint]).await.expect("创建带转账手续费的mint失败");
+        mint.pubkey()
+    }
+
+    // 创建配置了转账钩子的Token-2022 mint
+    #[allow(dead_code)]
+    pub async fn create_mint_with_transfer_hook(
+        context: &mut ProgramTestContext,
+        authority: &Pubkey,
+        decimals: u8,
+        hook_program_id: &Pubkey,
+    ) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = context.banks_client.get_rent().await.unwrap();
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[ExtensionType::TransferHook])
+            .unwrap();
+        let instructions = [
+            system_instruction::create_account(
+                &context.payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(space),
+                space as u64,
+                &spl_token_2022::ID,
+            ),
+            spl_token_2022::extension::transfer_hook::instruction::initialize(
+                &spl_token_2022::ID,
+                &mint.pubkey(),
+                Some(*authority),
+                Some(*hook_program_id),
+            )
+            .unwrap(),
+            spl_token_2022::instruction::initialize_mint2(&spl_token_2022::ID, &mint.pubkey(), authority, None, decimals)
+                .unwrap(),
+        ];
+        send(context, &instructions, &[&mint]).await.expect("创建带转账钩子的mint失败");
+        mint.pubkey()
+    }
+
+    // 创建token账户，所属代币程序和所需扩展由mint决定
+    #[allow(dead_code)]
+    pub async fn create_token_account(context: &mut ProgramTestContext, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+        let mint_account = context
+            .banks_client
+            .get_account(*mint)
+            .await
+            .unwrap()
+            .expect("mint不存在");
+        let token_program = mint_account.owner;
+        let space = if token_program == spl_token_2022::ID {
+            let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_account.data).unwrap();
+            let account_extensions =
+                ExtensionType::get_required_init_account_extensions(&mint_state.get_extension_types().unwrap());
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(&account_extensions).unwrap()
+        } else {
+            spl_token::state::Account::LEN
+        };
+
+        let account = Keypair::new();
+        let rent = context.banks_client.get_rent().await.unwrap();
+        let instructions = [
+            system_instruction::create_account(
+                &context.payer.pubkey(),
+                &account.pubkey(),
+                rent.minimum_balance(space),
+                space as u64,
+                &token_program,
+            ),
+            spl_token_2022::instruction::initialize_account3(&token_program, &account.pubkey(), mint, owner)
+                .unwrap(),
+        ];
+        send(context, &instructions, &[&account]).await.expect("创建token账户失败");
+        account.pubkey()
+    }
+
+    // 创建钱包的关联token账户(ATA)
+    #[allow(dead_code)]
+    pub async fn create_associated_token_account(
+        context: &mut ProgramTestContext,
+        mint: &Pubkey,
+        wallet: &Pubkey,
+    ) -> Pubkey {
+        let token_program = get_token_program(context, mint).await;
+        let instruction = spl_associated_token_account::instruction::create_associated_token_account(
+            &context.payer.pubkey(),
+            wallet,
+            mint,
+            &token_program,
+        );
+        send(context, &[instruction], &[]).await.expect("创建ATA失败");
+        get_associated_token_address_with_program_id(wallet, mint, &token_program)
+    }
+
+    // 铸造代币
+    #[allow(dead_code)]
+    pub async fn mint_tokens(
+        context: &mut ProgramTestContext,
+        mint: &Pubkey,
+        authority: &Keypair,
+        destination: &Pubkey,
+        amount: u64,
+    ) {
+        let token_program = get_token_program(context, mint).await;
+        let instruction = spl_token_2022::instruction::mint_to(
+            &token_program,
+            mint,
+            destination,
+            &authority.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+        send(context, &[instruction], &[authority]).await.expect("铸造代币失败");
+    }
+
+    // 获取mint或token账户所属的代币程序
+    #[allow(dead_code)]
+    pub async fn get_token_program(context: &mut ProgramTestContext, pubkey: &Pubkey) -> Pubkey {
+        context
+            .banks_client
+            .get_account(*pubkey)
+            .await
+            .unwrap()
+            .expect("账户不存在")
+            .owner
+    }
+
+    // 获取token账户余额，兼容SPL Token和Token-2022账户
+    #[allow(dead_code)]
+    pub async fn get_token_balance(context: &mut ProgramTestContext, pubkey: &Pubkey) -> u64 {
+        let account = context
+            .banks_client
+            .get_account(*pubkey)
+            .await
+            .unwrap()
+            .expect("token账户不存在");
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .unwrap()
+            .base
+            .amount
+    }
+
+    // 以管理员作为升级权限初始化银行账户并设置固定手续费；
+    // 等待初始化时的默认变更延迟后把延迟设为0，之后的测试可以在同一笔交易中提交并执行配置变更
+    #[allow(dead_code)]
+    pub async fn setup_bank_account(context: &mut ProgramTestContext, admin: &Keypair, fee: u64) {
+        set_program_data(context, Some(admin.pubkey()));
+        send(context, &[initialize_ix(&admin.pubkey())], &[admin])
+            .await
+            .expect("初始化失败");
+        let new_config = BankConfig {
+            fee_config: FeeConfig {
+                flat_fee: fee,
+                ..FeeConfig::default()
+            },
+            config_delay: 0,
+            allow_fee_decrease_bypass: false,
+        };
+        let eta = get_unix_timestamp(context).await + batch_transfer::DEFAULT_CONFIG_DELAY;
+        send(context, &[queue_config_change_ix(&admin.pubkey(), new_config, eta)], &[admin])
+            .await
+            .expect("提交初始配置失败");
+        set_unix_timestamp(context, eta).await;
+        send(context, &[execute_config_change_ix()], &[])
+            .await
+            .expect("设置手续费失败");
+    }
+}
@@ -0,0 +1,271 @@
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use batch_transfer::{self, ErrorCode, OperatorLimit, TransferInfo, Vault, VaultOperator};
+
+mod utils_test;
+use utils_test::{get_bank_account, get_vault, get_vault_operator, get_vault_token_account};
+use utils_test::program_test_utils::*;
+
+const DAY: i64 = 24 * 3_600;
+
+fn transfers(recipients: &[Pubkey], amount: u64) -> Vec<TransferInfo> {
+    recipients
+        .iter()
+        .map(|recipient| TransferInfo {
+            recipient: *recipient,
+            amount,
+        })
+        .collect()
+}
+
+/// 测试操作员从资金库批量转账SOL，额度按单批上限和滚动24小时上限校验，所有者可随时撤销
+#[tokio::test]
+async fn test_vault_sol_operator() {
+    let admin = Keypair::new();
+    let owner = Keypair::new();
+    let operator = Keypair::new();
+    let attacker = Keypair::new();
+    let recipients = [Keypair::new().pubkey(), Keypair::new().pubkey()];
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &owner.pubkey(), 20 * LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &operator.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &attacker.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, 10_000).await;
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+    let vault = get_vault(&batch_transfer::ID, &owner.pubkey(), 1);
+
+    let limits = vec![OperatorLimit {
+        mint: None,
+        max_per_batch: 2 * LAMPORTS_PER_SOL,
+        daily_cap: 3 * LAMPORTS_PER_SOL,
+    }];
+    send(
+        &mut context,
+        &[
+            create_vault_ix(&owner.pubkey(), 1),
+            deposit_vault_sol_ix(&owner.pubkey(), &vault, 10 * LAMPORTS_PER_SOL),
+            add_vault_operator_ix(&owner.pubkey(), &vault, &operator.pubkey(), limits.clone()),
+        ],
+        &[&owner],
+    )
+    .await
+    .expect("创建资金库并登记操作员失败");
+    let state: Vault = get_account_data(&mut context, &vault).await;
+    assert_eq!(state.owner, owner.pubkey());
+    let vault_operator = get_vault_operator(&batch_transfer::ID, &vault, &operator.pubkey());
+    let state: VaultOperator = get_account_data(&mut context, &vault_operator).await;
+    assert_eq!(state.operator, operator.pubkey());
+    assert_eq!(state.allowances[0].limit, limits[0]);
+
+    // 非所有者不能登记操作员
+    let instruction = add_vault_operator_ix(&attacker.pubkey(), &vault, &attacker.pubkey(), limits.clone());
+    let result = send(&mut context, &[instruction], &[&attacker]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    // 操作员转账，转账金额和手续费均从资金库扣除
+    let vault_balance_before = get_balance(&mut context, &vault).await;
+    let bank_balance_before = get_balance(&mut context, &bank_account).await;
+    let operator_balance_before = get_balance(&mut context, &operator.pubkey()).await;
+    let instruction = vault_batch_transfer_sol_ix(&operator.pubkey(), &vault, transfers(&recipients, 3 * LAMPORTS_PER_SOL / 4));
+    send(&mut context, &[instruction], &[&operator])
+        .await
+        .expect("操作员批量转账SOL失败");
+    assert_eq!(get_balance(&mut context, &recipients[0]).await, 3 * LAMPORTS_PER_SOL / 4);
+    assert_eq!(get_balance(&mut context, &recipients[1]).await, 3 * LAMPORTS_PER_SOL / 4);
+    assert_eq!(
+        vault_balance_before - get_balance(&mut context, &vault).await,
+        3 * LAMPORTS_PER_SOL / 2 + 10_000
+    );
+    assert_eq!(get_balance(&mut context, &bank_account).await - bank_balance_before, 10_000);
+    assert_eq!(get_balance(&mut context, &operator.pubkey()).await, operator_balance_before);
+
+    // 超过单批上限
+    let instruction = vault_batch_transfer_sol_ix(&operator.pubkey(), &vault, transfers(&recipients, 5 * LAMPORTS_PER_SOL / 4));
+    let result = send(&mut context, &[instruction], &[&operator]).await;
+    assert_program_error(result, ErrorCode::OperatorBatchLimitExceeded);
+
+    // 24小时内累计超过上限
+    let instruction = vault_batch_transfer_sol_ix(&operator.pubkey(), &vault, transfers(&recipients, LAMPORTS_PER_SOL));
+    let result = send(&mut context, &[instruction.clone()], &[&operator]).await;
+    assert_program_error(result, ErrorCode::OperatorDailyCapExceeded);
+
+    // 24小时后额度恢复
+    let now = get_unix_timestamp(&mut context).await;
+    set_unix_timestamp(&mut context, now + DAY).await;
+    send(&mut context, &[instruction], &[&operator])
+        .await
+        .expect("额度恢复后批量转账失败");
+    assert_eq!(get_balance(&mut context, &recipients[0]).await, 7 * LAMPORTS_PER_SOL / 4);
+
+    // 暂停期间拒绝操作员转账
+    send(&mut context, &[pause_ix(&admin.pubkey())], &[&admin])
+        .await
+        .expect("暂停失败");
+    let instruction = vault_batch_transfer_sol_ix(&operator.pubkey(), &vault, transfers(&recipients[..1], 1_000_000));
+    let result = send(&mut context, &[instruction], &[&operator]).await;
+    assert_program_error(result, ErrorCode::Paused);
+    send(&mut context, &[unpause_ix(&admin.pubkey())], &[&admin])
+        .await
+        .expect("恢复失败");
+
+    // 未登记的操作员不能转账
+    let instruction = vault_batch_transfer_sol_ix(&attacker.pubkey(), &vault, transfers(&recipients[..1], 1_000_000));
+    let result = send(&mut context, &[instruction], &[&attacker]).await;
+    assert!(result.is_err(), "未登记的操作员不应能转账");
+
+    // 非所有者不能撤销，所有者撤销后操作员不能再转账
+    let result = send(
+        &mut context,
+        &[revoke_vault_operator_ix(&attacker.pubkey(), &vault, &operator.pubkey())],
+        &[&attacker],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+    send(
+        &mut context,
+        &[revoke_vault_operator_ix(&owner.pubkey(), &vault, &operator.pubkey())],
+        &[&owner],
+    )
+    .await
+    .expect("撤销操作员失败");
+    assert!(context.banks_client.get_account(vault_operator).await.unwrap().is_none());
+    let instruction = vault_batch_transfer_sol_ix(&operator.pubkey(), &vault, transfers(&recipients[..1], 1_000_000));
+    let result = send(&mut context, &[instruction], &[&operator]).await;
+    assert!(result.is_err(), "撤销后操作员不应能转账");
+
+    // 所有者提取SOL，资金库保留租金豁免所需的最低余额
+    let rent_exempt_minimum = get_rent_exempt_minimum(&mut context, &vault).await;
+    let available = get_balance(&mut context, &vault).await - rent_exempt_minimum;
+    let result = send(&mut context, &[withdraw_vault_sol_ix(&attacker.pubkey(), &vault, 1)], &[&attacker]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+    let result = send(
+        &mut context,
+        &[withdraw_vault_sol_ix(&owner.pubkey(), &vault, available + 1)],
+        &[&owner],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::InsufficientFunds);
+    let owner_balance_before = get_balance(&mut context, &owner.pubkey()).await;
+    send(&mut context, &[withdraw_vault_sol_ix(&owner.pubkey(), &vault, available)], &[&owner])
+        .await
+        .expect("提取资金库SOL失败");
+    assert_eq!(get_balance(&mut context, &owner.pubkey()).await - owner_balance_before, available);
+    assert_eq!(get_balance(&mut context, &vault).await, rent_exempt_minimum);
+}
+
+/// 测试操作员从资金库批量转账代币，只能转出已登记的代币，手续费以SOL从资金库扣除
+#[tokio::test]
+async fn test_vault_token_operator() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let admin = Keypair::new();
+        let owner = Keypair::new();
+        let operator = Keypair::new();
+        let mint_authority = Keypair::new();
+
+        let mut program_test = program_test();
+        add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+        add_funded_account(&mut program_test, &owner.pubkey(), 10 * LAMPORTS_PER_SOL);
+        add_funded_account(&mut program_test, &operator.pubkey(), LAMPORTS_PER_SOL);
+        let mut context = program_test.start_with_context().await;
+        setup_bank_account(&mut context, &admin, 10_000).await;
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+
+        let mint = create_mint(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+        let other_mint = create_mint(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+        let owner_token = create_token_account(&mut context, &mint, &owner.pubkey()).await;
+        let owner_other_token = create_token_account(&mut context, &other_mint, &owner.pubkey()).await;
+        mint_tokens(&mut context, &mint, &mint_authority, &owner_token, 1_000_000).await;
+        mint_tokens(&mut context, &other_mint, &mint_authority, &owner_other_token, 1_000_000).await;
+        let recipients = [
+            create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await,
+            create_token_account(&mut context, &mint, &Keypair::new().pubkey()).await,
+        ];
+        let other_recipient = create_token_account(&mut context, &other_mint, &Keypair::new().pubkey()).await;
+
+        let vault = get_vault(&batch_transfer::ID, &owner.pubkey(), 7);
+        let vault_token = get_vault_token_account(&batch_transfer::ID, &vault, &mint);
+        let limits = vec![OperatorLimit {
+            mint: Some(mint),
+            max_per_batch: 50_000,
+            daily_cap: 80_000,
+        }];
+        send(
+            &mut context,
+            &[
+                create_vault_ix(&owner.pubkey(), 7),
+                deposit_vault_token_ix(&owner.pubkey(), &vault, &mint, &token_program, &owner_token, 200_000),
+                deposit_vault_token_ix(&owner.pubkey(), &vault, &other_mint, &token_program, &owner_other_token, 200_000),
+                add_vault_operator_ix(&owner.pubkey(), &vault, &operator.pubkey(), limits),
+            ],
+            &[&owner],
+        )
+        .await
+        .expect("创建资金库并存入代币失败");
+        assert_eq!(get_token_balance(&mut context, &vault_token).await, 200_000);
+
+        // 资金库没有可用SOL支付手续费
+        let instruction =
+            vault_batch_transfer_token_ix(&operator.pubkey(), &vault, &mint, &token_program, transfers(&recipients, 20_000));
+        let result = send(&mut context, &[instruction.clone()], &[&operator]).await;
+        assert_program_error(result, ErrorCode::InsufficientFunds);
+
+        send(
+            &mut context,
+            &[deposit_vault_sol_ix(&owner.pubkey(), &vault, LAMPORTS_PER_SOL)],
+            &[&owner],
+        )
+        .await
+        .expect("存入SOL失败");
+        let vault_balance_before = get_balance(&mut context, &vault).await;
+        let bank_balance_before = get_balance(&mut context, &bank_account).await;
+        send(&mut context, &[instruction], &[&operator])
+            .await
+            .expect("操作员批量转账代币失败");
+        assert_eq!(get_token_balance(&mut context, &recipients[0]).await, 20_000);
+        assert_eq!(get_token_balance(&mut context, &recipients[1]).await, 20_000);
+        assert_eq!(get_token_balance(&mut context, &vault_token).await, 160_000);
+        assert_eq!(vault_balance_before - get_balance(&mut context, &vault).await, 10_000);
+        assert_eq!(get_balance(&mut context, &bank_account).await - bank_balance_before, 10_000);
+
+        // 超过单批上限和24小时上限
+        let instruction =
+            vault_batch_transfer_token_ix(&operator.pubkey(), &vault, &mint, &token_program, transfers(&recipients, 25_001));
+        let result = send(&mut context, &[instruction], &[&operator]).await;
+        assert_program_error(result, ErrorCode::OperatorBatchLimitExceeded);
+        let instruction =
+            vault_batch_transfer_token_ix(&operator.pubkey(), &vault, &mint, &token_program, transfers(&recipients, 20_001));
+        let result = send(&mut context, &[instruction], &[&operator]).await;
+        assert_program_error(result, ErrorCode::OperatorDailyCapExceeded);
+
+        // 未登记的代币和SOL都不能转出
+        let instruction = vault_batch_transfer_token_ix(
+            &operator.pubkey(),
+            &vault,
+            &other_mint,
+            &token_program,
+            transfers(&[other_recipient], 1_000),
+        );
+        let result = send(&mut context, &[instruction], &[&operator]).await;
+        assert_program_error(result, ErrorCode::OperatorMintNotAllowed);
+        let instruction =
+            vault_batch_transfer_sol_ix(&operator.pubkey(), &vault, transfers(&[Keypair::new().pubkey()], LAMPORTS_PER_SOL / 10));
+        let result = send(&mut context, &[instruction], &[&operator]).await;
+        assert_program_error(result, ErrorCode::OperatorMintNotAllowed);
+
+        // 所有者提取代币
+        let instruction = withdraw_vault_token_ix(&owner.pubkey(), &vault, &mint, &token_program, &owner_token, 160_000);
+        send(&mut context, &[instruction], &[&owner])
+            .await
+            .expect("提取资金库代币失败");
+        assert_eq!(get_token_balance(&mut context, &vault_token).await, 0);
+        assert_eq!(get_token_balance(&mut context, &owner_token).await, 1_000_000 - 40_000);
+    }
+}
@@ -0,0 +1,348 @@
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use batch_transfer::{self, ErrorCode, VestingAccount, VestingInfo};
+use solana_program_test::{BanksClientError, ProgramTestContext};
+
+mod utils_test;
+use utils_test::program_test_utils::*;
+use utils_test::{get_bank_account, get_vesting};
+
+// 锁仓测试环境：创建者持有1_000_000个代币，两个接收者各有一个token账户
+struct Setup {
+    context: ProgramTestContext,
+    creator: Keypair,
+    mint: Pubkey,
+    token_program: Pubkey,
+    creator_token: Pubkey,
+    recipients: Vec<Keypair>,
+    recipient_tokens: Vec<Pubkey>,
+    start: i64,
+}
+
+async fn setup(token_program: Pubkey, sol_fee: u64) -> Setup {
+    let admin = Keypair::new();
+    let creator = Keypair::new();
+    let mint_authority = Keypair::new();
+    let recipients: Vec<Keypair> = (0..2).map(|_| Keypair::new()).collect();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &creator.pubkey(), LAMPORTS_PER_SOL);
+    for recipient in &recipients {
+        add_funded_account(&mut program_test, &recipient.pubkey(), LAMPORTS_PER_SOL);
+    }
+    let mut context = program_test.start_with_context().await;
+    setup_bank_account(&mut context, &admin, sol_fee).await;
+
+    let mint = create_mint(&mut context, &token_program, &mint_authority.pubkey(), 6).await;
+    let creator_token = create_token_account(&mut context, &mint, &creator.pubkey()).await;
+    mint_tokens(&mut context, &mint, &mint_authority, &creator_token, 1_000_000).await;
+    let mut recipient_tokens = Vec::new();
+    for recipient in &recipients {
+        recipient_tokens.push(create_token_account(&mut context, &mint, &recipient.pubkey()).await);
+    }
+    let start = get_unix_timestamp(&mut context).await;
+
+    Setup {
+        context,
+        creator,
+        mint,
+        token_program,
+        creator_token,
+        recipients,
+        recipient_tokens,
+        start,
+    }
+}
+
+impl Setup {
+    // 从 start 开始、cliff 为100秒、1000秒后全部释放的锁仓计划
+    fn schedule(&self, recipient: usize, amount: u64, revocable: bool) -> VestingInfo {
+        VestingInfo {
+            recipient: self.recipients[recipient].pubkey(),
+            amount,
+            start: self.start,
+            cliff: self.start + 100,
+            end: self.start + 1_000,
+            revocable,
+        }
+    }
+
+    fn vesting(&self, recipient: usize) -> Pubkey {
+        get_vesting(
+            &batch_transfer::ID,
+            &self.creator.pubkey(),
+            &self.mint,
+            &self.recipients[recipient].pubkey(),
+            self.start,
+        )
+    }
+
+    async fn create(&mut self, schedules: Vec<VestingInfo>) -> Result<(), BanksClientError> {
+        let instruction = batch_create_vesting_ix(
+            &self.creator.pubkey(),
+            &self.creator_token,
+            &self.mint,
+            &self.token_program,
+            schedules,
+        );
+        send(&mut self.context, &[instruction], &[&self.creator]).await
+    }
+
+    async fn withdraw(&mut self, recipient: usize) -> Result<(), BanksClientError> {
+        let instruction = withdraw_vested_ix(
+            &self.recipients[recipient].pubkey(),
+            &self.vesting(recipient),
+            &self.mint,
+            &self.token_program,
+            &self.recipient_tokens[recipient],
+        );
+        send(&mut self.context, &[instruction], &[&self.recipients[recipient]]).await
+    }
+
+    async fn revoke(&mut self, signer: &Keypair, recipient: usize) -> Result<(), BanksClientError> {
+        let instruction = revoke_vesting_ix(
+            &signer.pubkey(),
+            &self.vesting(recipient),
+            &self.mint,
+            &self.token_program,
+            &self.creator_token,
+        );
+        send(&mut self.context, &[instruction], &[signer]).await
+    }
+}
+
+/// 测试释放数量的计算
+#[test]
+fn test_vested_amount() {
+    let mut vesting = VestingAccount {
+        creator: Pubkey::new_unique(),
+        recipient: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        vault: Pubkey::new_unique(),
+        start: 1_000,
+        cliff: 1_250,
+        end: 2_000,
+        amount: 1_000_000,
+        withdrawn: 0,
+        revocable: true,
+        revoked: false,
+        bump: 255,
+    };
+
+    // cliff 之前不释放，cliff 之后按开始时间线性释放
+    assert_eq!(vesting.vested_amount(0), 0);
+    assert_eq!(vesting.vested_amount(1_249), 0);
+    assert_eq!(vesting.vested_amount(1_250), 250_000);
+    assert_eq!(vesting.vested_amount(1_999), 999_000);
+    assert_eq!(vesting.vested_amount(2_000), 1_000_000);
+    assert_eq!(vesting.vested_amount(i64::MAX), 1_000_000);
+
+    // 大额锁仓不会在乘法中溢出
+    vesting.amount = u64::MAX;
+    assert_eq!(vesting.vested_amount(1_500), u64::MAX / 2);
+
+    // 开始和结束时间为 i64 极端值时计算时间差不会溢出
+    vesting.amount = 1_000_000;
+    vesting.start = i64::MIN;
+    vesting.cliff = i64::MIN;
+    vesting.end = i64::MAX;
+    assert_eq!(vesting.vested_amount(i64::MIN), 0);
+    assert_eq!(vesting.vested_amount(0), 500_000);
+    assert_eq!(vesting.vested_amount(i64::MAX - 1), 999_999);
+    assert_eq!(vesting.vested_amount(i64::MAX), 1_000_000);
+    vesting.end = 2_000;
+    assert_eq!(vesting.vested_amount(1_999), 999_999);
+
+    // 已撤销的锁仓全部释放
+    vesting.amount = 300_000;
+    vesting.revoked = true;
+    assert_eq!(vesting.vested_amount(0), 300_000);
+}
+
+/// 测试批量创建锁仓并按释放进度提取
+#[tokio::test]
+async fn test_batch_create_vesting_and_withdraw() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+        let mut setup = setup(token_program, 10_000).await;
+        let bank_balance_before = get_balance(&mut setup.context, &bank_account).await;
+
+        let schedules = vec![setup.schedule(0, 100_000, false), setup.schedule(1, 200_000, true)];
+        setup.create(schedules).await.expect("批量创建锁仓失败");
+
+        assert_eq!(get_token_balance(&mut setup.context, &setup.creator_token).await, 700_000);
+        assert_eq!(get_balance(&mut setup.context, &bank_account).await - bank_balance_before, 10_000);
+        for (i, amount) in [(0, 100_000), (1, 200_000)] {
+            let vesting_key = setup.vesting(i);
+            let vesting: VestingAccount = get_account_data(&mut setup.context, &vesting_key).await;
+            assert_eq!(vesting.creator, setup.creator.pubkey());
+            assert_eq!(vesting.recipient, setup.recipients[i].pubkey());
+            assert_eq!(vesting.amount, amount);
+            assert_eq!(vesting.withdrawn, 0);
+            assert_eq!(vesting.revocable, i == 1);
+            let vault = get_associated_token_address_with_program_id(&vesting_key, &setup.mint, &token_program);
+            assert_eq!(vesting.vault, vault);
+            assert_eq!(get_token_balance(&mut setup.context, &vault).await, amount);
+        }
+
+        // cliff 之前没有可提取的代币
+        let result = setup.withdraw(0).await;
+        assert_program_error(result, ErrorCode::NothingToWithdraw);
+
+        // 释放一半
+        set_unix_timestamp(&mut setup.context, setup.start + 500).await;
+        setup.withdraw(0).await.expect("提取失败");
+        assert_eq!(get_token_balance(&mut setup.context, &setup.recipient_tokens[0]).await, 50_000);
+        let result = setup.withdraw(0).await;
+        assert_program_error(result, ErrorCode::NothingToWithdraw);
+
+        // 全部释放后提取剩余部分
+        set_unix_timestamp(&mut setup.context, setup.start + 1_000).await;
+        setup.withdraw(0).await.expect("提取失败");
+        setup.withdraw(1).await.expect("提取失败");
+        assert_eq!(get_token_balance(&mut setup.context, &setup.recipient_tokens[0]).await, 100_000);
+        assert_eq!(get_token_balance(&mut setup.context, &setup.recipient_tokens[1]).await, 200_000);
+
+        let vesting_key = setup.vesting(0);
+        let vesting: VestingAccount = get_account_data(&mut setup.context, &vesting_key).await;
+        assert_eq!(vesting.withdrawn, 100_000);
+    }
+}
+
+/// 测试创建者撤销锁仓，收回未释放部分，已释放部分仍归接收者
+#[tokio::test]
+async fn test_revoke_vesting() {
+    let mut setup = setup(spl_token::ID, 0).await;
+    let schedules = vec![setup.schedule(0, 100_000, false), setup.schedule(1, 200_000, true)];
+    setup.create(schedules).await.expect("批量创建锁仓失败");
+
+    set_unix_timestamp(&mut setup.context, setup.start + 250).await;
+
+    // 不可撤销的锁仓
+    let creator = setup.creator.insecure_clone();
+    let result = setup.revoke(&creator, 0).await;
+    assert_program_error(result, ErrorCode::VestingNotRevocable);
+
+    // 非创建者不能撤销
+    let attacker = setup.recipients[1].insecure_clone();
+    let result = setup.revoke(&attacker, 1).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+
+    setup.revoke(&creator, 1).await.expect("撤销锁仓失败");
+    assert_eq!(get_token_balance(&mut setup.context, &setup.creator_token).await, 700_000 + 150_000);
+    let vesting_key = setup.vesting(1);
+    let vesting: VestingAccount = get_account_data(&mut setup.context, &vesting_key).await;
+    assert!(vesting.revoked);
+    assert_eq!(vesting.amount, 50_000);
+
+    let result = setup.revoke(&creator, 1).await;
+    assert_program_error(result, ErrorCode::VestingAlreadyRevoked);
+
+    // 撤销后接收者仍可提取撤销时已释放的部分，且不再继续释放
+    set_unix_timestamp(&mut setup.context, setup.start + 1_000).await;
+    setup.withdraw(1).await.expect("提取失败");
+    assert_eq!(get_token_balance(&mut setup.context, &setup.recipient_tokens[1]).await, 50_000);
+    let result = setup.withdraw(1).await;
+    assert_program_error(result, ErrorCode::NothingToWithdraw);
+}
+
+/// 测试开始时间为 i64::MIN 的锁仓仍可正常提取和撤销，释放进度的计算不会溢出
+#[tokio::test]
+async fn test_vesting_extreme_timestamps() {
+    let mut setup = setup(spl_token::ID, 0).await;
+    let now = setup.start;
+    setup.start = i64::MIN;
+    let schedule = VestingInfo {
+        recipient: setup.recipients[0].pubkey(),
+        amount: 1_000_000,
+        start: i64::MIN,
+        cliff: i64::MIN,
+        end: now + 1_000,
+        revocable: true,
+    };
+    setup.create(vec![schedule]).await.expect("批量创建锁仓失败");
+
+    set_unix_timestamp(&mut setup.context, now).await;
+    let vesting_key = setup.vesting(0);
+    let vesting: VestingAccount = get_account_data(&mut setup.context, &vesting_key).await;
+    let vested = vesting.vested_amount(now);
+    assert!(vested > 0 && vested < 1_000_000);
+
+    setup.withdraw(0).await.expect("提取失败");
+    assert_eq!(get_token_balance(&mut setup.context, &setup.recipient_tokens[0]).await, vested);
+
+    let creator = setup.creator.insecure_clone();
+    setup.revoke(&creator, 0).await.expect("撤销锁仓失败");
+    assert_eq!(get_token_balance(&mut setup.context, &setup.creator_token).await, 1_000_000 - vested);
+}
+
+/// 测试无效的锁仓计划、重复的锁仓以及非接收者提取
+#[tokio::test]
+async fn test_batch_create_vesting_rejected() {
+    let mut setup = setup(spl_token::ID, 0).await;
+
+    // cliff 早于 start
+    let mut invalid = setup.schedule(1, 1_000, false);
+    invalid.cliff = invalid.start - 1;
+    let result = setup.create(vec![setup.schedule(0, 1_000, false), invalid]).await;
+    assert_program_error(result, ErrorCode::InvalidVestingSchedule);
+
+    // end 不晚于 start
+    let mut invalid = setup.schedule(0, 1_000, false);
+    invalid.end = invalid.start;
+    invalid.cliff = invalid.start;
+    let result = setup.create(vec![invalid]).await;
+    assert_program_error(result, ErrorCode::InvalidVestingSchedule);
+
+    // 数量为0
+    let result = setup.create(vec![setup.schedule(0, 0, false)]).await;
+    assert_program_error(result, ErrorCode::InvalidVestingSchedule);
+
+    // 同一接收者、同一开始时间的锁仓已存在
+    let result = setup
+        .create(vec![setup.schedule(0, 1_000, false), setup.schedule(0, 2_000, false)])
+        .await;
+    assert_program_error(result, ErrorCode::InvalidVestingAccount);
+    assert_eq!(get_token_balance(&mut setup.context, &setup.creator_token).await, 1_000_000);
+
+    // 非接收者不能提取
+    setup.create(vec![setup.schedule(0, 1_000, false)]).await.expect("批量创建锁仓失败");
+    set_unix_timestamp(&mut setup.context, setup.start + 1_000).await;
+    let attacker = setup.recipients[1].insecure_clone();
+    let instruction = withdraw_vested_ix(
+        &attacker.pubkey(),
+        &setup.vesting(0),
+        &setup.mint,
+        &setup.token_program,
+        &setup.recipient_tokens[1],
+    );
+    let result = send(&mut setup.context, &[instruction], &[&attacker]).await;
+    assert_program_error(result, ErrorCode::Unauthorized);
+}
+
+/// 测试锁仓地址被预先转入lamports时仍可创建锁仓
+#[tokio::test]
+async fn test_batch_create_vesting_prefunded_address() {
+    let mut setup = setup(spl_token::ID, 0).await;
+    let vesting_key = setup.vesting(0);
+
+    let payer = setup.recipients[1].insecure_clone();
+    let instruction = system_instruction::transfer(&payer.pubkey(), &vesting_key, 1_000_000);
+    send(&mut setup.context, &[instruction], &[&payer]).await.expect("转账失败");
+
+    setup.create(vec![setup.schedule(0, 1_000, false)]).await.expect("批量创建锁仓失败");
+    let vesting: VestingAccount = get_account_data(&mut setup.context, &vesting_key).await;
+    assert_eq!(vesting.amount, 1_000);
+    assert_eq!(
+        get_balance(&mut setup.context, &vesting_key).await,
+        get_rent_exempt_minimum(&mut setup.context, &vesting_key).await
+    );
+}
@@ -1,65 +1,122 @@
-use anchor_client::{
-    solana_sdk::{
-        signature::{Keypair, Signer},
-        native_token::LAMPORTS_PER_SOL,
-    },
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
 };
-use batch_transfer::{self};
+use batch_transfer::{self, ErrorCode, TransferInfo};
 
 mod utils_test;
-use utils_test::{get_test_program, get_bank_account};
+use utils_test::get_bank_account;
+use utils_test::program_test_utils::*;
 
 /// 测试管理员提取费用功能
-#[test]
-fn test_withdraw_fee() {
-    // 获取程序和支付者
-    let (program, _payer) = get_test_program();
-    
-    // 创建测试账户
+#[tokio::test]
+async fn test_withdraw_fee() {
     let admin = Keypair::new();
-    
-    // 获取银行账户PDA
-    let (bank_account, _) = get_bank_account(&program.id());
-    
+    let sender = Keypair::new();
+    let recipient = Keypair::new();
+    let destination = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &destination.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+    let fee = 10_000;
+    setup_bank_account(&mut context, &admin, fee).await;
+
     println!("开始测试管理员提取费用功能");
-    
-    // 模拟银行账户状态
-    let bank_account_data = batch_transfer::BankAccount {
-        admin: admin.pubkey(),
-        fee: 100, // 设置费用为1% (100 basis points)
-        is_initialized: true,
-    };
-    
-    // 模拟银行账户余额（假设已经收集了一些费用）
-    let bank_account_balance = LAMPORTS_PER_SOL / 10; // 0.1 SOL
-    println!("银行账户当前余额: {} lamports", bank_account_balance);
-    
-    // 记录管理员提取前的SOL余额
-    let admin_balance_before = LAMPORTS_PER_SOL / 2; // 0.5 SOL
-    println!("管理员提取前余额: {} lamports", admin_balance_before);
-    
-    // 模拟提取操作
-    // 在实际合约中，会调用withdraw_fee指令
-    
-    // 计算预期结果 - 所有费用转移到管理员账户
-    let admin_balance_after = admin_balance_before + bank_account_balance;
-    let bank_account_balance_after = 0; // 银行账户应该为0
-    
-    // 验证结果
-    println!("管理员提取后余额: {} lamports", admin_balance_after);
-    println!("银行账户提取后余额: {} lamports", bank_account_balance_after);
-    
-    assert_eq!(
-        admin_balance_after, 
-        admin_balance_before + bank_account_balance,
-        "管理员余额应该增加银行账户中的所有资金"
-    );
-    
+
+    // 两次批量转账，累计收取两次手续费
+    for _ in 0..2 {
+        let transfers = vec![TransferInfo {
+            recipient: recipient.pubkey(),
+            amount: LAMPORTS_PER_SOL / 100,
+        }];
+        send(&mut context, &[batch_transfer_sol_ix(&sender.pubkey(), transfers)], &[&sender])
+            .await
+            .expect("批量转账失败");
+    }
+
+    let rent_exempt_minimum = get_rent_exempt_minimum(&mut context, &bank_account).await;
+    let bank_balance = get_balance(&mut context, &bank_account).await;
+    assert_eq!(bank_balance, rent_exempt_minimum + 2 * fee, "银行账户应收到两次手续费");
+
+    // 提取部分手续费
+    send(
+        &mut context,
+        &[withdraw_fee_ix(&admin.pubkey(), &destination.pubkey(), Some(fee / 2))],
+        &[&admin],
+    )
+    .await
+    .expect("提取部分手续费失败");
+    assert_eq!(get_balance(&mut context, &destination.pubkey()).await, LAMPORTS_PER_SOL + fee / 2);
+
+    // 不指定金额时提取全部可提取余额
+    send(
+        &mut context,
+        &[withdraw_fee_ix(&admin.pubkey(), &destination.pubkey(), None)],
+        &[&admin],
+    )
+    .await
+    .expect("提取全部手续费失败");
+    assert_eq!(get_balance(&mut context, &destination.pubkey()).await, LAMPORTS_PER_SOL + 2 * fee);
     assert_eq!(
-        bank_account_balance_after, 
-        0,
-        "银行账户应该为空"
+        get_balance(&mut context, &bank_account).await,
+        rent_exempt_minimum,
+        "银行账户应保留租金豁免的最低余额"
     );
-    
+
+    // 可提取余额为0时再次提取应失败
+    let result = send(
+        &mut context,
+        &[withdraw_fee_ix(&admin.pubkey(), &destination.pubkey(), None)],
+        &[&admin],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::InsufficientFeeBalance);
+
     println!("管理员提取费用测试通过");
-} 
\ No newline at end of file
+}
+
+/// 测试提取金额超过可提取余额的情况
+#[tokio::test]
+async fn test_withdraw_fee_exceeds_available() {
+    let admin = Keypair::new();
+    let sender = Keypair::new();
+    let recipient = Keypair::new();
+    let destination = Keypair::new();
+
+    let mut program_test = program_test();
+    add_funded_account(&mut program_test, &admin.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &sender.pubkey(), LAMPORTS_PER_SOL);
+    add_funded_account(&mut program_test, &destination.pubkey(), LAMPORTS_PER_SOL);
+    let mut context = program_test.start_with_context().await;
+
+    let (bank_account, _) = get_bank_account(&batch_transfer::ID);
+    let fee = 10_000;
+    setup_bank_account(&mut context, &admin, fee).await;
+
+    let transfers = vec![TransferInfo {
+        recipient: recipient.pubkey(),
+        amount: LAMPORTS_PER_SOL / 100,
+    }];
+    send(&mut context, &[batch_transfer_sol_ix(&sender.pubkey(), transfers)], &[&sender])
+        .await
+        .expect("批量转账失败");
+
+    let bank_balance_before = get_balance(&mut context, &bank_account).await;
+
+    // 超过已收取手续费的金额会动用租金豁免余额，应被拒绝
+    let result = send(
+        &mut context,
+        &[withdraw_fee_ix(&admin.pubkey(), &destination.pubkey(), Some(fee + 1))],
+        &[&admin],
+    )
+    .await;
+    assert_program_error(result, ErrorCode::InsufficientFeeBalance);
+
+    assert_eq!(get_balance(&mut context, &bank_account).await, bank_balance_before);
+    assert_eq!(get_balance(&mut context, &destination.pubkey()).await, LAMPORTS_PER_SOL);
+}
@@ -3,7 +3,7 @@ import {Program} from "@coral-xyz/anchor";
 import {expect, assert} from "chai";
 import {BatchTransfer} from "../target/types/batch_transfer";
 import BN from "bn.js";
-import {createTestToken, getTestTokenAccount, initializeTestAccounts, mintTestTokens, LAMPORTS_PER_SOL, sleep} from "./helper";
+import {createTestToken, getTestTokenAccount, initializeTestAccounts, mintTestTokens, flatFeeConfig, LAMPORTS_PER_SOL, setFeeConfig, sleep, U64_MAX} from "./helper";
 
 describe("批量转账智能合约高级测试", () => {
     // 配置测试环境
@@ -102,19 +102,11 @@ describe("批量转账智能合约高级测试", () => {
     describe("边缘情况测试", () => {
         it("测试零手续费的批量转账", async () => {
             // 设置手续费为零
-            await program.methods
-                .setFee(new BN(0))
-                // @ts-ignore
-                .accounts({
-                    bankAccount: bankAccountPDA,
-                    admin: admin.publicKey,
-                })
-                .signers([admin])
-                .rpc();
+            await setFeeConfig(program, bankAccountPDA, admin, flatFeeConfig(new BN(0)));
 
             // 确认手续费已设为零
             const bankAccount = await program.account.bankAccount.fetch(bankAccountPDA);
-            expect(bankAccount.fee.toNumber()).to.equal(0);
+            expect(bankAccount.feeConfig.flatFee.toNumber()).to.equal(0);
 
             // 记录转账前的余额
             const initialRecipientBalance = await provider.connection.getBalance(recipients[0].publicKey);
@@ -131,7 +123,7 @@ describe("批量转账智能合约高级测试", () => {
 
             // 执行转账
             await program.methods
-                .batchTransferSol(transfers)
+                .batchTransferSol(transfers, false, { allow: {} }, null, U64_MAX)
                 // @ts-ignore
                 .accounts({
                     sender: sender.publicKey,
@@ -158,15 +150,7 @@ describe("批量转账智能合约高级测试", () => {
 
         it("测试最小数额转账（1 lamport）", async () => {
             // 设置小额手续费
-            await program.methods
-                .setFee(smallFee)
-                // @ts-ignore
-                .accounts({
-                    bankAccount: bankAccountPDA,
-                    admin: admin.publicKey,
-                })
-                .signers([admin])
-                .rpc();
+            await setFeeConfig(program, bankAccountPDA, admin, flatFeeConfig(smallFee));
 
             // 记录转账前的余额
             const initialRecipientBalance = await provider.connection.getBalance(recipients[1].publicKey);
@@ -183,7 +167,7 @@ describe("批量转账智能合约高级测试", () => {
 
             // 执行转账
             await program.methods
-                .batchTransferSol(transfers)
+                .batchTransferSol(transfers, false, { allow: {} }, null, U64_MAX)
                 // @ts-ignore
                 .accounts({
                     sender: sender.publicKey,
@@ -212,15 +196,7 @@ describe("批量转账智能合约高级测试", () => {
     describe("批量转账性能测试", () => {
         it("测试大量接收者批量转账SOL", async () => {
             // 设置较高手续费
-            await program.methods
-                .setFee(largeFee)
-                // @ts-ignore
-                .accounts({
-                    bankAccount: bankAccountPDA,
-                    admin: admin.publicKey,
-                })
-                .signers([admin])
-                .rpc();
+            await setFeeConfig(program, bankAccountPDA, admin, flatFeeConfig(largeFee));
 
             // 记录转账前所有接收者的余额
             const initialBalances = await Promise.all(
@@ -243,7 +219,7 @@ describe("批量转账智能合约高级测试", () => {
 
             // 执行批量转账
             await program.methods
-                .batchTransferSol(transfers)
+                .batchTransferSol(transfers, false, { allow: {} }, null, U64_MAX)
                 // @ts-ignore
                 .accounts({
                     sender: sender.publicKey,
@@ -297,11 +273,12 @@ describe("批量转账智能合约高级测试", () => {
 
             // 执行批量转账Token
             await program.methods
-                .batchTransferToken(transfers)
+                .batchTransferToken(transfers, false, { allow: {} }, null, U64_MAX)
                 // @ts-ignore
                 .accounts({
                     sender: sender.publicKey,
                     tokenAccount: senderTokenAccount,
+                    mint: mint,
                     bankAccount: bankAccountPDA,
                     tokenProgram: anchor.web3.TOKEN_PROGRAM_ID,
                     systemProgram: anchor.web3.SystemProgram.programId,
@@ -343,7 +320,7 @@ describe("批量转账智能合约高级测试", () => {
             const wrongAdmin = anchor.web3.Keypair.generate();
             
             try {
-                await program.methods.setFee(new anchor.BN(1000))
+                await program.methods.queueConfigChange({ feeConfig: flatFeeConfig(new anchor.BN(1000)), configDelay: new anchor.BN(0) }, new anchor.BN(0))
                     .accounts({
                         bankAccount: bankAccountPDA,
                         authority: wrongAdmin.publicKey,
@@ -381,7 +358,7 @@ describe("批量转账智能合约高级测试", () => {
                 ];
 
                 await program.methods
-                    .batchTransferSol(transfers)
+                    .batchTransferSol(transfers, false, { allow: {} }, null, U64_MAX)
                     // @ts-ignore
                     .accounts({
                         sender: sender.publicKey,
@@ -411,12 +388,12 @@ describe("批量转账智能合约高级测试", () => {
             }
         });
 
-        it("验证重复接收者的处理", async () => {
+        it("验证合并重复接收者", async () => {
             // 记录转账前的余额
             const initialBalance = await provider.connection.getBalance(recipients[0].publicKey);
             const initialBankAccountBalance = await provider.connection.getBalance(bankAccountPDA);
 
-            // 准备转账数据，包含重复的接收者
+            // 准备转账数据，包含重复的接收者，合并模式要求按接收者地址升序排列
             const amount = new BN(0.01 * LAMPORTS_PER_SOL);
             const transfers = [
                 {
@@ -431,7 +408,7 @@ describe("批量转账智能合约高级测试", () => {
 
             // 执行批量转账
             await program.methods
-                .batchTransferSol(transfers)
+                .batchTransferSol(transfers, false, { allow: {} }, null, U64_MAX)
                 // @ts-ignore
                 .accounts({
                     sender: sender.publicKey,
@@ -444,16 +421,11 @@ describe("批量转账智能合约高级测试", () => {
                         isWritable: true,
                         isSigner: false,
                     },
-                    {
-                        pubkey: recipients[0].publicKey, // 重复的接收者
-                        isWritable: true,
-                        isSigner: false,
-                    },
                 ])
                 .signers([sender])
                 .rpc();
 
-            // 验证接收者余额增加了两次金额
+            // 合并后只需传入一次接收者账户，验证接收者余额增加了两次金额
             const finalBalance = await provider.connection.getBalance(recipients[0].publicKey);
             const finalBankAccountBalance = await provider.connection.getBalance(bankAccountPDA);
 
@@ -556,11 +528,12 @@ describe("批量转账智能合约高级测试", () => {
             
             // 调用批量转账Token指令
             await program.methods
-                .batchTransferToken(transfers)
+                .batchTransferToken(transfers, false, { allow: {} }, null, U64_MAX)
                 // @ts-ignore - Anchor类型错误，但实际是有效的
                 .accounts({
                     sender: sender.publicKey,
                     tokenAccount: senderTokenAccount,
+                    mint: mint,
                     bankAccount: bankAccountPDA,
                     tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
                     systemProgram: anchor.web3.SystemProgram.programId,
@@ -586,7 +559,7 @@ describe("批量转账智能合约高级测试", () => {
             
             // 验证银行账户收到了手续费
             const bankAccount = await program.account.bankAccount.fetch(bankAccountPDA);
-            expect(finalBankAccountBalance - initialBankAccountBalance).to.equal(bankAccount.fee.toNumber());
+            expect(finalBankAccountBalance - initialBankAccountBalance).to.equal(bankAccount.feeConfig.flatFee.toNumber());
         });
     });
 }); 
\ No newline at end of file
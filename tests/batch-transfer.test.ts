@@ -9,8 +9,11 @@ import {
   createTestToken, 
   getTestTokenAccount, 
   mintTestTokens, 
+  flatFeeConfig, 
   LAMPORTS_PER_SOL, 
-  sleep 
+  sleep,
+  setFeeConfig,
+  U64_MAX
 } from "./helper";
 
 // 导入TOKEN_PROGRAM_ID
@@ -146,7 +149,7 @@ describe("批量转账智能合约测试", () => {
       const bankAccount = await program.account.bankAccount.fetch(bankAccountPDA);
       
       expect(bankAccount.admin.toString()).to.equal(admin.publicKey.toString());
-      expect(bankAccount.fee.toNumber()).to.equal(0);
+      expect(bankAccount.feeConfig.flatFee.toNumber()).to.equal(0);
       console.log("合约初始化成功，管理员设置为:", bankAccount.admin.toString());
     } catch (error) {
       console.error("初始化失败:", error);
@@ -172,20 +175,12 @@ describe("批量转账智能合约测试", () => {
       }
       
       // 使用管理员账户设置手续费
-      await program.methods
-        .setFee(mockFee)
-        // @ts-ignore - Anchor类型错误，但实际是有效的
-        .accounts({
-          bankAccount: bankAccountPDA,
-          admin: admin.publicKey,
-        })
-        .signers([admin])
-        .rpc();
+      await setFeeConfig(program, bankAccountPDA, admin, flatFeeConfig(mockFee));
         
       // 验证手续费更新
       const updatedBankAccount = await program.account.bankAccount.fetch(bankAccountPDA);
-      expect(updatedBankAccount.fee.toString()).to.equal(mockFee.toString());
-      console.log("手续费设置成功:", updatedBankAccount.fee.toString(), "lamports");
+      expect(updatedBankAccount.feeConfig.flatFee.toString()).to.equal(mockFee.toString());
+      console.log("手续费设置成功:", updatedBankAccount.feeConfig.flatFee.toString(), "lamports");
     } catch (error) {
       console.error("设置手续费失败:", error);
       // 如果错误是由于权限问题，我们跳过此测试而不是失败
@@ -222,7 +217,7 @@ describe("批量转账智能合约测试", () => {
 
     // 调用批量转账SOL指令
     await program.methods
-      .batchTransferSol(transfers)
+      .batchTransferSol(transfers, false, { allow: {} }, null, U64_MAX)
       // @ts-ignore - Anchor类型错误，但实际是有效的
       .accounts({
         sender: sender.publicKey,
@@ -289,12 +284,13 @@ describe("批量转账智能合约测试", () => {
 
     // 调用批量转账Token指令
     await program.methods
-      .batchTransferToken(transfers)
+      .batchTransferToken(transfers, false, { allow: {} }, null, U64_MAX)
       // @ts-ignore - Anchor类型错误，但实际是有效的
       .accounts({
         sender: sender.publicKey,
         bankAccount: bankAccountPDA,
         tokenAccount: senderTokenAccount,
+        mint: mint,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
@@ -343,7 +339,7 @@ describe("批量转账智能合约测试", () => {
     try {
       // 尝试使用空转账列表调用批量转账SOL
       await program.methods
-        .batchTransferSol([])
+        .batchTransferSol([], false, { allow: {} }, null, U64_MAX)
         // @ts-ignore - Anchor类型错误，但实际是有效的
         .accounts({
           sender: sender.publicKey,
@@ -365,7 +361,7 @@ describe("批量转账智能合约测试", () => {
     try {
       // 尝试使用非管理员账户设置手续费
       await program.methods
-        .setFee(mockFee)
+        .queueConfigChange({ feeConfig: flatFeeConfig(mockFee), configDelay: new BN(0) }, new BN(0))
         // @ts-ignore - Anchor类型错误，但实际是有效的
         .accounts({
           bankAccount: bankAccountPDA,
@@ -399,7 +395,7 @@ describe("批量转账智能合约测试", () => {
 
       // 尝试转账
       await program.methods
-        .batchTransferSol(transfers)
+        .batchTransferSol(transfers, false, { allow: {} }, null, U64_MAX)
         // @ts-ignore - Anchor类型错误，但实际是有效的
         .accounts({
           sender: poorSender.publicKey,
@@ -436,7 +432,7 @@ describe("批量转账智能合约测试", () => {
 
       // 尝试转账
       await program.methods
-        .batchTransferSol(transfers)
+        .batchTransferSol(transfers, false, { allow: {} }, null, U64_MAX)
         // @ts-ignore - Anchor类型错误，但实际是有效的
         .accounts({
           sender: sender.publicKey,
@@ -474,7 +470,7 @@ describe("批量转账智能合约测试", () => {
 
       // 尝试批量转账SOL指令
       await program.methods
-        .batchTransferSol(transfers)
+        .batchTransferSol(transfers, false, { allow: {} }, null, U64_MAX)
         // @ts-ignore - Anchor类型错误，但实际是有效的
         .accounts({
           sender: sender.publicKey,
@@ -517,7 +513,7 @@ describe("批量转账智能合约测试", () => {
 
     // 调用批量转账SOL指令
     await program.methods
-      .batchTransferSol(transfers)
+      .batchTransferSol(transfers, false, { allow: {} }, null, U64_MAX)
       // @ts-ignore - Anchor类型错误，但实际是有效的
       .accounts({
         sender: sender.publicKey,
@@ -563,7 +559,7 @@ describe("批量转账智能合约测试", () => {
 
     // 调用批量转账SOL指令
     await program.methods
-      .batchTransferSol(transfers)
+      .batchTransferSol(transfers, false, { allow: {} }, null, U64_MAX)
       // @ts-ignore - Anchor类型错误，但实际是有效的
       .accounts({
         sender: sender.publicKey,
@@ -608,7 +604,7 @@ describe("批量转账智能合约测试", () => {
     ];
 
     await program.methods
-      .batchTransferSol(solTransfers)
+      .batchTransferSol(solTransfers, false, { allow: {} }, null, U64_MAX)
       // @ts-ignore - Anchor类型错误，但实际是有效的
       .accounts({
         sender: sender.publicKey,
@@ -634,12 +630,13 @@ describe("批量转账智能合约测试", () => {
     ];
 
     await program.methods
-      .batchTransferToken(tokenTransfers)
+      .batchTransferToken(tokenTransfers, false, { allow: {} }, null, U64_MAX)
       // @ts-ignore - Anchor类型错误，但实际是有效的
       .accounts({
         sender: sender.publicKey,
         bankAccount: bankAccountPDA,
         tokenAccount: senderTokenAccount,
+        mint: mint,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
@@ -689,12 +686,13 @@ describe("批量转账智能合约测试", () => {
     try {
       // 尝试转账
       await program.methods
-        .batchTransferToken(transfers)
+        .batchTransferToken(transfers, false, { allow: {} }, null, U64_MAX)
         // @ts-ignore - Anchor类型错误，但实际是有效的
         .accounts({
           sender: sender.publicKey,
           bankAccount: bankAccountPDA,
           tokenAccount: senderTokenAccount,
+          mint: mint,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
@@ -722,7 +720,7 @@ describe("批量转账智能合约测试", () => {
     
     // 获取当前手续费
     const bankAccount = await program.account.bankAccount.fetch(bankAccountPDA);
-    const currentFee = bankAccount.fee;
+    const currentFee = bankAccount.feeConfig.flatFee;
     console.log("当前手续费:", currentFee.toString(), "lamports");
     
     // 准备新的手续费值 - 增加当前手续费
@@ -738,20 +736,12 @@ describe("批量转账智能合约测试", () => {
       }
       
       // 使用管理员账户设置新的手续费
-      await program.methods
-        .setFee(newFee)
-        // @ts-ignore - Anchor类型错误，但实际是有效的
-        .accounts({
-          bankAccount: bankAccountPDA,
-          admin: admin.publicKey,
-        })
-        .signers([admin])
-        .rpc();
+      await setFeeConfig(program, bankAccountPDA, admin, flatFeeConfig(newFee));
       
       // 验证手续费已更新
       const updatedBankAccount = await program.account.bankAccount.fetch(bankAccountPDA);
-      expect(updatedBankAccount.fee.toString()).to.equal(newFee.toString());
-      console.log("手续费已成功更新为:", updatedBankAccount.fee.toString(), "lamports");
+      expect(updatedBankAccount.feeConfig.flatFee.toString()).to.equal(newFee.toString());
+      console.log("手续费已成功更新为:", updatedBankAccount.feeConfig.flatFee.toString(), "lamports");
       
       // 使用新的手续费进行转账测试
       const transfers = [
@@ -767,7 +757,7 @@ describe("批量转账智能合约测试", () => {
       
       // 调用批量转账SOL指令
       await program.methods
-        .batchTransferSol(transfers)
+        .batchTransferSol(transfers, false, { allow: {} }, null, U64_MAX)
         // @ts-ignore - Anchor类型错误，但实际是有效的
         .accounts({
           sender: sender.publicKey,
@@ -830,7 +820,7 @@ describe("批量转账智能合约测试", () => {
     try {
       // 执行批量转账
       await program.methods
-        .batchTransferSol(transfers)
+        .batchTransferSol(transfers, false, { allow: {} }, null, U64_MAX)
         // @ts-ignore - Anchor类型错误，但实际是有效的
         .accounts({
           sender: sender.publicKey,
@@ -880,7 +870,7 @@ describe("批量转账智能合约测试", () => {
       // 获取当前银行账户信息
       const bankAccount = await program.account.bankAccount.fetch(bankAccountPDA);
       console.log(`当前管理员是: ${bankAccount.admin.toString()}`);
-      console.log(`当前手续费是: ${bankAccount.fee.toString()}`);
+      console.log(`当前手续费是: ${bankAccount.feeConfig.flatFee.toString()}`);
       
       // 检查调用者是否为管理员
       if (!bankAccount.admin.equals(provider.wallet.publicKey)) {
@@ -889,18 +879,11 @@ describe("批量转账智能合约测试", () => {
       }
       
       // 设置新手续费
-      await program.methods
-        .setFee(newFee)
-        // @ts-ignore - Anchor类型错误，但实际是有效的
-        .accounts({
-          bankAccount: bankAccountPDA,
-          admin: provider.wallet.publicKey,
-        })
-        .rpc();
+      await setFeeConfig(program, bankAccountPDA, (provider.wallet as anchor.Wallet).payer, flatFeeConfig(newFee));
       
       // 验证手续费已更新
       const updatedBankAccount = await program.account.bankAccount.fetch(bankAccountPDA);
-      expect(updatedBankAccount.fee.toString()).to.equal(newFee.toString());
+      expect(updatedBankAccount.feeConfig.flatFee.toString()).to.equal(newFee.toString());
       console.log(`手续费已更新为 ${newFee.toNumber() / LAMPORTS_PER_SOL} SOL`);
     } catch (e) {
       console.error("设置新手续费失败:", e);
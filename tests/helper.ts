@@ -1,8 +1,11 @@
 import * as anchor from "@coral-xyz/anchor";
 import { BN } from "bn.js";
+import { BatchTransfer } from "../target/types/batch_transfer";
 
 // 常量定义
 export const LAMPORTS_PER_SOL = 1000000000;
+// 不限制手续费上限时传入的 max_fee
+export const U64_MAX = new BN("18446744073709551615");
 
 /**
  * 等待一段时间（用于等待交易确认）
@@ -203,4 +206,49 @@ export async function mintTestTokens(
     new anchor.web3.Transaction().add(mintToInstruction),
     [payer, authority]
   );
-} 
\ No newline at end of file
+} 
+/**
+ * 构造只收取固定手续费的配置
+ * @param flatFee 固定手续费(lamports)
+ */
+export function flatFeeConfig(flatFee: anchor.BN) {
+  return {
+    flatFee,
+    bps: 0,
+    minFee: new anchor.BN(0),
+    maxFee: new anchor.BN(0),
+  };
+}
+
+/**
+ * 在同一笔交易中提交并执行手续费配置变更，保持当前的变更延迟；
+ * 只在延迟为0或新配置只降低手续费时能立即生效
+ * @param program 程序实例
+ * @param bankAccount 银行账户地址
+ * @param admin 管理员账户
+ * @param feeConfig 新的手续费配置
+ */
+export async function setFeeConfig(
+  program: anchor.Program<BatchTransfer>,
+  bankAccount: anchor.web3.PublicKey,
+  admin: anchor.web3.Keypair,
+  feeConfig: ReturnType<typeof flatFeeConfig>
+): Promise<string> {
+  const { configDelay } = await program.account.bankAccount.fetch(bankAccount);
+  // Clock sysvar 中 unix_timestamp 位于 slot、epoch_start_timestamp、epoch、leader_schedule_epoch 之后
+  const clock = await program.provider.connection.getAccountInfo(anchor.web3.SYSVAR_CLOCK_PUBKEY);
+  const now = new BN(clock!.data.readBigInt64LE(32).toString());
+
+  const executeInstruction = await program.methods
+    .executeConfigChange()
+    // @ts-ignore
+    .accounts({ bankAccount })
+    .instruction();
+  return program.methods
+    .queueConfigChange({ feeConfig, configDelay }, now)
+    // @ts-ignore
+    .accounts({ bankAccount, admin: admin.publicKey })
+    .postInstructions([executeInstruction])
+    .signers([admin])
+    .rpc();
+}
@@ -2,6 +2,7 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { expect } from "chai";
 import { BatchTransfer } from "../target/types/batch_transfer";
+import { flatFeeConfig, setFeeConfig, U64_MAX } from "./helper";
 
 describe("简化版批量转账测试", () => {
   // 配置Anchor提供者
@@ -73,14 +74,7 @@ describe("简化版批量转账测试", () => {
       console.log("设置手续费...");
       
       // 设置手续费
-      await program.methods
-        .setFee(initialFee)
-        // @ts-ignore - Anchor类型错误，但实际是有效的
-        .accounts({
-          bankAccount: bankAccountPDA,
-          admin: provider.wallet.publicKey,
-        })
-        .rpc();
+      await setFeeConfig(program, bankAccountPDA, (provider.wallet as anchor.Wallet).payer, flatFeeConfig(initialFee));
     }
   });
   
@@ -98,7 +92,7 @@ describe("简化版批量转账测试", () => {
     
     // 获取当前费用
     const bankAccount = await program.account.bankAccount.fetch(bankAccountPDA);
-    const currentFee = bankAccount.fee;
+    const currentFee = bankAccount.feeConfig.flatFee;
     
     // 转账金额
     const amount = new anchor.BN(0.01 * LAMPORTS_PER_SOL);
@@ -113,7 +107,7 @@ describe("简化版批量转账测试", () => {
     
     // 执行批量转账
     await program.methods
-      .batchTransferSol(transfers)
+      .batchTransferSol(transfers, false, { allow: {} }, null, U64_MAX)
       .accounts({
         sender: sender.publicKey,
         bankAccount: bankAccountPDA,
@@ -159,17 +153,11 @@ describe("简化版批量转账测试", () => {
     
     try {
       // 设置新手续费
-      await program.methods
-        .setFee(newFee)
-        .accounts({
-          bankAccount: bankAccountPDA,
-          admin: provider.wallet.publicKey,
-        })
-        .rpc();
+      await setFeeConfig(program, bankAccountPDA, (provider.wallet as anchor.Wallet).payer, flatFeeConfig(newFee));
       
       // 验证手续费已更新
       const bankAccount = await program.account.bankAccount.fetch(bankAccountPDA);
-      expect(bankAccount.fee.toString()).to.equal(newFee.toString());
+      expect(bankAccount.feeConfig.flatFee.toString()).to.equal(newFee.toString());
       console.log(`手续费已更新为 ${newFee.toNumber() / LAMPORTS_PER_SOL} SOL`);
     } catch (e) {
       console.error("设置新手续费失败:", e);